@@ -0,0 +1,95 @@
+//! Benchmarks of the building and topological sorting performance of the `DependencyGraph`, with
+//! `usize` and `u32` keys.
+
+mod common;
+
+use enso_data::dependency_graph::DependencyGraph;
+
+use common::bench_config;
+use common::SIZES;
+use criterion::black_box;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use std::hash::Hash;
+
+
+
+// =================
+// === Utilities ===
+// =================
+
+/// A graph where each node depends on the next one in `nodes`.
+fn chain<T:Copy+Eq+Hash+Ord>(nodes:&[T]) -> DependencyGraph<T> {
+    let mut graph = DependencyGraph::new();
+    for (first,second) in nodes.iter().zip(nodes.iter().skip(1)) {
+        graph.insert_dependency(*first,*second);
+    }
+    graph
+}
+
+
+
+// ==================
+// === Benchmarks ===
+// ==================
+
+fn bench_topo_sort<T>(c:&mut Criterion, name:&str, nodes:fn(usize) -> Vec<T>)
+where T:Copy+Eq+Hash+Ord {
+    let mut group = c.benchmark_group(name);
+    for &size in SIZES {
+        let nodes = nodes(size);
+        let graph = chain(&nodes);
+        group.bench_with_input(BenchmarkId::from_parameter(size),&nodes,|b,nodes| {
+            b.iter(|| graph.topo_sort(black_box(nodes)))
+        });
+    }
+    group.finish();
+}
+
+fn topo_sort_ascending(c:&mut Criterion) {
+    bench_topo_sort(c,"Topo Sort Ascending",|size| (0..size).collect())
+}
+
+fn topo_sort_descending(c:&mut Criterion) {
+    bench_topo_sort(c,"Topo Sort Descending",|size| (0..size).rev().collect())
+}
+
+fn topo_sort_ascending_u32(c:&mut Criterion) {
+    bench_topo_sort(c,"Topo Sort Ascending u32",|size| (0..size as u32).collect())
+}
+
+fn bench_build<T>(c:&mut Criterion, name:&str, nodes:fn(usize) -> Vec<T>)
+where T:Copy+Eq+Hash+Ord {
+    let mut group = c.benchmark_group(name);
+    for &size in SIZES {
+        let nodes = nodes(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size),&nodes,|b,nodes| {
+            b.iter(|| chain(black_box(nodes)))
+        });
+    }
+    group.finish();
+}
+
+fn build(c:&mut Criterion) {
+    bench_build(c,"Build",|size| (0..size).collect())
+}
+
+fn build_u32(c:&mut Criterion) {
+    bench_build(c,"Build u32",|size| (0..size as u32).collect())
+}
+
+criterion_group! {
+    name    = dependency_graph_benchmarks;
+    config  = bench_config();
+    targets = topo_sort_ascending,topo_sort_descending,topo_sort_ascending_u32,build,build_u32
+}
+
+
+
+// ==============
+// === Runner ===
+// ==============
+
+criterion_main!(dependency_graph_benchmarks);
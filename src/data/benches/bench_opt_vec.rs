@@ -0,0 +1,74 @@
+//! Benchmarks of the `OptVec` performance under insertions and removals reusing the free indexes.
+
+mod common;
+
+use enso_data::opt_vec::OptVec;
+
+use common::bench_config;
+use common::SIZES;
+use criterion::black_box;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+
+
+// ==================
+// === Benchmarks ===
+// ==================
+
+fn insert(c:&mut Criterion) {
+    let mut group = c.benchmark_group("OptVec Insert");
+    for &size in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(size),&size,|b,&size| b.iter(|| {
+            let mut vec = OptVec::<usize>::new();
+            for i in 0..size { vec.insert(black_box(i)); }
+            vec
+        }));
+    }
+    group.finish();
+}
+
+/// Removes every other element and inserts new ones in their place, several times in a row.
+fn churn(c:&mut Criterion) {
+    let mut group = c.benchmark_group("OptVec Churn");
+    for &size in SIZES {
+        let mut vec = OptVec::<usize>::new();
+        for i in 0..size { vec.insert(i); }
+        group.bench_with_input(BenchmarkId::from_parameter(size),&size,|b,&size| b.iter(|| {
+            for round in 0..4 {
+                for i in (round % 2..size).step_by(2) { vec.remove(black_box(i)); }
+                for i in (round % 2..size).step_by(2) { vec.insert(black_box(i)); }
+            }
+        }));
+    }
+    group.finish();
+}
+
+fn iterate(c:&mut Criterion) {
+    let mut group = c.benchmark_group("OptVec Iterate Sparse");
+    for &size in SIZES {
+        let mut vec = OptVec::<usize>::new();
+        for i in 0..size { vec.insert(i); }
+        for i in (0..size).step_by(3) { vec.remove(i); }
+        group.bench_with_input(BenchmarkId::from_parameter(size),&vec,|b,vec| {
+            b.iter(|| vec.iter().sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name    = opt_vec_benchmarks;
+    config  = bench_config();
+    targets = insert,churn,iterate
+}
+
+
+
+// ==============
+// === Runner ===
+// ==============
+
+criterion_main!(opt_vec_benchmarks);
@@ -1,5 +1,7 @@
 //! This file contains benchmarks of the query performance for the HashTree structure.
 
+mod common;
+
 use enso_data::hash_map_tree::HashMapTree;
 use itertools::*;
 
@@ -15,12 +17,10 @@ use std::time::Duration;
 // === Utilities ===
 // =================
 
-/// The base configuration for the benchmarks.
+/// The base configuration for the benchmarks. Tree queries are measured for longer than usual.
 fn bench_config() -> Criterion {
-    Criterion::default()
+    common::bench_config()
         .measurement_time(Duration::from_secs(60))
-        .warm_up_time(Duration::from_secs(3))
-        .sample_size(25)
         .retain_baseline("Tree".to_string())
 }
 
@@ -0,0 +1,24 @@
+//! Utilities shared by the benchmarks. Every benchmark includes this module with `mod common;`,
+//! and not all of them use every utility.
+
+#![allow(dead_code)]
+
+use criterion::Criterion;
+use std::time::Duration;
+
+
+
+// =================
+// === Utilities ===
+// =================
+
+/// The base configuration for the benchmarks.
+pub fn bench_config() -> Criterion {
+    Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .warm_up_time(Duration::from_secs(3))
+        .sample_size(25)
+}
+
+/// Sizes of the benchmarked inputs.
+pub const SIZES : &[usize] = &[1_000,10_000,100_000];
@@ -0,0 +1,105 @@
+//! Fuzzing of insertion and removal sequences of the data structures. Run it with
+//! `cargo fuzz run edit_sequences` in the `src/data` directory.
+
+#![no_main]
+
+use enso_data::dependency_graph::DependencyGraph;
+use enso_data::diet;
+use enso_data::hash_map_tree::HashMapTree;
+use enso_data::opt_vec::OptVec;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+
+
+// ============
+// === Edit ===
+// ============
+
+/// A single step of an edit sequence, applied to all the structures.
+#[derive(Arbitrary,Debug)]
+enum Edit {
+    Insert     {value:u8},
+    Remove     {index:u8},
+    SetPath    {path:Vec<u8>, value:u8},
+    RemovePath {path:Vec<u8>},
+    AddEdge    {first:u8, second:u8},
+    RemoveEdge {first:u8, second:u8},
+}
+
+/// Structures under test, together with simple reference implementations.
+#[derive(Default)]
+struct State {
+    diet        : diet::Tree16,
+    diet_model  : BTreeSet<usize>,
+    vec         : OptVec<u8>,
+    vec_model   : HashMap<usize,u8>,
+    vec_slots   : usize,
+    tree        : HashMapTree<u8,Option<u8>>,
+    graph       : DependencyGraph<u8>,
+    graph_nodes : BTreeSet<u8>,
+}
+
+impl State {
+    fn apply(&mut self, edit:Edit) {
+        match edit {
+            Edit::Insert {value} => {
+                self.diet.insert(value as usize);
+                self.diet_model.insert(value as usize);
+                let index = self.vec.insert(value);
+                assert!(self.vec_model.insert(index,value).is_none());
+                self.vec_slots = self.vec_slots.max(index + 1);
+            }
+            Edit::Remove {index} => {
+                let index = index as usize;
+                if index < self.vec_slots {
+                    assert_eq!(self.vec.remove(index),self.vec_model.remove(&index));
+                }
+            }
+            Edit::SetPath {path,value} => {
+                self.tree.set(path.clone(),Some(value));
+                assert_eq!(self.tree.get(path),Some(&Some(value)));
+            }
+            Edit::RemovePath {path} => {
+                if !path.is_empty() {
+                    self.tree.remove(path.clone());
+                    assert_eq!(self.tree.get(path),None);
+                }
+            }
+            Edit::AddEdge {first,second} => {
+                self.graph.insert_dependency(first,second);
+                self.graph_nodes.extend(&[first,second]);
+            }
+            Edit::RemoveEdge {first,second} => {
+                self.graph.remove_dependency(first,second);
+            }
+        }
+    }
+
+    fn check(&self) {
+        let covered = self.diet.to_vec().into_iter().flat_map(|t| t.start..=t.end);
+        assert_eq!(covered.collect::<BTreeSet<_>>(),self.diet_model);
+        assert_eq!(self.vec.len(),self.vec_model.len());
+        let nodes      = self.graph_nodes.iter().copied().collect::<Vec<_>>();
+        let mut sorted = self.graph.topo_sort(&nodes);
+        sorted.sort_unstable();
+        assert_eq!(sorted,nodes);
+    }
+}
+
+
+
+// ===================
+// === Fuzz Target ===
+// ===================
+
+fuzz_target!(|edits:Vec<Edit>| {
+    let mut state = State::default();
+    for edit in edits {
+        state.apply(edit);
+    }
+    state.check();
+});
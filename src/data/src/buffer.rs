@@ -0,0 +1,194 @@
+//! A vector tracking which of its elements were modified, so that only the changed parts of it
+//! have to be uploaded to an external storage, like a GPU buffer.
+
+use crate::prelude::*;
+
+use crate::interval::RightOpenInterval;
+use crate::interval_tree::IntervalTree;
+
+use std::iter::FromIterator;
+
+
+
+// ==============
+// === Buffer ===
+// ==============
+
+/// A vector remembering the indexes of all elements modified since the last call to
+/// `drain_dirty_slices`. All writes go through the API, so no modification can be missed. The
+/// dirty indexes are stored in an `IntervalTree`, so neighbor modifications are merged into a
+/// single contiguous slice.
+#[derive(Clone,Debug,Default)]
+pub struct Buffer<T> {
+    vec   : Vec<T>,
+    dirty : IntervalTree,
+}
+
+impl<T> Buffer<T> {
+    /// Constructor of an empty buffer.
+    pub fn new() -> Self {
+        let vec   = default();
+        let dirty = default();
+        Self {vec,dirty}
+    }
+
+    /// Number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Check whether the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Check whether any element was modified since the last drain.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Slice of all the elements.
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
+    }
+
+    /// Reference to the element at the index.
+    pub fn get(&self, index:usize) -> Option<&T> {
+        self.vec.get(index)
+    }
+
+    /// Mutable reference to the element at the index. The element is marked as dirty, even if it is
+    /// not modified through the reference.
+    pub fn get_mut(&mut self, index:usize) -> Option<&mut T> {
+        let value = self.vec.get_mut(index)?;
+        self.dirty.insert(index);
+        Some(value)
+    }
+
+    /// Set the element at the index and mark it as dirty.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn set(&mut self, index:usize, value:T) {
+        self.vec[index] = value;
+        self.dirty.insert(index);
+    }
+
+    /// Append the element and mark it as dirty. Returns its index.
+    pub fn push(&mut self, value:T) -> usize {
+        let index = self.vec.len();
+        self.vec.push(value);
+        self.dirty.insert(index);
+        index
+    }
+
+    /// Remove and return the last element. Removed elements are never reported as dirty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Shorten the buffer to `len` elements. Removed elements are never reported as dirty.
+    pub fn truncate(&mut self, len:usize) {
+        self.vec.truncate(len)
+    }
+
+    /// Mutable slice of the elements in the range. All of them are marked as dirty.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    pub fn slice_mut(&mut self, range:Range<usize>) -> &mut [T] {
+        let slice = &mut self.vec[range.clone()];
+        self.dirty.extend(range);
+        slice
+    }
+
+    /// Mark all the elements as dirty, for example after the external storage was lost.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.extend(0..self.vec.len());
+    }
+
+    /// Forget the dirty elements, returning the minimal set of contiguous slices covering all the
+    /// elements modified since the last drain, along with their start indexes, in ascending order.
+    pub fn drain_dirty_slices(&mut self) -> Vec<(usize,&[T])> {
+        let bounds    = RightOpenInterval(0,self.vec.len());
+        let intervals = mem::take(&mut self.dirty).to_vec();
+        let intervals = intervals.into_iter().map(RightOpenInterval::from);
+        let vec       = &self.vec;
+        intervals.filter_map(|t| t.clamp(bounds)).map(|t| (t.start,&vec[Range::from(t)])).collect()
+    }
+}
+
+impl<T:Clone> Buffer<T> {
+    /// Copy the values to the buffer, starting at the index, and mark them as dirty.
+    ///
+    /// # Panics
+    /// Panics if the values do not fit in the buffer.
+    pub fn write(&mut self, index:usize, values:&[T]) {
+        self.slice_mut(index..index + values.len()).clone_from_slice(values)
+    }
+}
+
+impl<T> From<Vec<T>> for Buffer<T> {
+    /// All the elements of the created buffer are dirty.
+    fn from(vec:Vec<T>) -> Self {
+        let dirty = (0..vec.len()).collect();
+        Self {vec,dirty}
+    }
+}
+
+impl<T> FromIterator<T> for Buffer<T> {
+    fn from_iter<I:IntoIterator<Item=T>>(iter:I) -> Self {
+        iter.into_iter().collect_vec().into()
+    }
+}
+
+impl<T> Extend<T> for Buffer<T> {
+    fn extend<I:IntoIterator<Item=T>>(&mut self, iter:I) {
+        for value in iter { self.push(value); }
+    }
+}
+
+impl<T> Deref for Buffer<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dirty_slices_are_merged() {
+        let mut buffer : Buffer<_> = (0..10).collect();
+        assert_eq!(buffer.drain_dirty_slices(),vec![(0,&[0,1,2,3,4,5,6,7,8,9][..])]);
+        assert!(!buffer.is_dirty());
+        buffer.set(2,20);
+        buffer.write(3,&[30,40]);
+        *buffer.get_mut(9).unwrap() = 90;
+        buffer.push(10);
+        assert_eq!(buffer.drain_dirty_slices(),vec![(2,&[20,30,40][..]),(9,&[90,10][..])]);
+        assert!(buffer.drain_dirty_slices().is_empty());
+    }
+
+    #[test]
+    fn removed_elements_are_not_dirty() {
+        let mut buffer : Buffer<_> = vec![0,1,2,3].into();
+        buffer.drain_dirty_slices();
+        buffer.set(1,10);
+        buffer.set(3,30);
+        buffer.truncate(2);
+        assert_eq!(buffer.drain_dirty_slices(),vec![(1,&[10][..])]);
+        buffer.slice_mut(0..2)[0] = 5;
+        buffer.pop();
+        buffer.pop();
+        assert!(buffer.drain_dirty_slices().is_empty());
+    }
+}
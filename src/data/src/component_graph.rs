@@ -0,0 +1,140 @@
+//! A graph of components, storing the component payloads in an `OptVec` and the dependencies
+//! between them in a `DependencyGraph` keyed by the `OptVec` indexes.
+
+use crate::prelude::*;
+
+use crate::dependency_graph::DependencyGraph;
+use crate::opt_vec;
+use crate::opt_vec::OptVec;
+
+
+
+// ======================
+// === ComponentGraph ===
+// ======================
+
+/// Components with dependencies between them. Insertion and removal keep both the storage and the
+/// dependencies in sync. Most importantly, the dependencies of a removed component are removed
+/// before its index can be reused, so a new component never inherits them.
+#[derive(Clone,Debug,Derivative)]
+#[derivative(Default(bound=""))]
+pub struct ComponentGraph<T,Index=usize> {
+    components   : OptVec<T,Index>,
+    dependencies : DependencyGraph<usize>,
+}
+
+impl<T,I:opt_vec::Index> ComponentGraph<T,I> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Number of components in the graph.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Check whether the graph contains no components.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Insert a new component without any dependencies. Returns its index.
+    pub fn insert(&mut self, component:T) -> I {
+        self.components.insert(component)
+    }
+
+    /// Remove the component and all of its dependencies. Returns `None` if the index was already
+    /// empty. Panics if the index was out of bounds.
+    pub fn remove(&mut self, index:I) -> Option<T> {
+        let component = self.components.remove(index)?;
+        self.dependencies.remove_node(&index.into());
+        Some(component)
+    }
+
+    /// Reference to the component, if it was not removed.
+    pub fn get(&self, index:I) -> Option<&T> {
+        self.components.safe_index(index)
+    }
+
+    /// Mutable reference to the component, if it was not removed.
+    pub fn get_mut(&mut self, index:I) -> Option<&mut T> {
+        self.components.safe_index_mut(index)
+    }
+
+    /// Iterator over all components.
+    pub fn iter(&self) -> opt_vec::Iter<T> {
+        self.components.iter()
+    }
+
+    /// Record that the `first` component has to be sorted before the `second` one. Returns
+    /// [`true`] if the dependency was inserted, or [`false`] if it was already present or one of
+    /// the components does not exist.
+    pub fn insert_dependency(&mut self, first:I, second:I) -> bool {
+        let exists = self.contains(first) && self.contains(second);
+        exists && self.dependencies.insert_dependency(first.into(),second.into())
+    }
+
+    /// Remove a dependency. Returns [`true`] if the dependency was found, or [`false`] otherwise.
+    pub fn remove_dependency(&mut self, first:I, second:I) -> bool {
+        self.dependencies.remove_dependency(first.into(),second.into())
+    }
+
+    /// Indexes of all components, sorted topologically. Cycles are broken on the lowest index. See
+    /// `DependencyGraph::topo_sort` to learn more.
+    pub fn topo_sort(&self) -> Vec<I> {
+        let keys = self.indexes().collect_vec();
+        self.dependencies.unchecked_topo_sort(keys).into_iter().map(|index| index.into()).collect()
+    }
+
+    /// Indexes of all components, in ascending order.
+    fn indexes(&self) -> impl Iterator<Item=usize> + '_ {
+        let slots = self.components.deref();
+        slots.iter().enumerate().filter_map(|(index,slot)| slot.as_ref().map(|_| index))
+    }
+
+    fn contains(&self, index:I) -> bool {
+        let index = index.into();
+        index < self.components.deref().len() && self.components.safe_index(index.into()).is_some()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removal_clears_dependencies() {
+        let mut graph = ComponentGraph::<&str>::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        assert!(graph.insert_dependency(c,a));
+        assert!(graph.insert_dependency(a,b));
+        assert_eq!(graph.topo_sort(),vec![c,a,b]);
+
+        assert_eq!(graph.remove(c),Some("c"));
+        assert_eq!(graph.remove(c),None);
+        let d = graph.insert("d");
+        assert_eq!(d,c);
+        assert_eq!(graph.topo_sort(),vec![a,b,d]);
+        assert_eq!(graph.len(),3);
+        assert!(!graph.remove_dependency(d,a));
+    }
+
+    #[test]
+    fn dependencies_of_missing_components() {
+        let mut graph = ComponentGraph::<&str>::new();
+        let a = graph.insert("a");
+        assert!(!graph.insert_dependency(a,5));
+        graph.remove(a);
+        assert!(!graph.insert_dependency(a,a));
+        assert!(graph.is_empty());
+    }
+}
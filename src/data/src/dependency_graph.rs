@@ -2,6 +2,10 @@
 
 use crate::prelude::*;
 
+use crate::history::History;
+use crate::history::Reversible;
+
+use std::cmp::Reverse;
 use std::collections::BTreeSet;
 
 
@@ -10,19 +14,26 @@ use std::collections::BTreeSet;
 // === Node ===
 // ============
 
+/// Edges of a [`Node`]. Up to two edges are stored inline, without a heap allocation.
+pub type Edges<T> = SmallVec<[T;2]>;
+
 /// A dependency graph node. Registers all incoming and outgoing edges. Incoming enges are
 /// considered sources of this node. They need to be sorted before this node when performing the
 /// topological sorting.
 ///
-/// Please note that the input and output edges are stored in a vector because in most cases there
-/// would be small amount of them (zero or one).
+/// Please note that the input and output edges are stored in [`Edges`], which keep up to two edges
+/// inline, because in most cases there would be small amount of them (zero, one, or two). Nodes
+/// with more edges allocate them on the heap, just like a vector. Compared to storing the edges in
+/// vectors, it halves the number of allocations when building typical graphs. Keys should be kept
+/// small: with `u32` keys the inline edges take as much space as a vector header. The order of the
+/// edges is not meaningful, so removed edges are replaced with the last ones.
 #[derive(Clone,Debug)]
 #[derive(Derivative)]
 #[derivative(Default(bound=""))]
 #[allow(missing_docs)]
 pub struct Node<Edge> {
-    pub ins : Vec<Edge>,
-    pub out : Vec<Edge>,
+    pub ins : Edges<Edge>,
+    pub out : Edges<Edge>,
 }
 
 impl<Edge> Node<Edge> {
@@ -42,7 +53,13 @@ impl<Edge> Node<Edge> {
 ///
 /// The primary use case of this graph is topological sorting of dependencies. Please note that this
 /// graph implementation is not DAG, it can contain cycles. In case a cycle occurs it will be
-/// automatically broken on the lowest node id.
+/// automatically broken on the lowest node id. As it can invert many of the recorded dependencies,
+/// the [`DependencyGraph::topo_sort_breaking_cycles`] can be used instead, which removes only a
+/// small set of edges, chosen with the [`DependencyGraph::feedback_arc_set`] heuristic.
+///
+/// The graph works with any ordered keys, but compact keys, like `u32`, make the nodes and their
+/// inline edges smaller. See [`Node`] to learn more, and the `bench_dependency_graph` benchmarks to
+/// compare the performance of `usize` and `u32` keys.
 #[derive(Clone)]
 #[derive(Derivative)]
 #[derivative(Default(bound="T:Eq+Hash+Ord"))]
@@ -74,13 +91,37 @@ impl<T:Clone+Eq+Hash+Ord> DependencyGraph<T> {
     /// Remove a dependency from the graph. Returns [`true`] if the dependency was found, or
     /// [`false`] otherwise.
     pub fn remove_dependency(&mut self, first:T, second:T) -> bool {
-        let fst_found = self.nodes.get_mut(&first).map(|t| t.out.remove_item(&second).is_some());
-        let snd_found = self.nodes.get_mut(&second).map(|t| t.ins.remove_item(&first).is_some());
+        let fst_node  = self.nodes.get_mut(&first);
+        let fst_found = fst_node.map(|t| t.out.swap_remove_item(&second).is_some());
+        let snd_node  = self.nodes.get_mut(&second);
+        let snd_found = snd_node.map(|t| t.ins.swap_remove_item(&first).is_some());
         if self.nodes.get(&first).map(|t|t.is_empty()) == Some(true) { self.nodes.remove(&first); }
         if self.nodes.get(&second).map(|t|t.is_empty()) == Some(true) { self.nodes.remove(&second); }
         fst_found == Some(true) && snd_found == Some(true)
     }
 
+    /// Remove all (incoming and outgoing) dependencies of the node. Returns [`true`] if the node
+    /// had any dependencies, or [`false`] otherwise.
+    pub fn remove_node(&mut self, key:&T) -> bool {
+        let node = match self.nodes.remove(key) {
+            None       => return false,
+            Some(node) => node,
+        };
+        for key2 in node.ins {
+            self.nodes.get_mut(&key2).for_each(|t| t.out.swap_remove_item(key));
+            self.remove_if_empty(&key2);
+        }
+        for key2 in node.out {
+            self.nodes.get_mut(&key2).for_each(|t| t.ins.swap_remove_item(key));
+            self.remove_if_empty(&key2);
+        }
+        true
+    }
+
+    fn remove_if_empty(&mut self, key:&T) {
+        if self.nodes.get(key).map(|t|t.is_empty()) == Some(true) { self.nodes.remove(key); }
+    }
+
     /// Removes all (incoming and outgoing) dependencies from nodes whose indexes do not belong to
     /// the provided slice.
     pub fn keep_only(&mut self, keys:&[T]) {
@@ -114,10 +155,10 @@ impl<T:Clone+Eq+Hash+Ord> DependencyGraph<T> {
                     if let Some(node) = self.nodes.get_mut(&key) {
                         let node = mem::take(node);
                         for key2 in node.ins {
-                            self.nodes.get_mut(&key2).for_each(|t| t.out.remove_item(&key))
+                            self.nodes.get_mut(&key2).for_each(|t| t.out.swap_remove_item(&key))
                         }
                         for key2 in node.out {
-                            self.nodes.get_mut(&key2).for_each(|t| t.ins.remove_item(&key))
+                            self.nodes.get_mut(&key2).for_each(|t| t.ins.swap_remove_item(&key))
                         }
                     }
                     opt_key = keys_iter.next();
@@ -136,7 +177,45 @@ impl<T:Clone+Eq+Hash+Ord> DependencyGraph<T> {
     /// In case the graph is not a DAG, it will still be sorted by breaking cycles on elements with
     /// the smallest index.
     pub fn topo_sort(&self, keys:&[T]) -> Vec<T> {
-        self.unchecked_topo_sort(keys.iter().cloned().sorted().collect_vec())
+        self.topo_sort_iter(keys).cloned().collect()
+    }
+
+    /// Just like [`topo_sort`], but sorts the provided vector in place. Duplicated indexes are
+    /// removed. Neither the graph nor the indexes are cloned.
+    pub fn topo_sort_in_place(&self, keys:&mut Vec<T>) {
+        keys.sort_unstable();
+        keys.dedup();
+        let mut targets = vec![0;keys.len()];
+        for (target,key) in self.topo_sort_iter(keys.iter()).enumerate() {
+            if let Ok(index) = keys.binary_search(key) { targets[index] = target }
+        }
+        for index in 0..keys.len() {
+            while targets[index] != index {
+                let target = targets[index];
+                keys.swap(index,target);
+                targets.swap(index,target);
+            }
+        }
+    }
+
+    /// Just like [`topo_sort`], but returns a lazy iterator over the borrowed indexes. The provided
+    /// indexes do not need to be sorted, and duplicated indexes are visited once.
+    pub fn topo_sort_iter<'a>(&'a self, keys:impl IntoIterator<Item=&'a T>) -> TopoSortIter<'a,T> {
+        let keys            = keys.into_iter().collect::<BTreeSet<_>>();
+        let mut in_counts   = HashMap::new();
+        let mut orphans     = BTreeSet::new();
+        let mut non_orphans = BTreeSet::new();
+        for key in keys.iter().copied() {
+            let ins      = self.nodes.get(key).map(|node| node.ins.as_slice()).unwrap_or_default();
+            let in_count = ins.iter().filter(|key2| keys.contains(key2)).count();
+            if in_count == 0 { orphans.insert(key); }
+            else {
+                in_counts.insert(key,in_count);
+                non_orphans.insert(key);
+            }
+        }
+        let graph = self;
+        TopoSortIter {graph,in_counts,orphans,non_orphans}
     }
 
     /// Just like [`topo_sort`], but consumes the current dependency graph instead of cloning it.
@@ -184,7 +263,7 @@ impl<T:Clone+Eq+Hash+Ord> DependencyGraph<T> {
                         for ix2 in mem::take(&mut node.out) {
                             if let Some(node2) = nodes.get_mut(&ix2) {
                                 let ins = &mut node2.ins;
-                                ins.remove_item(&ix);
+                                ins.swap_remove_item(&ix);
                                 if ins.is_empty() && non_orphans.remove(&ix2) {
                                     orphans.insert(ix2);
                                 }
@@ -196,8 +275,145 @@ impl<T:Clone+Eq+Hash+Ord> DependencyGraph<T> {
         }
         sorted
     }
+
+    /// Sorts the provided indexes in topological order, just like [`topo_sort`], but if the graph
+    /// is not a DAG, the cycles are broken by ignoring the edges of the [`feedback_arc_set`] of
+    /// the graph restricted to the provided indexes. Returns the sorted indexes and the ignored
+    /// edges.
+    pub fn topo_sort_breaking_cycles(&self, keys:&[T]) -> (Vec<T>,Vec<(T,T)>) {
+        let sorted_keys = keys.iter().cloned().sorted().collect_vec();
+        let mut graph   = self.clone().unchecked_kept_only(sorted_keys.iter().cloned());
+        let removed     = graph.feedback_arc_set();
+        for (first,second) in &removed {
+            graph.remove_dependency(first.clone(),second.clone());
+        }
+        (graph.into_unchecked_topo_sort(sorted_keys),removed)
+    }
+
+    /// Finds a small set of edges whose removal makes the graph acyclic, using the greedy heuristic
+    /// of Eades, Lin and Smyth. The nodes are ordered by repeatedly moving sinks to the end and
+    /// sources to the beginning of the order, and when neither exists, moving the node with the
+    /// biggest difference of outgoing and incoming edges (the smallest node on ties) to the
+    /// beginning. All edges pointing backwards in this order (including self-loops) are returned.
+    pub fn feedback_arc_set(&self) -> Vec<(T,T)> {
+        let order = self.eades_order();
+        let index = order.iter().enumerate().map(|(ix,key)| (*key,ix)).collect::<HashMap<_,_>>();
+        let mut removed = Vec::new();
+        for (key,node) in &self.nodes {
+            for key2 in &node.out {
+                if index[key2] <= index[key] { removed.push((key.clone(),key2.clone())) }
+            }
+        }
+        removed
+    }
+
+    fn eades_order(&self) -> Vec<&T> {
+        let mut ins     = HashMap::<&T,usize>::new();
+        let mut outs    = HashMap::<&T,usize>::new();
+        let mut sources = Vec::new();
+        let mut sinks   = Vec::new();
+        for (key,node) in &self.nodes {
+            let in_count  = neighbors(key,&node.ins).count();
+            let out_count = neighbors(key,&node.out).count();
+            if in_count  == 0 { sources.push(key) }
+            if out_count == 0 { sinks.push(key) }
+            ins.insert(key,in_count);
+            outs.insert(key,out_count);
+        }
+        let mut remaining = self.nodes.keys().collect::<BTreeSet<_>>();
+        let mut left      = Vec::new();
+        let mut right     = Vec::new();
+        loop {
+            let next = if let Some(key) = sinks.pop() {
+                if remaining.contains(key) { right.push(key) }
+                key
+            } else if let Some(key) = sources.pop() {
+                if remaining.contains(key) { left.push(key) }
+                key
+            } else {
+                let delta = |key:&&T| outs[key] as isize - ins[key] as isize;
+                match remaining.iter().copied().max_by_key(|key| (delta(key),Reverse(*key))) {
+                    None      => break,
+                    Some(key) => { left.push(key); key }
+                }
+            };
+            if remaining.remove(next) {
+                let node = &self.nodes[next];
+                for key2 in neighbors(next,&node.out) {
+                    if remaining.contains(key2) {
+                        let count = ins.get_mut(key2).unwrap();
+                        *count -= 1;
+                        if *count == 0 { sources.push(key2) }
+                    }
+                }
+                for key2 in neighbors(next,&node.ins) {
+                    if remaining.contains(key2) {
+                        let count = outs.get_mut(key2).unwrap();
+                        *count -= 1;
+                        if *count == 0 { sinks.push(key2) }
+                    }
+                }
+            }
+        }
+        left.extend(right.into_iter().rev());
+        left
+    }
+}
+
+/// The nodes connected with the `key` node by the `edges`, skipping self-loops.
+fn neighbors<'a,T:Eq>(key:&'a T, edges:&'a [T]) -> impl Iterator<Item=&'a T> {
+    edges.iter().filter(move |key2| *key2 != key)
+}
+
+
+// === TopoSortIter ===
+
+/// Iterator over indexes in topological order. See [`DependencyGraph::topo_sort_iter`] to learn
+/// more.
+#[derive(Derivative)]
+#[derivative(Debug(bound="T:Debug+Eq+Hash"))]
+pub struct TopoSortIter<'a,T> {
+    graph       : &'a DependencyGraph<T>,
+    in_counts   : HashMap<&'a T,usize>,
+    orphans     : BTreeSet<&'a T>,
+    non_orphans : BTreeSet<&'a T>,
+}
+
+impl<'a,T:Eq+Hash+Ord> Iterator for TopoSortIter<'a,T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let key = match self.orphans.iter().next().copied() {
+            Some(key) => { self.orphans.remove(key); key }
+            None      => {
+                // Non DAG, contains cycle. Let's break them on the smallest node `key`.
+                let key = self.non_orphans.iter().next().copied()?;
+                self.non_orphans.remove(key);
+                key
+            }
+        };
+        if let Some(node) = self.graph.nodes.get(key) {
+            for key2 in &node.out {
+                if let Some(in_count) = self.in_counts.get_mut(key2) {
+                    *in_count -= 1;
+                    if *in_count == 0 && self.non_orphans.remove(key2) {
+                        self.orphans.insert(key2);
+                    }
+                }
+            }
+        }
+        Some(key)
+    }
+
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        let len = self.orphans.len() + self.non_orphans.len();
+        (len,Some(len))
+    }
 }
 
+impl<'a,T:Eq+Hash+Ord> ExactSizeIterator for TopoSortIter<'a,T> {}
+
+
+// === Impls ===
 
 impl<'a,T> IntoIterator for &'a DependencyGraph<T> {
     type Item     = (&'a T, &'a Node<T>);
@@ -222,6 +438,193 @@ impl<T:Ord> Extend<(T,Node<T>)> for DependencyGraph<T> {
 }
 
 
+// === Arbitrary ===
+
+/// Generates graphs from arbitrary sets of edges, possibly containing cycles.
+#[cfg(feature="arbitrary")]
+impl<'a,T> arbitrary::Arbitrary<'a> for DependencyGraph<T>
+where T:arbitrary::Arbitrary<'a>+Clone+Eq+Hash+Ord {
+    fn arbitrary(u:&mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut graph = Self::new();
+        for edge in u.arbitrary_iter::<(T,T)>()? {
+            let (first,second) = edge?;
+            graph.insert_dependency(first,second);
+        }
+        Ok(graph)
+    }
+}
+
+
+// === Undo and Redo ===
+
+/// A change of a [`DependencyGraph`], recorded by its [`History`].
+#[derive(Clone,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub enum Change<T> {
+    InsertDependency(T,T),
+    RemoveDependency(T,T),
+}
+
+impl<T:Clone+Eq+Hash+Ord> Reversible for DependencyGraph<T> {
+    type Change = Change<T>;
+    fn apply(&mut self, change:Change<T>) -> Option<Change<T>> {
+        match change {
+            Change::InsertDependency(first,second) => {
+                let changed = self.insert_dependency(first.clone(),second.clone());
+                changed.as_some(Change::RemoveDependency(first,second))
+            }
+            Change::RemoveDependency(first,second) => {
+                let changed = self.remove_dependency(first.clone(),second.clone());
+                changed.as_some(Change::InsertDependency(first,second))
+            }
+        }
+    }
+}
+
+/// Modifiers of the graph recording the changes, so they can be undone. They behave like the
+/// methods of [`DependencyGraph`] with the same names.
+impl<T:Clone+Eq+Hash+Ord> History<DependencyGraph<T>> {
+    /// Insert a new dependency to the graph. Returns [`true`] if the insertion was successful.
+    pub fn insert_dependency(&mut self, first:T, second:T) -> bool {
+        self.apply(Change::InsertDependency(first,second))
+    }
+
+    /// Remove a dependency from the graph. Returns [`true`] if the dependency was found.
+    pub fn remove_dependency(&mut self, first:T, second:T) -> bool {
+        self.apply(Change::RemoveDependency(first,second))
+    }
+
+    /// Remove all (incoming and outgoing) dependencies of the node. Returns [`true`] if the node
+    /// had any dependencies.
+    pub fn remove_node(&mut self, key:&T) -> bool {
+        let node  = match self.nodes.get(key) { None => return false, Some(node) => node };
+        let ins   = node.ins.iter().map(|key2| (key2.clone(),key.clone()));
+        let out   = node.out.iter().map(|key2| (key.clone(),key2.clone()));
+        let edges = ins.chain(out).unique().collect_vec();
+        for (first,second) in edges {
+            self.remove_dependency(first,second);
+        }
+        true
+    }
+}
+
+
+
+// =============================
+// === CachedDependencyGraph ===
+// =============================
+
+/// A [`DependencyGraph`] caching the last computed topological order. Sorting the same keys again
+/// returns the cached order, unless the graph was modified in a way which could change it, that
+/// is, unless an edge between two of the sorted keys was inserted or removed. It is meant for
+/// graphs sorted every frame, which change rarely.
+///
+/// The graph can be read through `Deref`, but it has to be modified through the methods of this
+/// structure, so the cache is invalidated properly.
+#[derive(Clone)]
+#[derive(Derivative)]
+#[derivative(Default(bound="T:Eq+Hash+Ord"))]
+#[derivative(Debug(bound="T:Debug+Eq+Hash"))]
+pub struct CachedDependencyGraph<T> {
+    graph : DependencyGraph<T>,
+    cache : Option<SortCache<T>>,
+}
+
+/// The last sorted keys, in the order they were provided, and their topological order.
+#[derive(Clone,Debug)]
+struct SortCache<T> {
+    keys     : Vec<T>,
+    key_set  : BTreeSet<T>,
+    order    : Vec<T>,
+    is_dirty : bool,
+}
+
+impl<T:Clone+Eq+Hash+Ord> CachedDependencyGraph<T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Consume the structure, returning the graph.
+    pub fn into_graph(self) -> DependencyGraph<T> {
+        self.graph
+    }
+
+    /// Insert a new dependency to the graph. See [`DependencyGraph::insert_dependency`] to learn
+    /// more.
+    pub fn insert_dependency(&mut self, first:T, second:T) -> bool {
+        self.invalidate_edge(&first,&second);
+        self.graph.insert_dependency(first,second)
+    }
+
+    /// Remove a dependency from the graph. See [`DependencyGraph::remove_dependency`] to learn
+    /// more.
+    pub fn remove_dependency(&mut self, first:T, second:T) -> bool {
+        self.invalidate_edge(&first,&second);
+        self.graph.remove_dependency(first,second)
+    }
+
+    /// Remove all dependencies of the node. See [`DependencyGraph::remove_node`] to learn more.
+    pub fn remove_node(&mut self, key:&T) -> bool {
+        if let Some(cache) = &mut self.cache {
+            cache.is_dirty |= cache.key_set.contains(key);
+        }
+        self.graph.remove_node(key)
+    }
+
+    /// Remove all dependencies from nodes which do not belong to the provided slice. See
+    /// [`DependencyGraph::keep_only`] to learn more.
+    pub fn keep_only(&mut self, keys:&[T]) {
+        self.invalidate();
+        self.graph.keep_only(keys)
+    }
+
+    /// Drop the cached order, so the next sorting computes it again.
+    pub fn invalidate(&mut self) {
+        self.cache = None;
+    }
+
+    /// Check whether sorting the keys would return the cached order.
+    pub fn is_cached(&self, keys:&[T]) -> bool {
+        self.cache.as_ref().map(|cache| !cache.is_dirty && cache.keys == keys) == Some(true)
+    }
+
+    /// Sort the keys in topological order, just like [`DependencyGraph::topo_sort`]. If the same
+    /// keys, in the same order, were sorted the last time, and no relevant edge changed since, the
+    /// cached order is returned without sorting.
+    pub fn topo_sort(&mut self, keys:&[T]) -> &[T] {
+        if !self.is_cached(keys) {
+            let keys     = keys.to_vec();
+            let key_set  = keys.iter().cloned().collect();
+            let order    = self.graph.topo_sort(&keys);
+            let is_dirty = false;
+            self.cache   = Some(SortCache {keys,key_set,order,is_dirty});
+        }
+        self.cache.as_ref().map(|cache| cache.order.as_slice()).unwrap_or_default()
+    }
+
+    fn invalidate_edge(&mut self, first:&T, second:&T) {
+        if let Some(cache) = &mut self.cache {
+            cache.is_dirty |= cache.key_set.contains(first) && cache.key_set.contains(second);
+        }
+    }
+}
+
+impl<T> Deref for CachedDependencyGraph<T> {
+    type Target = DependencyGraph<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.graph
+    }
+}
+
+impl<T> From<DependencyGraph<T>> for CachedDependencyGraph<T> {
+    fn from(graph:DependencyGraph<T>) -> Self {
+        let cache = None;
+        Self {graph,cache}
+    }
+}
+
+
 
 // ==============
 // === Macros ===
@@ -259,13 +662,15 @@ impl<T:Ord> Extend<(T,Node<T>)> for DependencyGraph<T> {
 // === Tests ===
 // =============
 
-extern crate test;
-
 /// Asserts whether the graph will sort the provided slice in the same order as it was provided.
 /// Please note, that the slice is sorted in order before being sorted topologically.
 pub fn assert_valid_sort(graph:&DependencyGraph<usize>, sorted:&[usize]) {
-    let sorted = sorted.to_vec();
+    let sorted   = sorted.to_vec();
+    let mut keys = sorted.iter().copied().rev().collect_vec();
+    graph.topo_sort_in_place(&mut keys);
     assert_eq!(graph.topo_sort(&sorted),sorted);
+    assert_eq!(graph.clone().into_topo_sort(&sorted),sorted);
+    assert_eq!(keys,sorted);
 }
 
 /// The same as [`assert_valid_sort`] but with a shorter syntax. Learn more about it by looking at
@@ -284,6 +689,24 @@ macro_rules! assert_valid_sort {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_undo_and_redo() {
+        let mut history = History::new(dependency_graph!{1->0,2->1},8);
+        history.insert_dependency(0,3);
+        history.checkpoint();
+        history.remove_node(&1);
+        history.remove_dependency(5,6);
+        assert_eq!(history.topo_sort(&[0,1,2,3]),vec![0,1,2,3]);
+        assert!(history.undo());
+        assert_valid_sort(&history,&[2,1,0,3]);
+        assert!(history.undo());
+        assert_eq!(history.topo_sort(&[0,1,2,3]),vec![2,1,0,3]);
+        assert_eq!((&*history).into_iter().map(|(key,_)| *key).collect_vec(),vec![0,1,2]);
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(history.topo_sort(&[0,1,2,3]),vec![0,1,2,3]);
+    }
+
     #[test]
     fn test_identity() {
         assert_valid_sort!{
@@ -314,6 +737,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_remove_node() {
+        let mut graph = dependency_graph!{0->1,1->2,2->1,3->1};
+        assert!(graph.remove_node(&1));
+        assert!(!graph.remove_node(&1));
+        assert!(graph.into_iter().next().is_none());
+    }
+
+    #[test]
+    fn test_feedback_arc_set() {
+        let graph = dependency_graph!{0->1,1->2,2->0,2->3,3->4,4->2,5->5};
+        let keys  = (0..6).collect_vec();
+        let (sorted,removed) = graph.topo_sort_breaking_cycles(&keys);
+        assert_eq!(removed.len(),3);
+        assert!(removed.contains(&(5,5)));
+        assert_eq!(sorted.iter().copied().sorted().collect_vec(),keys);
+        let position = |key:usize| sorted.iter().position(|t| *t == key).unwrap();
+        for (key,node) in &graph {
+            for key2 in &node.out {
+                if !removed.contains(&(*key,*key2)) {
+                    assert!(position(*key) < position(*key2));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_feedback_arc_set_of_dag() {
+        let graph = dependency_graph!{4->3,3->2,2->1,1->0,4->0};
+        assert!(graph.feedback_arc_set().is_empty());
+        assert_eq!(graph.topo_sort_breaking_cycles(&[0,1,2,3,4]),(vec![4,3,2,1,0],vec![]));
+    }
+
+    #[test]
+    fn test_cached_order() {
+        let mut graph = CachedDependencyGraph::from(dependency_graph!{2->1,1->0});
+        let keys      = [0,1,2];
+        assert_eq!(graph.topo_sort(&keys),&[2,1,0]);
+        assert!(graph.is_cached(&keys));
+        assert!(!graph.is_cached(&[2,1,0]));
+        graph.insert_dependency(5,0);
+        graph.remove_node(&7);
+        assert!(graph.is_cached(&keys));
+        graph.insert_dependency(0,2);
+        assert!(!graph.is_cached(&keys));
+        graph.remove_dependency(1,0);
+        assert_eq!(graph.topo_sort(&keys),&[0,2,1]);
+        assert!(graph.is_cached(&keys));
+        graph.remove_node(&2);
+        assert_eq!(graph.topo_sort(&keys),&[0,1,2]);
+    }
+
+    #[test]
+    fn test_inline_edges() {
+        let graph = dependency_graph!{0->1,0->2,1->2,3->0,3->1,3->2};
+        let nodes = graph.into_iter().collect::<BTreeMap<_,_>>();
+        assert!(!nodes[&0].ins.spilled() && !nodes[&0].out.spilled());
+        assert!(nodes[&2].ins.spilled());
+        assert_eq!(nodes[&2].ins.as_slice(),&[0,1,3]);
+    }
+
     #[test]
     fn test_non_dag() {
         assert_valid_sort!{
@@ -327,39 +811,3 @@ mod tests {
         }
     }
 }
-
-#[cfg(test)]
-mod benches {
-    use super::*;
-    use test::Bencher;
-
-    /// # Results (ms)
-    ///
-    ///   iters | time(ms) |
-    ///   10^3  | 0.47     |
-    ///   10^4  | 5.2      |
-    ///   10^5  | 74.2     |
-    #[bench]
-    fn bench_ascending(b:&mut Bencher) {
-        let iters     = 1_000;
-        let out       = (0..iters).collect_vec();
-        let mut graph = DependencyGraph::new();
-        for (i,j) in out.iter().zip(out.iter().skip(1)) { graph.insert_dependency(*i,*j); }
-        b.iter(move || assert_eq!(graph.topo_sort(&out),out));
-    }
-
-    /// # Results (ms)
-    ///
-    ///   iters | time(ms) |
-    ///   10^3  | 0.5      |
-    ///   10^4  | 6.2      |
-    ///   10^5  | 86.8     |
-    #[bench]
-    fn bench_descending(b:&mut Bencher) {
-        let iters     = 1_000;
-        let out       = (0..iters).rev().collect_vec();
-        let mut graph = DependencyGraph::new();
-        for (i,j) in out.iter().zip(out.iter().skip(1)) { graph.insert_dependency(*i,*j); }
-        b.iter(move || assert_eq!(graph.topo_sort(&out),out));
-    }
-}
@@ -18,7 +18,7 @@
 //!    described here: https://en.wikipedia.org/wiki/B-tree#Algorithms.
 //!
 //! # Benchmarks
-//! This module contains a lot of benchmarks in order to compare different techniques of managing
+//! The `benches/bench_diet.rs` file contains benchmarks comparing different techniques of managing
 //! free indexes for the needs of efficient attribute memory management in EnsoGL. Read the docs of
 //! [`ensogl::AttributeScopeData`] to learn more.
 #![allow(clippy::field_reassign_with_default)]
@@ -27,43 +27,7 @@ use crate::prelude::*;
 
 use std::mem::MaybeUninit;
 
-
-
-// ================
-// === Interval ===
-// ================
-
-/// Closed interval. For example, [`Interval(1,2)`] means `[1,2]` in math.
-#[derive(Clone,Copy,Default,Eq,PartialEq)]
-#[allow(missing_docs)]
-pub struct Interval {
-    pub start : usize,
-    pub end   : usize,
-}
-
-/// Constructor.
-#[allow(non_snake_case)]
-pub fn Interval(start:usize, end:usize) -> Interval {
-    Interval {start,end}
-}
-
-impl Debug for Interval {
-    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Interval({:?},{:?})", self.start, self.end)
-    }
-}
-
-impl From<usize> for Interval {
-    fn from(t:usize) -> Self {
-        Interval(t,t)
-    }
-}
-
-impl From<(usize,usize)> for Interval {
-    fn from(t:(usize,usize)) -> Self {
-        Interval(t.0,t.1)
-    }
-}
+pub use crate::interval::Interval;
 
 
 
@@ -917,264 +881,3 @@ mod tests {
         )
     }
 }
-
-
-
-// ==================
-// === Benchmarks ===
-// ==================
-
-extern crate test;
-
-
-// This is a simplified implementation created for the needs of benchmarking of the
-// `std::collections::BTreeSet`. It works correctly only for inserting unit intervals (where
-// `start` = `end`).
-#[cfg(test)]
-impl PartialOrd for Interval {
-    fn partial_cmp(&self, other:&Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-// This is a simplified implementation created for the needs of benchmarking of the
-// `std::collections::BTreeSet`. It works correctly only for inserting unit intervals (where
-// `start` = `end`).
-#[cfg(test)]
-impl Ord for Interval{
-    fn cmp(&self, other:&Self) -> std::cmp::Ordering {
-        if      other.start + 1 < self.start   { std::cmp::Ordering::Greater }
-        else if other.start     > self.end + 1 { std::cmp::Ordering::Less }
-        else                                   { std::cmp::Ordering::Equal }
-    }
-}
-
-/// # How the results were measured
-///
-/// The results provided below are shown only for intuition building and would vary depending on the
-/// used hardware. They were measured by using the MacBook Pro 2019 with Intel Core i9 2.4GHz.
-///
-///
-/// # Summary
-///
-/// There are several interesting facts about the current implementation:
-///
-/// 1. It seems that (at least for now) the best performing implementation is the `Tree16`.
-///
-/// 2. When performing insertions of ascending, non-overlapping intervals, the `Tree16` performs
-///    60% SLOWER than `std::collections::BTreeSet`.
-///
-/// 3. When performing insertions of descending, non-overlapping intervals, the `Tree16` performs
-///    40% SLOWER than `std::collections::BTreeSet`.
-///
-/// 4. In case of `Tree16`, the insertion in ascending order is 50% slower than in descending order.
-///    In case of `std::collections::BTreeSet`, insertion in ascending order is 40% slower than in
-///    descending order.
-///
-/// 5. This implementation is 4x FASTER than the `lz_diet` crate.
-///
-#[cfg(test)]
-mod benches {
-    use super::*;
-    use test::Bencher;
-
-    /// # Results (ms)
-    ///                                -> BEST <-
-    ///        | Tree2 | Tree4 | Tree8 | Tree16 | Tree32 |
-    ///   10^4 | 12.5  | 4     | 2.3   | 1.4    | 1.8    |
-    ///   10^5 |       | 63.9  | 31.6  | 18.3   | 21.2   |
-    ///   10^6 |       |       |       | 285.5  |        |
-    #[bench]
-    fn bench_insert_ascending(b:&mut Bencher) {
-        b.iter(|| {
-            let mut v = Tree16::default();
-            for i in 0 .. test::black_box(1000) {
-                v.insert(i*2);
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///                                -> BEST <-
-    ///        | Tree2 | Tree4 | Tree8 | Tree16 | Tree32 | Tree64 |
-    ///   10^4 | 12.3  | 3.6   | 1.8   | 0.92   | 1      | 1.7    |
-    ///   10^5 | 200   | 62    | 27.5  | 12     | 12     | 18.9   |
-    ///   10^6 |       |       |       | 212    |        |        |
-    #[bench]
-    fn bench_insert_descending(b:&mut Bencher) {
-        b.iter(|| {
-            let max   = test::black_box(100_000);
-            let mut v = Tree16::default();
-            for i in 0 .. max {
-                v.insert((max-i)*2);
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///                                -> BEST <-
-    ///        | Tree2 | Tree4 | Tree8 | Tree16 | Tree32 | Tree64 |
-    ///   10^4 | 14.1  | 5.2   | 3.4   | 2.7    | 3.32   | 4.8    |
-    ///   10^5 |       |       | 43.9  | 32     | 39.5   |        |
-    #[bench]
-    fn bench_insert_ascending_growing(b:&mut Bencher) {
-        b.iter(|| {
-            let max = test::black_box(1000);
-            let mut v = Tree16::default();
-            for i in 0 .. max { v.insert(i*4); }
-            for i in 0 .. max { v.insert(i*4+1); }
-            for i in 0 .. max { v.insert(i*4+2); }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^4 | 0.92 |
-    ///   10^5 | 11.8 |
-    ///   10^6 | 149  |
-    #[bench]
-    fn bench_insert_ascending_std(b:&mut Bencher) {
-        b.iter(|| {
-            let mut v = std::collections::BTreeSet::<Interval>::default();
-            for i in 1 .. test::black_box(1000) {
-                let j = i*2;
-                v.insert(Interval(j,j));
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^4 | 0.6 |
-    ///   10^5 | 8.8 |
-    ///   10^6 | 101 |
-    #[bench]
-    fn bench_insert_descending_std(b:&mut Bencher) {
-        b.iter(|| {
-            let mut v = std::collections::BTreeSet::<Interval>::default();
-            let max   = test::black_box(1000);
-            for i in 0 .. max {
-                let j = (max-i)*2;
-                v.insert(Interval(j,j));
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^4 | 0.08  |
-    ///   10^5 | 9.7   |
-    ///   10^6 | 115.5 |
-    #[bench]
-    fn bench_insert_ascending_std_usize(b:&mut Bencher) {
-        b.iter(|| {
-            let mut v = std::collections::BTreeSet::<usize>::default();
-            for i in 1 .. test::black_box(1_000_000) {
-                v.insert(i*2);
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^5 | 0.1 |
-    ///   10^6 | 2.5 |
-    #[bench]
-    fn bench_insert_vec_non_sorted(b:&mut Bencher) {
-        b.iter(|| {
-            let mut v = Vec::<usize>::default();
-            for i in 1 .. test::black_box(1000) {
-                v.push(i*2);
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///        | 100  | 1000 | 10_000 | (sort_every)
-    ///   10^4 | 0.3  | 0.09 | 0.06   |
-    ///   10^5 | 26   | 3.5  | 0.9    |
-    ///   10^6 |      |      | 63.8   |
-    #[bench]
-    fn bench_insert_vec_sort_every_x(b:&mut Bencher) {
-        b.iter(|| {
-            let sort_every = test::black_box(10000);
-            let mut v = Vec::<usize>::default();
-            for i in 1 .. test::black_box(1000) {
-                v.push(i*2);
-                if i % sort_every == 0 {
-                    v.sort_unstable()
-                }
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^5 | 0.04 |
-    ///   10^6 | 0.5  |
-    ///   10^7 | 7.5  |
-    ///   10^8 | 89.4 |
-    #[bench]
-    fn vec_sort_already_almost_sorted(b:&mut Bencher) {
-        let mut v = Vec::<usize>::default();
-        let num = test::black_box(1000);
-        for i in 0 .. num {
-            v.push(num - i);
-        }
-        b.iter(|| {
-            v.sort_unstable()
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^8 | 8 |
-    ///
-    /// # Comparison to not using `Rc<Cell<...>>`
-    /// Note that there is NO DIFFERENCE between this and the version without `Rc<Cell<...>>`.
-    /// However, this may behave differently in real-world use case, so we need to make benchmarks
-    /// before using it in EnsoGL attribute manageent sytem. Read the docs of
-    /// [`ensogl::AttributeScopeData`] to learn more.
-    #[bench]
-    fn mode_rc_cell_num(b:&mut Bencher) {
-        let v = Rc::new(Cell::new(0));
-        let num = test::black_box(100_000_000);
-        b.iter(|| {
-            for i in 0 .. num {
-                if i % 2 == 0 { v.set(v.get() + 1) }
-                else          { v.set(v.get() - 1) }
-            }
-        });
-    }
-
-    /// # Results (ms)
-    ///
-    ///   10^8 | 8 |
-    #[bench]
-    fn mode_num(b:&mut Bencher) {
-        let mut v = 0;
-        let num = test::black_box(100_000_000);
-        b.iter(|| {
-            for i in 0 .. num {
-                if i % 2 == 0 { v += 1 }
-                else          { v -= 1 }
-            }
-        });
-    }
-
-    // /// Benchmarks of the `lz_diet-0.1.6` crate. Disabled in order not to include it in the final
-    // /// binary.
-    // /// # Results (ms)
-    // ///   10^4 | 7.4  |
-    // ///   10^5 | 85.1 |
-    // #[bench]
-    // fn bench_insert_ascending_lz_diet(b:&mut Bencher) {
-    //     b.iter(|| {
-    //         let mut v = lz_diet::Diet::new();
-    //         for i in 0 .. test::black_box(1000_00) {
-    //             v.insert(i*2);
-    //         }
-    //     });
-    // }
-}
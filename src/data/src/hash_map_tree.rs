@@ -2,6 +2,12 @@
 
 use crate::prelude::*;
 
+use crate::history::History;
+use crate::history::Reversible;
+use crate::observed::Observable;
+use crate::observed::Observed;
+use crate::tree::TreeLike;
+
 use std::collections::hash_map::RandomState;
 use std::hash::BuildHasher;
 
@@ -42,6 +48,12 @@ impl<K,V,S> HashMapTree<K,V,S> {
         !self.is_leaf()
     }
 
+    /// Consume the tree, returning the value of its root. The branches are dropped iteratively.
+    pub fn into_value(mut self) -> V {
+        self.drop_children();
+        self.value
+    }
+
     /// Obtain an iterator over the tree.
     pub fn iter(&self) -> Iter<K,V,S> {
         let root_item = Some(&self.value);
@@ -76,75 +88,68 @@ where K : Eq+Hash,
     /// Sets the value at position described by `path`. In case a required sub-branch does not
     /// exist, a default instance will be created.
     #[inline]
-    pub fn set<P,I>(&mut self, path:P, value:T)
-    where P:IntoIterator<Item=I>, T:Default, I:Into<K> {
+    pub fn set<P>(&mut self, path:P, value:T)
+    where P:Path<K>, T:Default {
         self.get_or_create_node(path).value = value;
     }
 
     /// Sets the value at position described by `path`. In case a required sub-branch does not
     /// exist, uses `cons_missing` to create it.
     #[inline]
-    pub fn set_with<P,I,F>(&mut self, path:P, value:T, cons_missing:F)
-    where P:IntoIterator<Item=I>, T:Default, I:Into<K>, F:FnMut()->T {
+    pub fn set_with<P,F>(&mut self, path:P, value:T, cons_missing:F)
+    where P:Path<K>, T:Default, F:FnMut()->T {
         self.get_or_create_node_with(path,cons_missing).value = value;
     }
 
     /// Gets a reference to a value at the specified path if the path exists in the tree.
     #[inline]
-    pub fn get<P,I>(&self, segments:P) -> Option<&T>
-    where P:IntoIterator<Item=I>, I:Into<K> {
+    pub fn get<P>(&self, segments:P) -> Option<&T>
+    where P:Path<K> {
         self.get_node(segments).map(|node| &node.value)
     }
 
     /// Gets a mutable reference to a value at the specified path if the path exists in the tree.
     #[inline]
-    pub fn get_mut<P,I>(&mut self, segments:P) -> Option<&mut T>
-    where P:IntoIterator<Item=I>, I:Into<K> {
+    pub fn get_mut<P>(&mut self, segments:P) -> Option<&mut T>
+    where P:Path<K> {
         self.get_node_mut(segments).map(|node| &mut node.value)
     }
 
     /// Gets a reference to a node at the specified path if the node exists.
     #[inline]
-    pub fn get_node<P,I>(&self, segments:P) -> Option<&HashMapTree<K,T,S>>
-    where P:IntoIterator<Item=I>, I:Into<K> {
-        segments.into_iter().fold(Some(self),|map,t| {
-            map.and_then(|m| {
-                let key = t.into();
-                m.branches.get(&key)
-            })
-        })
+    pub fn get_node<P>(&self, segments:P) -> Option<&HashMapTree<K,T,S>>
+    where P:Path<K> {
+        segments.segments().try_fold(self,|node,segment| segment.lookup(&node.branches))
     }
 
     /// Gets a mutable reference to a node at the specified path if the node exists.
     #[inline]
-    pub fn get_node_mut<P,I>(&mut self, segments:P) -> Option<&mut HashMapTree<K,T,S>>
-    where P:IntoIterator<Item=I>, I:Into<K> {
-        segments.into_iter().fold(Some(self),|map,t| {
-            map.and_then(|m| {
-                let key = t.into();
-                m.branches.get_mut(&key)
-            })
-        })
+    pub fn get_node_mut<P>(&mut self, segments:P) -> Option<&mut HashMapTree<K,T,S>>
+    where P:Path<K> {
+        segments.segments().try_fold(self,|node,segment| segment.lookup_mut(&mut node.branches))
     }
 
     /// Removes the node at the specified path.
     #[inline]
-    pub fn remove<P,I>(&mut self, segments:P) -> Option<T>
-    where P:IntoIterator<Item=I>, I:Into<K> {
-        let mut segments = segments.into_iter().map(|t|t.into()).collect_vec();
-        segments.pop().and_then(|last| {
-            self.get_node_mut(segments).and_then(|node| {
-                node.branches.remove(&last).map(|branch| branch.value)
-            })
-        })
+    pub fn remove<P>(&mut self, segments:P) -> Option<T>
+    where P:Path<K> {
+        let mut segments = segments.segments().peekable();
+        let mut node     = self;
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_none() {
+                return segment.remove_from(&mut node.branches).map(HashMapTree::into_value)
+            }
+            node = segment.lookup_mut(&mut node.branches)?;
+        }
+        None
     }
 
     /// Iterates over keys in `path`. For each key, traverses into the appropriate branch. In case
     /// the branch does not exist, a default instance will be created. Returns mutable reference to
     /// the target tree node.
     #[inline]
-    pub fn get_or_create_node<P,I>(&mut self, path:P) -> &mut HashMapTree<K,T,S>
-    where P:IntoIterator<Item=I>, T:Default, I:Into<K> {
+    pub fn get_or_create_node<P>(&mut self, path:P) -> &mut HashMapTree<K,T,S>
+    where P:Path<K>, T:Default {
         self.get_or_create_node_with(path,default)
     }
 
@@ -152,9 +157,9 @@ where K : Eq+Hash,
     /// the branch does not exist, uses `cons_missing` to construct it. Returns mutable reference to
     /// the target tree node.
     #[inline]
-    pub fn get_or_create_node_with<P,I,F>
+    pub fn get_or_create_node_with<P,F>
     (&mut self, path:P, cons_missing:F) -> &mut HashMapTree<K,T,S>
-    where P:IntoIterator<Item=I>, I:Into<K>, F:FnMut()->T {
+    where P:Path<K>, F:FnMut()->T {
         self.get_or_create_node_traversing_with(path,cons_missing,|_|{})
     }
 
@@ -162,9 +167,9 @@ where K : Eq+Hash,
     /// the branch does not exist, uses `cons_missing` provided with the current path to construct
     /// it. Returns mutable reference to the target tree node.
     #[inline]
-    pub fn get_or_create_node_path_with<P,I,F>
+    pub fn get_or_create_node_path_with<P,F>
     (&mut self, path:P, cons_missing:F) -> &mut HashMapTree<K,T,S>
-    where K:Clone, P:IntoIterator<Item=I>, I:Into<K>, F:FnMut(&[K])->T {
+    where K:Clone, P:Path<K>, F:FnMut(&[K])->T {
         self.get_or_create_node_traversing_path_with(path,cons_missing,|_|{})
     }
 
@@ -172,13 +177,12 @@ where K : Eq+Hash,
     /// the branch does not exist, uses `cons_missing` to construct it. Moreover, for each traversed
     /// branch the `callback` is evaluated. Returns mutable reference to the target tree node.
     #[inline]
-    pub fn get_or_create_node_traversing_with<P,I,F,M>
+    pub fn get_or_create_node_traversing_with<P,F,M>
     (&mut self, segments:P, mut cons_missing:F, mut callback:M) -> &mut HashMapTree<K,T,S>
-    where P:IntoIterator<Item=I>, I:Into<K>, F:FnMut()->T, M:FnMut(&mut HashMapTree<K,T,S>) {
-        segments.into_iter().fold(self,|map,t| {
-            let key   = t.into();
-            let entry = map.branches.entry(key);
-            let node  = entry.or_insert_with(|| HashMapTree::from_value(cons_missing()));
+    where P:Path<K>, F:FnMut()->T, M:FnMut(&mut HashMapTree<K,T,S>) {
+        segments.segments().fold(self,|map,segment| {
+            let cons = || HashMapTree::from_value(cons_missing());
+            let node = segment.lookup_or_insert_with(&mut map.branches,cons);
             callback(node);
             node
         })
@@ -189,16 +193,15 @@ where K : Eq+Hash,
     /// it. Moreover, for each traversed branch the `callback` is evaluated. Returns mutable
     /// reference to the target tree node.
     #[inline]
-    pub fn get_or_create_node_traversing_path_with<P,I,F,M>
+    pub fn get_or_create_node_traversing_path_with<P,F,M>
     (&mut self, segments:P, mut cons_missing:F, mut callback:M) -> &mut HashMapTree<K,T,S>
     where K : Clone,
-          P : IntoIterator<Item=I>,
-          I : Into<K>,
+          P : Path<K>,
           F : FnMut(&[K])->T,
           M : FnMut(&mut HashMapTree<K,T,S>) {
         let mut path = Vec::new();
-        segments.into_iter().fold(self,|map,t| {
-            let key = t.into();
+        segments.segments().fold(self,|map,segment| {
+            let key = segment.into_key();
             path.push(key.clone());
             let entry = map.branches.entry(key);
             let node  = entry.or_insert_with(|| HashMapTree::from_value(cons_missing(&path)));
@@ -301,6 +304,24 @@ where K : Eq+Hash+Clone,
     }
 }
 
+/// Deep trees can be dropped with `iter_drop`, or cleared with `drop_children`, without
+/// overflowing the stack. The default drop glue is recursive, as implementing `Drop` would forbid
+/// moving the public fields out of the tree.
+impl<K,V,S> DeepDrop for HashMapTree<K,V,S> {
+    type Child = Self;
+    fn take_children(&mut self, children:&mut Vec<Self>) {
+        children.extend(self.branches.drain().map(|(_,branch)| branch));
+    }
+}
+
+
+impl<'a,K:'a,V:'a,S:'a> TreeLike<'a> for HashMapTree<K,V,S> {
+    type Value    = V;
+    type Children = std::collections::hash_map::Values<'a,K,Self>;
+    fn value    (&'a self) -> &'a V          { &self.value }
+    fn children (&'a self) -> Self::Children { self.branches.values() }
+}
+
 
 // === Iterators ===
 
@@ -376,6 +397,310 @@ where K : Eq + Hash,
     }
 }
 
+/// Generates trees by setting values at arbitrary paths.
+#[cfg(feature="arbitrary")]
+impl<'a,K,V,S> arbitrary::Arbitrary<'a> for HashMapTree<K,V,S>
+where K : arbitrary::Arbitrary<'a> + Eq + Hash,
+      V : arbitrary::Arbitrary<'a> + Default,
+      S : BuildHasher + Default {
+    fn arbitrary(u:&mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary_iter::<(Vec<K>,V)>()?.collect()
+    }
+}
+
+
+// === Undo and Redo ===
+
+/// A change of a [`HashMapTree`], recorded by its [`History`].
+#[derive(Derivative)]
+#[derivative(Clone(bound = "K:Clone, V:Clone, S:Clone"))]
+#[derivative(Debug(bound = "K:Eq+Hash+Debug, V:Debug, S:BuildHasher"))]
+pub enum Change<K,V,S=RandomState> {
+    /// Set the value at the path, creating the missing nodes with default values.
+    Set(Vec<K>,V),
+    /// Remove the node at the path, along with all its branches.
+    Remove(Vec<K>),
+    /// Insert the subtree at the path, replacing the existing node. Does nothing if the parent of
+    /// the path does not exist.
+    Insert(Vec<K>,HashMapTree<K,V,S>),
+}
+
+impl<K,V,S> Reversible for HashMapTree<K,V,S>
+where K : Clone+Eq+Hash,
+      V : Default,
+      S : BuildHasher+Default {
+    type Change = Change<K,V,S>;
+    fn apply(&mut self, change:Self::Change) -> Option<Self::Change> {
+        match change {
+            Change::Set(path,value) => {
+                let mut node  = &*self;
+                let mut depth = 0;
+                while let Some(branch) = path.get(depth).and_then(|key| node.branches.get(key)) {
+                    node   = branch;
+                    depth += 1;
+                }
+                if depth == path.len() {
+                    let node = self.get_node_mut(SegmentIter(path.iter()))?;
+                    let old  = mem::replace(&mut node.value,value);
+                    Some(Change::Set(path,old))
+                } else {
+                    self.set(SegmentIter(path.iter()),value);
+                    Some(Change::Remove(path[..=depth].to_vec()))
+                }
+            }
+            Change::Remove(path) => {
+                let (key,parent) = path.split_last()?;
+                let parent       = self.get_node_mut(SegmentIter(parent.iter()))?;
+                let node         = parent.branches.remove(key)?;
+                Some(Change::Insert(path,node))
+            }
+            Change::Insert(path,node) => match path.split_last() {
+                None => Some(Change::Insert(path,mem::replace(self,node))),
+                Some((key,parent)) => {
+                    let parent   = self.get_node_mut(SegmentIter(parent.iter()))?;
+                    let previous = parent.branches.insert(key.clone(),node);
+                    Some(match previous {
+                        Some(previous) => Change::Insert(path,previous),
+                        None           => Change::Remove(path),
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// Modifiers of the tree recording the changes, so they can be undone.
+impl<K,V,S> History<HashMapTree<K,V,S>>
+where K : Clone+Eq+Hash,
+      V : Default,
+      S : BuildHasher+Default {
+    /// Sets the value at position described by `path`. Missing nodes are created with default
+    /// values.
+    pub fn set<P:Path<K>>(&mut self, path:P, value:V) {
+        let path = path.segments().map(Segment::into_key).collect();
+        self.apply(Change::Set(path,value));
+    }
+
+    /// Removes the node at the specified path, along with all its branches. Returns false if the
+    /// node did not exist.
+    pub fn remove<P:Path<K>>(&mut self, path:P) -> bool {
+        let path = path.segments().map(Segment::into_key).collect();
+        self.apply(Change::Remove(path))
+    }
+}
+
+
+// === Observing ===
+
+/// A change of a [`HashMapTree`], reported to the observers of [`Observed`] trees.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum Event<K> {
+    /// The value at the path was set. The missing nodes on the path were created with default
+    /// values.
+    Set(Vec<K>),
+    /// The node at the path was removed, along with all its branches.
+    Removed(Vec<K>),
+}
+
+impl<K,V,S> Observable for HashMapTree<K,V,S> {
+    type Event = Event<K>;
+}
+
+/// Modifiers of the tree notifying the observers. They behave like the methods of [`HashMapTree`]
+/// with the same names.
+impl<K,V,S> Observed<HashMapTree<K,V,S>>
+where K : Clone+Eq+Hash,
+      V : Default,
+      S : BuildHasher+Default {
+    /// Sets the value at position described by `path`. Missing nodes are created with default
+    /// values.
+    pub fn set<P:Path<K>>(&mut self, path:P, value:V) {
+        let path = path.segments().map(Segment::into_key).collect_vec();
+        self.data_mut().set(SegmentIter(path.iter()),value);
+        self.notify(Event::Set(path));
+    }
+
+    /// Removes the node at the specified path. Returns its value.
+    pub fn remove<P:Path<K>>(&mut self, path:P) -> Option<V> {
+        let path  = path.segments().map(Segment::into_key).collect_vec();
+        let value = self.data_mut().remove(SegmentIter(path.iter()))?;
+        self.notify(Event::Removed(path));
+        Some(value)
+    }
+}
+
+
+
+// ============
+// === Path ===
+// ============
+
+/// A path in the tree, given as a sequence of segments. Segments are looked up in the branches in
+/// their borrowed form when possible, so no keys are allocated unless new branches are created.
+///
+/// Paths are implemented for any iterable of values convertible to keys, for any iterator of
+/// segments wrapped in `SegmentIter`, and for dot-separated strings wrapped in `DottedPath`.
+pub trait Path<K:Eq+Hash> {
+    /// The type of the path segments.
+    type Segment : Segment<K>;
+    /// Iterator over the path segments.
+    type Segments : Iterator<Item=Self::Segment>;
+    /// Iterate over the path segments.
+    fn segments(self) -> Self::Segments;
+}
+
+/// A single segment of a `Path`, usable as a key of the tree branches. Segments are looked up in
+/// maps keyed by `K`, so they can be used with the branches of other trees as well.
+pub trait Segment<K:Eq+Hash> {
+    /// Convert the segment to an owned key.
+    fn into_key(self) -> K;
+
+    /// Check whether the segment is equal to the key.
+    fn matches(&self, key:&K) -> bool;
+
+    /// Get the value of the segment in the map.
+    fn get_in<'t,T,S:BuildHasher>(&self, map:&'t HashMap<K,T,S>) -> Option<&'t T>;
+
+    /// Get the mutable value of the segment in the map.
+    fn get_mut_in<'t,T,S:BuildHasher>(&self, map:&'t mut HashMap<K,T,S>) -> Option<&'t mut T>;
+
+    /// Remove the value of the segment from the map.
+    fn remove_in<T,S:BuildHasher>(&self, map:&mut HashMap<K,T,S>) -> Option<T>;
+
+    /// Get the branch of the segment.
+    fn lookup<'t,V,S:BuildHasher>
+    (&self, branches:&'t Branches<K,V,S>) -> Option<&'t HashMapTree<K,V,S>> {
+        self.get_in(branches)
+    }
+
+    /// Get the mutable branch of the segment.
+    fn lookup_mut<'t,V,S:BuildHasher>
+    (&self, branches:&'t mut Branches<K,V,S>) -> Option<&'t mut HashMapTree<K,V,S>> {
+        self.get_mut_in(branches)
+    }
+
+    /// Remove the branch of the segment.
+    fn remove_from<V,S:BuildHasher>
+    (&self, branches:&mut Branches<K,V,S>) -> Option<HashMapTree<K,V,S>> {
+        self.remove_in(branches)
+    }
+
+    /// Get the mutable branch of the segment, creating it with `cons` if it does not exist.
+    fn lookup_or_insert_with<V,S:BuildHasher,F:FnOnce()->HashMapTree<K,V,S>>
+    (self, branches:&mut Branches<K,V,S>, cons:F) -> &mut HashMapTree<K,V,S>;
+}
+
+
+// === Segment Impls ===
+
+/// Implements `Segment<$key>` for a borrowed segment type, looking it up by its borrowed form
+/// and converting it to an owned key only when a new branch is created.
+macro_rules! impl_borrowed_segment {
+    ([$($params:tt)*] $tp:ty => $key:ty, $borrowed:ty, $to_key:expr) => {
+        impl<$($params)*> Segment<$key> for $tp {
+            fn into_key(self) -> $key {
+                $to_key(self)
+            }
+
+            fn matches(&self, key:&$key) -> bool {
+                <$key as std::borrow::Borrow<$borrowed>>::borrow(key) == *self
+            }
+
+            fn get_in<'t,T,S:BuildHasher>(&self, map:&'t HashMap<$key,T,S>) -> Option<&'t T> {
+                map.get::<$borrowed>(self)
+            }
+
+            fn get_mut_in<'t,T,S:BuildHasher>
+            (&self, map:&'t mut HashMap<$key,T,S>) -> Option<&'t mut T> {
+                map.get_mut::<$borrowed>(self)
+            }
+
+            fn remove_in<T,S:BuildHasher>(&self, map:&mut HashMap<$key,T,S>) -> Option<T> {
+                map.remove::<$borrowed>(self)
+            }
+
+            fn lookup_or_insert_with<V,S:BuildHasher,F:FnOnce()->HashMapTree<$key,V,S>>
+            (self, branches:&mut Branches<$key,V,S>, cons:F) -> &mut HashMapTree<$key,V,S> {
+                if !branches.contains_key::<$borrowed>(self) {
+                    branches.insert($to_key(self),cons());
+                }
+                branches.get_mut::<$borrowed>(self).unwrap()
+            }
+        }
+    };
+}
+
+impl_borrowed_segment!(['a,K:Eq+Hash+Clone] &'a K => K, K, K::clone);
+impl_borrowed_segment!(['a] &'a str => String, str, str::to_owned);
+
+impl<K:Eq+Hash> Segment<K> for K {
+    fn into_key(self) -> K {
+        self
+    }
+
+    fn matches(&self, key:&K) -> bool {
+        self == key
+    }
+
+    fn get_in<'t,T,S:BuildHasher>(&self, map:&'t HashMap<K,T,S>) -> Option<&'t T> {
+        map.get(self)
+    }
+
+    fn get_mut_in<'t,T,S:BuildHasher>(&self, map:&'t mut HashMap<K,T,S>) -> Option<&'t mut T> {
+        map.get_mut(self)
+    }
+
+    fn remove_in<T,S:BuildHasher>(&self, map:&mut HashMap<K,T,S>) -> Option<T> {
+        map.remove(self)
+    }
+
+    fn lookup_or_insert_with<V,S:BuildHasher,F:FnOnce()->HashMapTree<K,V,S>>
+    (self, branches:&mut Branches<K,V,S>, cons:F) -> &mut HashMapTree<K,V,S> {
+        branches.entry(self).or_insert_with(cons)
+    }
+}
+
+
+// === Path Impls ===
+
+/// Iterator converting the segments of the iterator `I` to keys.
+pub type IntoKeys<I,K> = std::iter::Map<I,fn(<I as Iterator>::Item)->K>;
+
+/// Any iterable of values convertible to keys is a path. The segments are converted to owned keys
+/// before the lookup, so use `SegmentIter` to look up borrowed segments without allocating keys.
+impl<K,P> Path<K> for P
+where K:Eq+Hash, P:IntoIterator, P::Item:Into<K> {
+    type Segment  = K;
+    type Segments = IntoKeys<P::IntoIter,K>;
+    fn segments(self) -> Self::Segments { self.into_iter().map(Into::into) }
+}
+
+/// Any iterator of segments used as a `Path`. Unlike plain iterators, it does not convert the
+/// segments to keys, so for example `SegmentIter(path.iter())` looks up the branches by reference.
+#[derive(Clone,Copy,Debug)]
+pub struct SegmentIter<I>(pub I);
+
+impl<K:Eq+Hash,I:Iterator<Item:Segment<K>>> Path<K> for SegmentIter<I> {
+    type Segment  = I::Item;
+    type Segments = I;
+    fn segments(self) -> I { self.0 }
+}
+
+/// A string path with segments separated by dots, like `"app.gui"`. The empty string is the path
+/// of the root. Every dot separates two segments, so `"app."` is the path of an empty segment in
+/// the `"app"` branch, not of the `"app"` branch itself.
+#[derive(Clone,Copy,Debug)]
+pub struct DottedPath<'a>(pub &'a str);
+
+impl<'a> Path<String> for DottedPath<'a> {
+    type Segment  = &'a str;
+    type Segments = std::iter::Take<std::str::Split<'a,char>>;
+    fn segments(self) -> Self::Segments {
+        let count = if self.0.is_empty() { 0 } else { usize::MAX };
+        self.0.split('.').take(count)
+    }
+}
+
 
 
 // =============
@@ -386,6 +711,40 @@ where K : Eq + Hash,
 mod tests {
     use super::*;
 
+    #[test]
+    fn observing_changes() {
+        let events   = Rc::new(RefCell::new(Vec::new()));
+        let mut tree = Observed::new(HashMapTree::<i32,usize>::new());
+        let sink     = events.clone();
+        tree.observe(move |event| sink.borrow_mut().push(event.clone()));
+        tree.set(vec![1,2],1);
+        assert_eq!(tree.remove(vec![3]),None);
+        assert_eq!(tree.remove(vec![1]),Some(0));
+        assert_eq!(*events.borrow(),vec![Event::Set(vec![1,2]),Event::Removed(vec![1])]);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let tree        = HashMapTree::<String,usize>::from_iter(vec![(vec!["a".into()],1)]);
+        let mut history = History::new(tree.clone(),8);
+        history.set(DottedPath("a.b.c"),2);
+        history.set(DottedPath("a"),3);
+        history.checkpoint();
+        history.remove(DottedPath("a.b"));
+        history.remove(DottedPath("x"));
+        history.checkpoint();
+        let edited = history.clone().into_data();
+        assert_eq!(history.get(DottedPath("a")),Some(&3));
+        assert_eq!(history.get(DottedPath("a.b")),None);
+        assert!(history.undo());
+        assert_eq!(history.get(DottedPath("a.b.c")),Some(&2));
+        assert!(history.undo());
+        assert_eq!(*history,tree);
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(*history,edited);
+    }
+
     #[test]
     fn single_insert_get() {
         let value = "String";
@@ -454,4 +813,55 @@ mod tests {
             assert_eq!(output, val * 2);
         }
     }
+
+    #[test]
+    fn tree_like() {
+        let mut tree = HashMapTree::<i32,i32>::new();
+        tree.set(vec![1,2],3);
+        tree.set(vec![1,4],5);
+        let depths = tree.dfs().map(|(depth,node)| (node.value,depth)).collect::<HashMap<_,_>>();
+        assert_eq!(tree.node_count(),4);
+        assert_eq!(depths[&3],2);
+        assert_eq!(depths[&5],2);
+    }
+
+    #[test]
+    fn string_paths() {
+        let mut tree = HashMapTree::<String,i32>::new();
+        tree.set(DottedPath("app.gui"),1);
+        tree.set(vec!["app","gui","graph"],2);
+        tree.set(DottedPath(&"app.model".to_string()),3);
+        tree.set(DottedPath("app."),4);
+        let model = ["app".to_string(),"model".to_string()];
+        assert_eq!(tree.get(DottedPath("")),Some(&0));
+        assert_eq!(tree.get(DottedPath("app.gui")),Some(&1));
+        assert_eq!(tree.get(["app","gui","graph"]),Some(&2));
+        assert_eq!(tree.get(SegmentIter(model.iter())),Some(&3));
+        assert_eq!(tree.get(DottedPath("app")),Some(&0));
+        assert_eq!(tree.get(vec!["app",""]),Some(&4));
+        assert_eq!(tree.get(DottedPath("app.guide")),None);
+        assert_eq!(tree.remove(DottedPath("app.gui")),Some(1));
+        assert_eq!(tree.get(DottedPath("app.gui.graph")),None);
+        assert_eq!(tree.get(DottedPath("app")),Some(&0));
+    }
+
+    #[test]
+    fn iterator_paths() {
+        let mut tree = HashMapTree::<i64,i32>::new();
+        tree.set((1..4).map(|t:i32| t * 2),1);
+        assert_eq!(tree.get(vec![2_u8,4,6]),Some(&1));
+        assert_eq!(tree.get(SegmentIter([2,4].iter())),Some(&0));
+        assert_eq!(tree.get(SegmentIter(std::iter::once(2))),Some(&0));
+    }
+
+    #[test]
+    fn deep_drop() {
+        let mut tree = HashMapTree::<i32,i32>::new();
+        tree.set(vec![0;200_000],1);
+        assert_eq!(tree.remove(vec![0]),Some(0));
+        tree.set(vec![1;200_000],1);
+        let HashMapTree {value,branches} = tree;
+        assert_eq!(value,0);
+        branches.into_iter().for_each(|(_,branch)| iter_drop(branch));
+    }
 }
@@ -0,0 +1,197 @@
+//! Bounded undo and redo history of a data structure, based on a journal of changes.
+//!
+//! Instead of storing snapshots of the whole structure, the history stores the changes reverting
+//! the applied ones, so the memory it uses is proportional to the size of the changes, not to the
+//! size of the structure. Changes are grouped into steps by checkpoints, and every step is undone
+//! and redone as a whole.
+
+use crate::prelude::*;
+
+use std::collections::VecDeque;
+
+
+
+// ==================
+// === Reversible ===
+// ==================
+
+/// A data structure whose changes can be reverted.
+pub trait Reversible {
+    /// A change of the structure.
+    type Change;
+
+    /// Apply the change. Returns the change reverting it, or `None` if the structure did not
+    /// change.
+    fn apply(&mut self, change:Self::Change) -> Option<Self::Change>;
+}
+
+
+
+// ===============
+// === History ===
+// ===============
+
+/// A data structure along with the journal of its changes. See the module docs to learn more.
+///
+/// All the changes applied since the last checkpoint form a single step. Only the most recent
+/// `capacity` steps can be undone. Applying a change forgets the steps which can be redone.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "D:Clone, D::Change:Clone"))]
+#[derivative(Debug(bound = "D:Debug, D::Change:Debug"))]
+pub struct History<D:Reversible> {
+    data     : D,
+    capacity : usize,
+    /// Changes reverting the applied ones, in the order of application, since the last checkpoint.
+    pending  : Vec<D::Change>,
+    undo     : VecDeque<Vec<D::Change>>,
+    redo     : Vec<Vec<D::Change>>,
+}
+
+impl<D:Reversible> History<D> {
+    /// Constructor. The history keeps at most `capacity` steps which can be undone.
+    pub fn new(data:D, capacity:usize) -> Self {
+        let pending = default();
+        let undo    = default();
+        let redo    = default();
+        Self {data,capacity,pending,undo,redo}
+    }
+
+    /// Consume the history, returning the data structure.
+    pub fn into_data(self) -> D {
+        self.data
+    }
+
+    /// Apply the change, recording it in the current step. Returns false if the structure did
+    /// not change.
+    pub fn apply(&mut self, change:D::Change) -> bool {
+        match self.data.apply(change) {
+            None          => false,
+            Some(reverse) => {
+                self.pending.push(reverse);
+                self.redo.clear();
+                true
+            }
+        }
+    }
+
+    /// Finish the current step. Does nothing if no changes were applied since the last checkpoint.
+    pub fn checkpoint(&mut self) {
+        if !self.pending.is_empty() {
+            self.undo.push_back(mem::take(&mut self.pending));
+            while self.undo.len() > self.capacity {
+                self.undo.pop_front();
+            }
+        }
+    }
+
+    /// Check whether there is a step which can be undone, including the unfinished one.
+    pub fn can_undo(&self) -> bool {
+        !self.pending.is_empty() || !self.undo.is_empty()
+    }
+
+    /// Check whether there is a step which can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Revert the last step, finishing it first if needed. Returns false if there was nothing to
+    /// undo.
+    pub fn undo(&mut self) -> bool {
+        self.checkpoint();
+        match self.undo.pop_back() {
+            None       => false,
+            Some(step) => {
+                let step = self.revert(step);
+                self.redo.push(step);
+                true
+            }
+        }
+    }
+
+    /// Apply the last undone step again. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            None       => false,
+            Some(step) => {
+                let step = self.revert(step);
+                self.undo.push_back(step);
+                true
+            }
+        }
+    }
+
+    /// Forget all the steps, keeping the current state of the structure.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Apply the changes of the step in reverse order. Returns the step reverting them.
+    fn revert(&mut self, step:Vec<D::Change>) -> Vec<D::Change> {
+        let data = &mut self.data;
+        step.into_iter().rev().filter_map(|change| data.apply(change)).collect()
+    }
+}
+
+impl<D:Reversible> Deref for History<D> {
+    type Target = D;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A counter changed by adding signed values.
+    #[derive(Debug,Default)]
+    struct Counter(i32);
+
+    impl Reversible for Counter {
+        type Change = i32;
+        fn apply(&mut self, change:i32) -> Option<i32> {
+            self.0 += change;
+            (change != 0).as_some(-change)
+        }
+    }
+
+    #[test]
+    fn steps_are_undone_and_redone() {
+        let mut history = History::new(Counter::default(),2);
+        assert!(!history.apply(0));
+        assert!(!history.can_undo());
+        history.apply(1);
+        history.apply(2);
+        history.checkpoint();
+        history.apply(10);
+        assert!(history.undo());
+        assert_eq!(history.0,3);
+        assert!(history.undo());
+        assert_eq!(history.0,0);
+        assert!(!history.undo());
+        assert!(history.redo());
+        assert_eq!(history.0,3);
+        history.apply(100);
+        assert!(!history.can_redo());
+        assert_eq!(history.0,103);
+    }
+
+    #[test]
+    fn old_steps_are_forgotten() {
+        let mut history = History::new(Counter::default(),2);
+        for value in 1..=3 {
+            history.apply(value);
+            history.checkpoint();
+        }
+        while history.undo() {}
+        assert_eq!(history.0,1);
+    }
+}
@@ -0,0 +1,232 @@
+//! Allocator of `usize` identifiers, reusing the freed ones.
+//!
+//! The freed identifiers are stored in an [`IntervalTree`], so freeing and allocating long runs of
+//! identifiers takes memory and time proportional to the number of runs, not to their lengths.
+//! Freed identifiers directly below the smallest never allocated one are forgotten, so the tree
+//! stays small when the identifiers are freed in the reverse order of allocation.
+
+use crate::prelude::*;
+
+use crate::interval::Interval;
+use crate::interval_tree::IntervalTree;
+use crate::overflow;
+
+
+
+// ===================
+// === ReusePolicy ===
+// ===================
+
+/// The choice of the freed identifier returned by [`IdAllocator::allocate`].
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum ReusePolicy {
+    /// The smallest freed identifier, which keeps the allocated identifiers dense.
+    LowestFirst,
+    /// The most recently freed identifier, which is likely to still be in the caches of the
+    /// structures indexed by the identifiers.
+    MostRecentlyFreed,
+}
+
+impl Default for ReusePolicy {
+    fn default() -> Self {
+        Self::LowestFirst
+    }
+}
+
+
+
+// ===================
+// === IdAllocator ===
+// ===================
+
+/// Allocator of `usize` identifiers. See the module docs to learn more.
+///
+/// Ranges of identifiers are always allocated from the lowest freed run which is long enough,
+/// regardless of the [`ReusePolicy`].
+#[derive(Clone,Debug,Default)]
+pub struct IdAllocator {
+    policy : ReusePolicy,
+    /// The freed identifiers smaller than `next`.
+    free   : IntervalTree,
+    /// The smallest identifier which was never allocated, or was forgotten after being freed.
+    next   : usize,
+    /// The freed runs in the order of freeing, used by the `MostRecentlyFreed` policy. Runs may
+    /// contain identifiers which were allocated again since then.
+    recent : Vec<Interval<usize>>,
+}
+
+impl IdAllocator {
+    /// Constructor.
+    pub fn new(policy:ReusePolicy) -> Self {
+        Self {policy,..default()}
+    }
+
+    /// The policy of reusing the freed identifiers.
+    pub fn policy(&self) -> ReusePolicy {
+        self.policy
+    }
+
+    /// Number of allocated identifiers.
+    pub fn allocated_count(&self) -> usize {
+        self.next - self.free.item_count()
+    }
+
+    /// Check whether the identifier is allocated.
+    pub fn is_allocated(&self, id:usize) -> bool {
+        id < self.next && !self.free.contains(id)
+    }
+
+    /// Allocate a single identifier, reusing a freed one if possible.
+    ///
+    /// # Panics
+    /// Panics if all the `usize` values are allocated.
+    pub fn allocate(&mut self) -> usize {
+        let reused = match self.policy {
+            ReusePolicy::LowestFirst       => self.free.take_first_item(),
+            ReusePolicy::MostRecentlyFreed => self.take_recent(),
+        };
+        reused.unwrap_or_else(|| {
+            let id    = self.next;
+            self.next = overflow::add(id,1);
+            id
+        })
+    }
+
+    /// Allocate `count` consecutive identifiers. Returns an empty interval if `count` is 0.
+    ///
+    /// # Panics
+    /// Panics if there are not enough consecutive identifiers left.
+    pub fn allocate_range(&mut self, count:usize) -> Interval<usize> {
+        if count == 0 { return Interval {start:1,end:0} }
+        let len   = |interval:&Interval<usize>| interval.end - interval.start + 1;
+        let fits  = self.free.iter().find(|interval| len(interval) >= count);
+        let start = match fits {
+            Some(interval) => interval.start,
+            None           => {
+                let start = self.next;
+                self.next = overflow::add(start,count);
+                start
+            }
+        };
+        let end = start + count - 1;
+        self.free.remove_range(Interval {start,end});
+        Interval {start,end}
+    }
+
+    /// Free the identifier. Returns false if it was not allocated.
+    pub fn free(&mut self, id:usize) -> bool {
+        let freed = self.is_allocated(id);
+        if freed {
+            self.free.insert(id);
+            self.remember_freed(Interval {start:id,end:id});
+            self.forget_tail();
+        }
+        freed
+    }
+
+    /// Free all the allocated identifiers in the closed interval. Returns the number of freed
+    /// identifiers.
+    pub fn free_range(&mut self, range:Interval<usize>) -> usize {
+        let Interval {start,end} = range;
+        if start > end || start >= self.next { return 0 }
+        let range = Interval {start,end:end.min(self.next - 1)};
+        let freed = self.free.insert_interval(range);
+        if freed > 0 {
+            self.remember_freed(range);
+            self.forget_tail();
+        }
+        freed
+    }
+
+    /// Record the freed run for the `MostRecentlyFreed` policy. Other policies do not need it.
+    fn remember_freed(&mut self, run:Interval<usize>) {
+        if self.policy == ReusePolicy::MostRecentlyFreed { self.recent.push(run) }
+    }
+
+    /// Forget the freed identifiers directly below `next`, so they are allocated like new ones.
+    fn forget_tail(&mut self) {
+        if let Some(tail) = self.free.iter().last().filter(|t| t.end + 1 == self.next) {
+            self.free.remove_range(tail);
+            self.next = tail.start;
+        }
+        if self.free.is_empty() { self.recent.clear() }
+    }
+
+    /// Take the most recently freed identifier, skipping the ones allocated again since then.
+    fn take_recent(&mut self) -> Option<usize> {
+        while let Some(run) = self.recent.last_mut() {
+            let id = self.free.prev_item_at_or_before(run.end).filter(|id| *id >= run.start);
+            match id {
+                None     => { self.recent.pop(); }
+                Some(id) => {
+                    self.free.remove(id);
+                    if id == run.start { self.recent.pop(); } else { run.end = id - 1 }
+                    return Some(id)
+                }
+            }
+        }
+        self.free.take_first_item()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowest_freed_ids_are_reused_first() {
+        let mut ids = IdAllocator::default();
+        assert_eq!((0..5).map(|_| ids.allocate()).collect_vec(),vec![0,1,2,3,4]);
+        assert!(ids.free(3));
+        assert!(ids.free(1));
+        assert!(!ids.free(1));
+        assert!(!ids.free(7));
+        assert_eq!(ids.allocated_count(),3);
+        assert_eq!((ids.allocate(),ids.allocate(),ids.allocate()),(1,3,5));
+    }
+
+    #[test]
+    fn freed_runs_are_not_recorded_for_lowest_first_policy() {
+        let mut ids = IdAllocator::default();
+        ids.allocate_range(100);
+        for id in (0..100).step_by(2) { ids.free(id); }
+        ids.free_range(Interval {start:51,end:61});
+        assert!(ids.recent.is_empty());
+        assert_eq!(ids.allocate(),0);
+    }
+
+    #[test]
+    fn most_recently_freed_ids_are_reused_first() {
+        let mut ids = IdAllocator::new(ReusePolicy::MostRecentlyFreed);
+        ids.allocate_range(10);
+        ids.free(2);
+        ids.free_range(Interval {start:4,end:6});
+        ids.free(8);
+        assert_eq!((ids.allocate(),ids.allocate(),ids.allocate()),(8,6,5));
+        assert_eq!(ids.allocate_range(2),Interval {start:10,end:11});
+        assert_eq!((ids.allocate(),ids.allocate(),ids.allocate()),(4,2,12));
+    }
+
+    #[test]
+    fn ranges_are_allocated_in_the_lowest_fitting_gap() {
+        let mut ids = IdAllocator::default();
+        assert_eq!(ids.allocate_range(10),Interval {start:0,end:9});
+        ids.free_range(Interval {start:1,end:2});
+        ids.free_range(Interval {start:4,end:7});
+        assert_eq!(ids.allocate_range(3),Interval {start:4,end:6});
+        assert_eq!(ids.allocate_range(2),Interval {start:1,end:2});
+        assert_eq!(ids.allocate_range(2),Interval {start:10,end:11});
+        assert!(ids.allocate_range(0).start > ids.allocate_range(0).end);
+        assert_eq!(ids.free_range(Interval {start:7,end:100}),4);
+        assert_eq!(ids.allocated_count(),7);
+        assert_eq!(ids.allocate(),7);
+        assert!(ids.is_allocated(3));
+        assert!(!ids.is_allocated(8));
+    }
+}
@@ -3,6 +3,11 @@
 
 use crate::prelude::*;
 
+use crate::opt_vec;
+
+use std::collections::btree_map;
+use std::iter::FromIterator;
+
 
 
 // =============
@@ -47,6 +52,18 @@ impl<T> PartialEq for Index<T> {
     }
 }
 
+impl<T> PartialOrd for Index<T> {
+    fn partial_cmp(&self, other:&Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Index<T> {
+    fn cmp(&self, other:&Self) -> std::cmp::Ordering {
+        self.raw.cmp(&other.raw)
+    }
+}
+
 impl<T> From<Index<T>> for usize {
     fn from(t:Index<T>) -> Self {
         t.raw
@@ -82,3 +99,445 @@ impl<T> Display for Index<T> {
         write!(f,"{}",self.raw)
     }
 }
+
+
+
+// ================
+// === IndexMap ===
+// ================
+
+/// A map from typed indexes to values, stored in a vector indexed by the raw index values. It is
+/// meant to store auxiliary data of elements of indexable containers, like `OptVec`, when most of
+/// the elements have the data. Otherwise, use the `SparseIndexMap`.
+#[derive(Derivative)]
+#[derivative(Clone(bound="V:Clone"))]
+#[derivative(Debug(bound="V:Debug"))]
+#[derivative(Default(bound=""))]
+pub struct IndexMap<I,V> {
+    vec     : Vec<Option<V>>,
+    len     : usize,
+    phantom : PhantomData<I>,
+}
+
+impl<I:opt_vec::Index,V> IndexMap<I,V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Number of values in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all values from the map.
+    pub fn clear(&mut self) {
+        self.vec.clear();
+        self.len = 0;
+    }
+
+    /// Check whether the map contains a value for the index.
+    pub fn contains_key(&self, index:I) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Reference to the value of the index.
+    pub fn get(&self, index:I) -> Option<&V> {
+        self.vec.get(index.into())?.as_ref()
+    }
+
+    /// Mutable reference to the value of the index.
+    pub fn get_mut(&mut self, index:I) -> Option<&mut V> {
+        self.vec.get_mut(index.into())?.as_mut()
+    }
+
+    /// Set the value of the index, returning the previous one.
+    pub fn insert(&mut self, index:I, value:V) -> Option<V> {
+        self.entry(index).insert(value)
+    }
+
+    /// Remove the value of the index.
+    pub fn remove(&mut self, index:I) -> Option<V> {
+        let value = self.vec.get_mut(index.into())?.take();
+        if value.is_some() { self.len -= 1; }
+        value
+    }
+
+    /// Get the entry of the index for in-place manipulation. The vector is resized to contain the
+    /// index only when a value is inserted.
+    pub fn entry(&mut self, index:I) -> Entry<I,V> {
+        let vec = &mut self.vec;
+        let len = &mut self.len;
+        Entry {index,vec,len}
+    }
+
+    /// Iterator over the indexes and values, in the order of indexes.
+    pub fn iter(&self) -> impl Iterator<Item=(I,&V)> {
+        let values = self.vec.iter().enumerate();
+        values.filter_map(|(raw,value)| value.as_ref().map(|value| (raw.into(),value)))
+    }
+
+    /// Iterator over the indexes and mutable values, in the order of indexes.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=(I,&mut V)> {
+        let values = self.vec.iter_mut().enumerate();
+        values.filter_map(|(raw,value)| value.as_mut().map(|value| (raw.into(),value)))
+    }
+
+    /// Iterator over the indexes, in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item=I> + '_ {
+        self.iter().map(|(index,_)| index)
+    }
+
+    /// Iterator over the values, in the order of indexes.
+    pub fn values(&self) -> impl Iterator<Item=&V> {
+        self.vec.iter().filter_map(|value| value.as_ref())
+    }
+}
+
+
+// === Entry ===
+
+/// A slot of an `IndexMap` which may contain a value. See `IndexMap::entry` to learn more.
+#[derive(Debug)]
+pub struct Entry<'a,I,V> {
+    index : I,
+    vec   : &'a mut Vec<Option<V>>,
+    len   : &'a mut usize,
+}
+
+impl<'a,I:opt_vec::Index,V> Entry<'a,I,V> {
+    /// The index of this entry.
+    pub fn index(&self) -> I {
+        self.index
+    }
+
+    /// Reference to the value, if present.
+    pub fn get(&self) -> Option<&V> {
+        self.vec.get(self.index.into())?.as_ref()
+    }
+
+    /// Set the value, returning the previous one.
+    pub fn insert(self, value:V) -> Option<V> {
+        let (slot,len) = self.into_slot();
+        let old        = slot.replace(value);
+        if old.is_none() { *len += 1; }
+        old
+    }
+
+    /// Remove the value.
+    pub fn remove(self) -> Option<V> {
+        let old = self.vec.get_mut(self.index.into())?.take();
+        if old.is_some() { *self.len -= 1; }
+        old
+    }
+
+    /// Modify the value if present.
+    pub fn and_modify(self, f:impl FnOnce(&mut V)) -> Self {
+        if let Some(value) = self.vec.get_mut(self.index.into()).and_then(|t| t.as_mut()) {
+            f(value)
+        }
+        self
+    }
+
+    /// Reference to the value, inserting the provided one if not present.
+    pub fn or_insert(self, value:V) -> &'a mut V {
+        self.or_insert_with(|| value)
+    }
+
+    /// Reference to the value, inserting the result of `f` if not present.
+    pub fn or_insert_with(self, f:impl FnOnce() -> V) -> &'a mut V {
+        let (slot,len) = self.into_slot();
+        slot.get_or_insert_with(|| { *len += 1; f() })
+    }
+
+    /// Reference to the value, inserting the default one if not present.
+    pub fn or_default(self) -> &'a mut V where V:Default {
+        self.or_insert_with(default)
+    }
+
+    /// The slot of the value and the length of the map, resizing the vector to contain the slot.
+    fn into_slot(self) -> (&'a mut Option<V>,&'a mut usize) {
+        let raw = self.index.into();
+        if raw >= self.vec.len() { self.vec.resize_with(raw + 1,default) }
+        (&mut self.vec[raw],self.len)
+    }
+}
+
+
+// === Impls ===
+
+impl<I:opt_vec::Index,V> std::ops::Index<I> for IndexMap<I,V> {
+    type Output = V;
+    fn index(&self, index:I) -> &V {
+        self.get(index).unwrap_or_else(|| panic!("No value for the index {:?}.",index))
+    }
+}
+
+impl<I:opt_vec::Index,V> std::ops::IndexMut<I> for IndexMap<I,V> {
+    fn index_mut(&mut self, index:I) -> &mut V {
+        self.get_mut(index).unwrap_or_else(|| panic!("No value for the index {:?}.",index))
+    }
+}
+
+impl<I:opt_vec::Index,V> Extend<(I,V)> for IndexMap<I,V> {
+    fn extend<T:IntoIterator<Item=(I,V)>>(&mut self, iter:T) {
+        for (index,value) in iter { self.insert(index,value); }
+    }
+}
+
+impl<I:opt_vec::Index,V> FromIterator<(I,V)> for IndexMap<I,V> {
+    fn from_iter<T:IntoIterator<Item=(I,V)>>(iter:T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+
+
+// ======================
+// === SparseIndexMap ===
+// ======================
+
+/// A map from typed indexes to values, stored in a `BTreeMap`. Unlike `IndexMap`, its memory usage
+/// does not depend on the biggest index, so it is meant to store auxiliary data of a small subset
+/// of elements of indexable containers.
+#[derive(Derivative)]
+#[derivative(Clone(bound="V:Clone"))]
+#[derivative(Debug(bound="V:Debug"))]
+#[derivative(Default(bound=""))]
+pub struct SparseIndexMap<I,V> {
+    map     : BTreeMap<usize,V>,
+    phantom : PhantomData<I>,
+}
+
+impl<I:opt_vec::Index,V> SparseIndexMap<I,V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Number of values in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Remove all values from the map.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Check whether the map contains a value for the index.
+    pub fn contains_key(&self, index:I) -> bool {
+        self.map.contains_key(&index.into())
+    }
+
+    /// Reference to the value of the index.
+    pub fn get(&self, index:I) -> Option<&V> {
+        self.map.get(&index.into())
+    }
+
+    /// Mutable reference to the value of the index.
+    pub fn get_mut(&mut self, index:I) -> Option<&mut V> {
+        self.map.get_mut(&index.into())
+    }
+
+    /// Set the value of the index, returning the previous one.
+    pub fn insert(&mut self, index:I, value:V) -> Option<V> {
+        self.map.insert(index.into(),value)
+    }
+
+    /// Remove the value of the index.
+    pub fn remove(&mut self, index:I) -> Option<V> {
+        self.map.remove(&index.into())
+    }
+
+    /// Get the entry of the index for in-place manipulation.
+    pub fn entry(&mut self, index:I) -> SparseEntry<I,V> {
+        let entry = self.map.entry(index.into());
+        SparseEntry {index,entry}
+    }
+
+    /// Iterator over the indexes and values, in the order of indexes.
+    pub fn iter(&self) -> impl Iterator<Item=(I,&V)> {
+        self.map.iter().map(|(raw,value)| ((*raw).into(),value))
+    }
+
+    /// Iterator over the indexes and mutable values, in the order of indexes.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=(I,&mut V)> {
+        self.map.iter_mut().map(|(raw,value)| ((*raw).into(),value))
+    }
+
+    /// Iterator over the indexes, in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item=I> + '_ {
+        self.map.keys().map(|raw| (*raw).into())
+    }
+
+    /// Iterator over the values, in the order of indexes.
+    pub fn values(&self) -> impl Iterator<Item=&V> {
+        self.map.values()
+    }
+}
+
+
+// === SparseEntry ===
+
+/// An entry of a `SparseIndexMap` which may contain a value. See `SparseIndexMap::entry` to learn
+/// more.
+#[derive(Debug)]
+pub struct SparseEntry<'a,I,V> {
+    index : I,
+    entry : btree_map::Entry<'a,usize,V>,
+}
+
+impl<'a,I:Copy,V> SparseEntry<'a,I,V> {
+    /// The index of this entry.
+    pub fn index(&self) -> I {
+        self.index
+    }
+
+    /// Reference to the value, if present.
+    pub fn get(&self) -> Option<&V> {
+        match &self.entry {
+            btree_map::Entry::Occupied(entry) => Some(entry.get()),
+            btree_map::Entry::Vacant(_)       => None,
+        }
+    }
+
+    /// Set the value, returning the previous one.
+    pub fn insert(self, value:V) -> Option<V> {
+        match self.entry {
+            btree_map::Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            btree_map::Entry::Vacant(entry)       => { entry.insert(value); None }
+        }
+    }
+
+    /// Remove the value.
+    pub fn remove(self) -> Option<V> {
+        match self.entry {
+            btree_map::Entry::Occupied(entry) => Some(entry.remove()),
+            btree_map::Entry::Vacant(_)       => None,
+        }
+    }
+
+    /// Modify the value if present.
+    pub fn and_modify(self, f:impl FnOnce(&mut V)) -> Self {
+        let index = self.index;
+        let entry = self.entry.and_modify(f);
+        Self {index,entry}
+    }
+
+    /// Reference to the value, inserting the provided one if not present.
+    pub fn or_insert(self, value:V) -> &'a mut V {
+        self.entry.or_insert(value)
+    }
+
+    /// Reference to the value, inserting the result of `f` if not present.
+    pub fn or_insert_with(self, f:impl FnOnce() -> V) -> &'a mut V {
+        self.entry.or_insert_with(f)
+    }
+
+    /// Reference to the value, inserting the default one if not present.
+    pub fn or_default(self) -> &'a mut V where V:Default {
+        self.entry.or_default()
+    }
+}
+
+
+// === Impls ===
+
+impl<I:opt_vec::Index,V> std::ops::Index<I> for SparseIndexMap<I,V> {
+    type Output = V;
+    fn index(&self, index:I) -> &V {
+        self.get(index).unwrap_or_else(|| panic!("No value for the index {:?}.",index))
+    }
+}
+
+impl<I:opt_vec::Index,V> std::ops::IndexMut<I> for SparseIndexMap<I,V> {
+    fn index_mut(&mut self, index:I) -> &mut V {
+        self.get_mut(index).unwrap_or_else(|| panic!("No value for the index {:?}.",index))
+    }
+}
+
+impl<I:opt_vec::Index,V> Extend<(I,V)> for SparseIndexMap<I,V> {
+    fn extend<T:IntoIterator<Item=(I,V)>>(&mut self, iter:T) {
+        for (index,value) in iter { self.insert(index,value); }
+    }
+}
+
+impl<I:opt_vec::Index,V> FromIterator<(I,V)> for SparseIndexMap<I,V> {
+    fn from_iter<T:IntoIterator<Item=(I,V)>>(iter:T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sprite;
+    type SpriteIndex = Index<Sprite>;
+
+    #[test]
+    fn index_map() {
+        let mut map = IndexMap::<SpriteIndex,&str>::new();
+        assert_eq!(map.insert(3.into(),"c"),None);
+        assert_eq!(map.insert(1.into(),"a"),None);
+        assert_eq!(map.insert(3.into(),"cc"),Some("c"));
+        *map.entry(2.into()).or_default() = "b";
+        map.entry(1.into()).and_modify(|value| *value = "aa");
+        assert_eq!(map.len(),3);
+        assert_eq!(map.iter().map(|(index,value)| (index.raw,*value)).collect_vec(),
+                   vec![(1,"aa"),(2,"b"),(3,"cc")]);
+        assert_eq!(map.remove(2.into()),Some("b"));
+        assert_eq!(map.remove(7.into()),None);
+        assert_eq!(map.entry(3.into()).remove(),Some("cc"));
+        assert_eq!(map.keys().collect_vec(),vec![1.into()]);
+        assert_eq!(map.len(),1);
+    }
+
+    #[test]
+    fn index_map_grows_only_on_insertion() {
+        let mut map = IndexMap::<SpriteIndex,usize>::new();
+        let far     = SpriteIndex::new(1_000_000);
+        assert_eq!(map.entry(far).get(),None);
+        assert_eq!(map.entry(far).remove(),None);
+        map.entry(far).and_modify(|value| *value += 1);
+        assert_eq!(map.vec.len(),0);
+        *map.entry(far).and_modify(|value| *value += 1).or_insert(1) += 1;
+        assert_eq!(map.vec.len(),1_000_001);
+        assert_eq!(map[far],2);
+        assert_eq!(map.len(),1);
+    }
+
+    #[test]
+    fn sparse_index_map() {
+        let mut map = SparseIndexMap::<SpriteIndex,usize>::new();
+        *map.entry(1_000_000.into()).or_insert(1) += 1;
+        *map.entry(1_000_000.into()).or_insert(1) += 1;
+        map.insert(5.into(),5);
+        assert_eq!(map[1_000_000.into()],3);
+        assert_eq!(map.values().copied().collect_vec(),vec![5,3]);
+        assert_eq!(map.entry(5.into()).insert(6),Some(5));
+        assert_eq!(map.entry(6.into()).get(),None);
+        assert_eq!(map.len(),2);
+    }
+}
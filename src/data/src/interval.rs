@@ -0,0 +1,359 @@
+//! Intervals of values, shared by the interval-based data structures, like [`crate::diet`] and
+//! [`crate::interval_tree`]. Most of the operations are defined for intervals of `usize` values.
+
+use crate::prelude::*;
+
+use crate::overflow;
+use crate::overflow::OverflowError;
+
+use std::ops::RangeInclusive;
+
+
+
+// ================
+// === Interval ===
+// ================
+
+/// Closed interval. For example, [`Interval(1,2)`] means `[1,2]` in math.
+#[derive(Clone,Copy,Default,Eq,Hash,PartialEq)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct Interval<T=usize> {
+    pub start : T,
+    pub end   : T,
+}
+
+/// Constructor.
+#[allow(non_snake_case)]
+pub fn Interval(start:usize, end:usize) -> Interval {
+    Interval {start,end}
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl Interval {
+    /// Number of values in the interval. Closed intervals are never empty.
+    pub fn len(self) -> usize {
+        self.end - self.start + 1
+    }
+
+    /// Check whether the value is in the interval.
+    pub fn contains(self, value:usize) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Check whether the intervals have any common values.
+    pub fn intersects(self, other:Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Iterator over the values in the interval, in ascending order.
+    pub fn iter(self) -> RangeInclusive<usize> {
+        self.into()
+    }
+
+    /// The interval moved towards bigger values by the offset.
+    ///
+    /// # Panics
+    /// Panics if the end of the interval moved by the offset does not fit in `usize`.
+    pub fn shift_right(self, offset:usize) -> Self {
+        Interval(overflow::add(self.start,offset),overflow::add(self.end,offset))
+    }
+
+    /// The interval moved towards smaller values by the offset.
+    ///
+    /// # Panics
+    /// Panics if the start of the interval is smaller than the offset.
+    pub fn shift_left(self, offset:usize) -> Self {
+        Interval(overflow::sub(self.start,offset),overflow::sub(self.end,offset))
+    }
+
+    /// Like `shift_right`, but with an explicit overflow policy. Saturated ends may make the
+    /// interval shorter.
+    pub fn shift_right_with
+    (self, offset:usize, policy:overflow::Policy) -> Result<Self,OverflowError> {
+        Ok(Interval(policy.add(self.start,offset)?,policy.add(self.end,offset)?))
+    }
+
+    /// Like `shift_left`, but with an explicit overflow policy. Saturated ends may make the
+    /// interval shorter.
+    pub fn shift_left_with
+    (self, offset:usize, policy:overflow::Policy) -> Result<Self,OverflowError> {
+        Ok(Interval(policy.sub(self.start,offset)?,policy.sub(self.end,offset)?))
+    }
+
+    /// The values present in both intervals, or `None` if they are disjoint.
+    pub fn intersection(self, other:Self) -> Option<Self> {
+        let start = self.start.max(other.start);
+        let end   = self.end.min(other.end);
+        if start <= end { Some(Interval(start,end)) } else { None }
+    }
+
+    /// The values present in any of the intervals, or `None` if they are disjoint, so their union
+    /// is not an interval.
+    pub fn union(self, other:Self) -> Option<Self> {
+        let start = self.start.min(other.start);
+        let end   = self.end.max(other.end);
+        self.intersection(other).map(|_| Interval(start,end))
+    }
+
+    /// Like `union`, but also merges intervals which do not overlap but are adjacent, like `[1,2]`
+    /// and `[3,4]`.
+    pub fn union_if_adjacent(self, other:Self) -> Option<Self> {
+        let start    = self.start.min(other.start);
+        let end      = self.end.max(other.end);
+        // The end of an interval ending at `usize::MAX` has no successor, but touches everything.
+        let touches  = |l:Self,r:Self| l.end.checked_add(1).map_or(true,|next| next >= r.start);
+        let adjacent = touches(self,other) && touches(other,self);
+        if adjacent { Some(Interval(start,end)) } else { None }
+    }
+
+    /// The part of the interval within the bounds, or `None` if the interval is out of the bounds.
+    pub fn clamp(self, bounds:Self) -> Option<Self> {
+        self.intersection(bounds)
+    }
+}
+
+impl<T:Debug> Debug for Interval<T> {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Interval({:?},{:?})", self.start, self.end)
+    }
+}
+
+impl From<usize> for Interval {
+    fn from(t:usize) -> Self {
+        Interval(t,t)
+    }
+}
+
+impl From<(usize,usize)> for Interval {
+    fn from(t:(usize,usize)) -> Self {
+        Interval(t.0,t.1)
+    }
+}
+
+impl From<RangeInclusive<usize>> for Interval {
+    fn from(range:RangeInclusive<usize>) -> Self {
+        Interval(*range.start(),*range.end())
+    }
+}
+
+impl From<Interval> for RangeInclusive<usize> {
+    fn from(t:Interval) -> Self {
+        t.start ..= t.end
+    }
+}
+
+/// Generates non-empty intervals, with the end not smaller than the start.
+#[cfg(feature="arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Interval {
+    fn arbitrary(u:&mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let start  = usize::arbitrary(u)?;
+        let length = usize::arbitrary(u)?;
+        Ok(Interval(start,start.saturating_add(length)))
+    }
+}
+
+
+
+// =========================
+// === RightOpenInterval ===
+// =========================
+
+/// Right-open interval. For example, [`RightOpenInterval(1,3)`] means `[1,3)` in math. Unlike the
+/// closed [`Interval`], it can be empty, when its end is not bigger than its start.
+#[derive(Clone,Copy,Default,Eq,Hash,PartialEq)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct RightOpenInterval {
+    pub start : usize,
+    pub end   : usize,
+}
+
+/// Constructor.
+#[allow(non_snake_case)]
+pub fn RightOpenInterval(start:usize, end:usize) -> RightOpenInterval {
+    RightOpenInterval {start,end}
+}
+
+impl RightOpenInterval {
+    /// The closed interval containing the same values, or `None` if this interval is empty.
+    pub fn to_closed(self) -> Option<Interval> {
+        if self.start < self.end { Some(Interval(self.start,self.end - 1)) } else { None }
+    }
+
+    /// Number of values in the interval.
+    pub fn len(self) -> usize {
+        if self.is_empty() { 0 } else { self.end - self.start }
+    }
+
+    /// Check whether the interval has no values.
+    pub fn is_empty(self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Check whether the value is in the interval.
+    pub fn contains(self, value:usize) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Check whether the intervals have any common values. Empty intervals do not intersect
+    /// anything.
+    pub fn intersects(self, other:Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Iterator over the values in the interval, in ascending order.
+    pub fn iter(self) -> Range<usize> {
+        self.into()
+    }
+
+    /// The interval moved towards bigger values by the offset.
+    ///
+    /// # Panics
+    /// Panics if the end of the interval moved by the offset does not fit in `usize`.
+    pub fn shift_right(self, offset:usize) -> Self {
+        RightOpenInterval(overflow::add(self.start,offset),overflow::add(self.end,offset))
+    }
+
+    /// The interval moved towards smaller values by the offset.
+    ///
+    /// # Panics
+    /// Panics if the start of the interval is smaller than the offset.
+    pub fn shift_left(self, offset:usize) -> Self {
+        RightOpenInterval(overflow::sub(self.start,offset),overflow::sub(self.end,offset))
+    }
+
+    /// The values present in both intervals, or `None` if they have no common values.
+    pub fn intersection(self, other:Self) -> Option<Self> {
+        let start = self.start.max(other.start);
+        let end   = self.end.min(other.end);
+        if start < end { Some(RightOpenInterval(start,end)) } else { None }
+    }
+
+    /// The values present in any of the intervals, or `None` if they have no common values, so
+    /// their union is not an interval.
+    pub fn union(self, other:Self) -> Option<Self> {
+        let start = self.start.min(other.start);
+        let end   = self.end.max(other.end);
+        self.intersection(other).map(|_| RightOpenInterval(start,end))
+    }
+
+    /// Like `union`, but also merges intervals which do not overlap but are adjacent, like `[1,3)`
+    /// and `[3,5)`. Empty intervals are never merged.
+    pub fn union_if_adjacent(self, other:Self) -> Option<Self> {
+        let start    = self.start.min(other.start);
+        let end      = self.end.max(other.end);
+        let adjacent = self.end >= other.start && other.end >= self.start;
+        let merged   = adjacent && !self.is_empty() && !other.is_empty();
+        if merged { Some(RightOpenInterval(start,end)) } else { None }
+    }
+
+    /// The part of the interval within the bounds, or `None` if no value of the interval is within
+    /// the bounds.
+    pub fn clamp(self, bounds:Self) -> Option<Self> {
+        self.intersection(bounds)
+    }
+}
+
+impl Debug for RightOpenInterval {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RightOpenInterval({:?},{:?})", self.start, self.end)
+    }
+}
+
+impl From<(usize,usize)> for RightOpenInterval {
+    fn from(t:(usize,usize)) -> Self {
+        RightOpenInterval(t.0,t.1)
+    }
+}
+
+impl From<Range<usize>> for RightOpenInterval {
+    fn from(range:Range<usize>) -> Self {
+        RightOpenInterval(range.start,range.end)
+    }
+}
+
+impl From<RightOpenInterval> for Range<usize> {
+    fn from(t:RightOpenInterval) -> Self {
+        t.start .. t.end
+    }
+}
+
+impl From<Interval> for RightOpenInterval {
+    fn from(t:Interval) -> Self {
+        RightOpenInterval(t.start,t.end + 1)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closed_interval_arithmetic() {
+        let interval = Interval(2,5);
+        assert_eq!(interval.shift_right(3),Interval(5,8));
+        assert_eq!(interval.shift_left(2),Interval(0,3));
+        assert_eq!(interval.shift_left_with(3,overflow::Policy::Saturate),Ok(Interval(0,2)));
+        let error = interval.shift_right_with(usize::MAX - 3,overflow::Policy::Error).unwrap_err();
+        assert_eq!(error.lhs,5);
+        assert_eq!(interval.intersection(Interval(5,9)),Some(Interval(5,5)));
+        assert_eq!(interval.intersection(Interval(6,9)),None);
+        assert_eq!(interval.union(Interval(4,9)),Some(Interval(2,9)));
+        assert_eq!(interval.union(Interval(6,9)),None);
+        assert_eq!(RangeInclusive::from(interval),2..=5);
+        assert_eq!(Interval::from(2..=5),interval);
+    }
+
+    #[test]
+    fn right_open_interval_arithmetic() {
+        let interval = RightOpenInterval(2,5);
+        assert_eq!(interval.shift_right(3),RightOpenInterval(5,8));
+        assert_eq!(interval.intersection(RightOpenInterval(4,9)),Some(RightOpenInterval(4,5)));
+        assert_eq!(interval.intersection(RightOpenInterval(5,9)),None);
+        assert_eq!(interval.union(RightOpenInterval(0,3)),Some(RightOpenInterval(0,5)));
+        assert_eq!(interval.to_closed(),Some(Interval(2,4)));
+        assert_eq!(RightOpenInterval(2,2).to_closed(),None);
+        assert_eq!(RightOpenInterval::from(Interval(2,4)),interval);
+        assert_eq!(Range::from(interval),2..5);
+    }
+
+    #[test]
+    fn closed_interval_queries() {
+        let interval = Interval(2,4);
+        assert_eq!(interval.len(),3);
+        assert!(interval.contains(2) && interval.contains(4) && !interval.contains(5));
+        assert!(interval.intersects(Interval(4,6)));
+        assert!(!interval.intersects(Interval(5,6)));
+        assert_eq!(interval.union_if_adjacent(Interval(5,6)),Some(Interval(2,6)));
+        assert_eq!(interval.union_if_adjacent(Interval(0,1)),Some(Interval(0,4)));
+        assert_eq!(interval.union_if_adjacent(Interval(6,6)),None);
+        assert_eq!(interval.clamp(Interval(0,3)),Some(Interval(2,3)));
+        assert_eq!(interval.clamp(Interval(5,9)),None);
+        assert_eq!(interval.iter().collect_vec(),vec![2,3,4]);
+    }
+
+    #[test]
+    fn right_open_interval_queries() {
+        let interval = RightOpenInterval(2,5);
+        assert_eq!(interval.len(),3);
+        assert_eq!(RightOpenInterval(5,2).len(),0);
+        assert!(RightOpenInterval(2,2).is_empty());
+        assert!(interval.contains(2) && interval.contains(4) && !interval.contains(5));
+        assert!(interval.intersects(RightOpenInterval(4,6)));
+        assert!(!interval.intersects(RightOpenInterval(5,6)));
+        assert!(!interval.intersects(RightOpenInterval(3,3)));
+        let merged = Some(RightOpenInterval(2,7));
+        assert_eq!(interval.union_if_adjacent(RightOpenInterval(5,7)),merged);
+        assert_eq!(interval.union_if_adjacent(RightOpenInterval(6,7)),None);
+        assert_eq!(interval.union_if_adjacent(RightOpenInterval(5,5)),None);
+        assert_eq!(interval.clamp(RightOpenInterval(0,3)),Some(RightOpenInterval(2,3)));
+        assert_eq!(interval.iter().collect_vec(),vec![2,3,4]);
+    }
+}
@@ -0,0 +1,1389 @@
+//! A set of values stored as a sorted vector of disjoint, non-adjacent closed intervals.
+//!
+//! Unlike the [`crate::diet`] trees, the intervals are kept in a single flat vector, so neighbor
+//! intervals are always merged and every query is a single binary search. This makes it a good fit
+//! for sets of indexes which form long continuous runs, like free slots or dirty lines.
+//!
+//! Whole intervals can be inserted and removed with only two binary searches, one for each end of
+//! the interval.
+
+use crate::prelude::*;
+
+use crate::index;
+use crate::observed::Observable;
+use crate::observed::Observed;
+use crate::overflow;
+use crate::overflow::OverflowError;
+
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+pub use crate::interval::Interval;
+
+
+
+// ============
+// === Step ===
+// ============
+
+/// Values which can be stored in an [`IntervalTree`]. They are totally ordered, and every value
+/// has a well defined successor and predecessor, so adjacent intervals can be merged.
+pub trait Step : Copy + Ord {
+    /// The smallest value bigger than this one, or `None` if this is the biggest value.
+    fn successor(self) -> Option<Self>;
+    /// The biggest value smaller than this one, or `None` if this is the smallest value.
+    fn predecessor(self) -> Option<Self>;
+    /// Number of steps from this value to the `other` value, which is not smaller than this one, or
+    /// `None` if the number does not fit in `usize`.
+    fn distance(self, other:Self) -> Option<usize>;
+    /// The value `n` steps after this one, or `None` if there is no such value.
+    fn forward(self, n:usize) -> Option<Self>;
+}
+
+macro_rules! impl_step_for_integers {
+    ($($tp:ty : $unsigned:ty),*) => {$(
+        impl Step for $tp {
+            fn successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn predecessor(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            #[allow(trivial_numeric_casts)]
+            fn distance(self, other:Self) -> Option<usize> {
+                usize::try_from(other.wrapping_sub(self) as $unsigned).ok()
+            }
+
+            #[allow(trivial_numeric_casts)]
+            fn forward(self, n:usize) -> Option<Self> {
+                let fits = self.distance(Self::MAX).map_or(true,|max| n <= max);
+                fits.as_some_from(|| (self as $unsigned).wrapping_add(n as $unsigned) as Self)
+            }
+        }
+    )*};
+}
+
+impl_step_for_integers! {
+    u8:u8, u16:u16, u32:u32, u64:u64, u128:u128, usize:usize,
+    i8:u8, i16:u16, i32:u32, i64:u64, i128:u128, isize:usize
+}
+
+/// Code points of the surrogates are skipped, as they are not valid chars.
+impl Step for char {
+    fn successor(self) -> Option<Self> {
+        match self {
+            '\u{D7FF}' => Some('\u{E000}'),
+            _          => char::from_u32(self as u32 + 1),
+        }
+    }
+
+    fn predecessor(self) -> Option<Self> {
+        match self {
+            '\u{E000}' => Some('\u{D7FF}'),
+            _          => (self as u32).checked_sub(1).and_then(char::from_u32),
+        }
+    }
+
+    fn distance(self, other:Self) -> Option<usize> {
+        let surrogate_count  = 0x800;
+        let skips_surrogates = self <= '\u{D7FF}' && other >= '\u{E000}';
+        let distance         = usize::try_from(other as u32 - self as u32).ok()?;
+        Some(if skips_surrogates { distance - surrogate_count } else { distance })
+    }
+
+    fn forward(self, n:usize) -> Option<Self> {
+        let surrogate_count = 0x800;
+        let code            = (self as u32).checked_add(u32::try_from(n).ok()?)?;
+        let skips           = self <= '\u{D7FF}' && code > 0xD7FF;
+        let code            = if skips { code.checked_add(surrogate_count)? } else { code };
+        char::from_u32(code)
+    }
+}
+
+impl<T> Step for index::Index<T> {
+    fn successor(self) -> Option<Self> {
+        self.raw.successor().map(Self::new)
+    }
+
+    fn predecessor(self) -> Option<Self> {
+        self.raw.predecessor().map(Self::new)
+    }
+
+    fn distance(self, other:Self) -> Option<usize> {
+        self.raw.distance(other.raw)
+    }
+
+    fn forward(self, n:usize) -> Option<Self> {
+        self.raw.forward(n).map(Self::new)
+    }
+}
+
+
+
+// ====================
+// === IntervalTree ===
+// ====================
+
+/// A set of values, `usize` by default. See the module docs to learn more.
+///
+/// The set can optionally maintain the rank index, the number of values before every interval, so
+/// the values can be accessed by their position in logarithmic time. See `enable_rank_index` to
+/// learn more.
+///
+/// The number of values in the set is counted in `usize`, so the modifiers panic if it would not
+/// fit, for example when inserting the interval of all the `u64` values.
+#[derive(Clone,Derivative)]
+#[derivative(Default(bound=""),PartialEq(bound="T:PartialEq"),Eq(bound="T:Eq"))]
+pub struct IntervalTree<T=usize> {
+    vec        : Vec<Interval<T>>,
+    item_count : usize,
+    /// Number of values in the intervals before every interval, if the rank index is enabled.
+    #[derivative(PartialEq="ignore")]
+    rank_index : Option<Vec<usize>>,
+}
+
+impl<T:Step> IntervalTree<T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Number of values in the set.
+    pub fn item_count(&self) -> usize {
+        self.item_count
+    }
+
+    /// Number of disjoint intervals the values are stored in.
+    pub fn interval_count(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Remove all values from the set.
+    pub fn clear(&mut self) {
+        self.vec.clear();
+        self.item_count = 0;
+        self.update_rank_index(0);
+    }
+
+    /// Binary search for the interval containing the value. Returns `Ok` with the index of the
+    /// interval if found, or `Err` with the index at which an interval containing the value would
+    /// need to be inserted.
+    pub fn index(&self, t:T) -> Result<usize,usize> {
+        self.vec.binary_search_by(|interval| {
+            if      interval.end   < t { Ordering::Less }
+            else if interval.start > t { Ordering::Greater }
+            else                       { Ordering::Equal }
+        })
+    }
+
+    /// Check whether the value is in the set.
+    pub fn contains(&self, t:T) -> bool {
+        self.index(t).is_ok()
+    }
+
+    /// The smallest value in the set which is not smaller than the given one.
+    pub fn next_item_at_or_after(&self, t:T) -> Option<T> {
+        match self.index(t) {
+            Ok(_)      => Some(t),
+            Err(index) => self.vec.get(index).map(|interval| interval.start),
+        }
+    }
+
+    /// The biggest value in the set which is not bigger than the given one.
+    pub fn prev_item_at_or_before(&self, t:T) -> Option<T> {
+        match self.index(t) {
+            Ok(_)      => Some(t),
+            Err(index) => index.checked_sub(1).map(|index| self.vec[index].end),
+        }
+    }
+
+    /// Check whether all the values of the closed interval are in the set. As neighbor intervals
+    /// are always merged, it is the case only if a single interval of the set contains it. An
+    /// interval with the start bigger than the end contains no values, so it is always contained.
+    pub fn contains_range(&self, range:impl Into<Interval<T>>) -> bool {
+        let range = range.into();
+        range.start > range.end || match self.index(range.start) {
+            Err(_)    => false,
+            Ok(index) => range.end <= self.vec[index].end,
+        }
+    }
+
+    /// Check whether any value of the closed intervals is in the set. The intervals have to be
+    /// sorted by their starts, but they may overlap. Intervals with the start bigger than the end
+    /// are skipped. Takes time linear in the number of intervals of the set and of the slice.
+    pub fn intersects_any(&self, ranges:&[Interval<T>]) -> bool {
+        let mut intervals = self.vec.iter().peekable();
+        for range in ranges.iter().filter(|range| range.start <= range.end) {
+            while intervals.next_if(|interval| interval.end < range.start).is_some() {}
+            match intervals.peek() {
+                None                                          => return false,
+                Some(interval) if interval.start <= range.end => return true,
+                Some(_)                                       => {}
+            }
+        }
+        false
+    }
+
+    /// Insert the value, merging it with the neighbor intervals if possible.
+    pub fn insert(&mut self, t:T) {
+        if let Err(index) = self.index(t) {
+            let left          = index.checked_sub(1).map(|index| self.vec[index].end);
+            let right         = self.vec.get(index).map(|interval| interval.start);
+            let touches_left  = left.and_then(Step::successor) == Some(t);
+            let touches_right = right.is_some() && t.successor() == right;
+            match (touches_left,touches_right) {
+                (true,true) => {
+                    let right = self.vec.remove(index);
+                    self.vec[index - 1].end = right.end;
+                }
+                (true,false)  => self.vec[index - 1].end = t,
+                (false,true)  => self.vec[index].start   = t,
+                (false,false) => self.vec.insert(index,Interval {start:t,end:t}),
+            }
+            self.item_count = overflow::add(self.item_count,1);
+            self.update_rank_index(index.saturating_sub(1));
+        }
+    }
+
+    /// Insert all the values in the closed interval, merging it with the overlapping and adjacent
+    /// intervals. Returns the number of values which were not in the set before.
+    pub fn insert_interval(&mut self, interval:impl Into<Interval<T>>) -> usize {
+        let range = interval.into();
+        if range.start > range.end { return 0 }
+        let before = range.start.predecessor().unwrap_or(range.start);
+        let after  = range.end.successor().unwrap_or(range.end);
+        let first  = match self.index(before) { Ok(index) | Err(index) => index };
+        let last   = match self.index(after)  { Ok(index) => index + 1, Err(index) => index };
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.vec[first].start);
+            merged.end   = merged.end.max(self.vec[last - 1].end);
+        }
+        let absorbed = self.vec[first..last].iter().map(Self::interval_len).sum::<usize>();
+        let inserted = Self::interval_len(&merged) - absorbed;
+        self.vec.splice(first..last,iter::once(merged));
+        self.item_count = overflow::add(self.item_count,inserted);
+        self.update_rank_index(first);
+        inserted
+    }
+
+    /// Remove the value, splitting the interval containing it if the value is not at its end.
+    /// Returns false if the value was not in the set.
+    pub fn remove(&mut self, t:T) -> bool {
+        match self.index(t) {
+            Err(_)    => false,
+            Ok(index) => {
+                let interval = self.vec[index];
+                let before   = t.predecessor().filter(|_| interval.start < t);
+                let after    = t.successor().filter(|_| t < interval.end);
+                match (before,after) {
+                    (None,None)         => { self.vec.remove(index); }
+                    (Some(end),None)    => self.vec[index].end   = end,
+                    (None,Some(start))  => self.vec[index].start = start,
+                    (Some(end),Some(start)) => {
+                        self.vec[index].end = end;
+                        self.vec.insert(index + 1,Interval {start,end:interval.end});
+                    }
+                }
+                self.item_count -= 1;
+                self.update_rank_index(index);
+                true
+            }
+        }
+    }
+
+    /// Remove all the values in the closed interval, trimming the intervals overlapping its ends
+    /// and splitting the interval containing it if needed. Returns the number of removed values.
+    /// Only two binary searches are performed, regardless of the length of the interval.
+    pub fn remove_range(&mut self, range:Interval<T>) -> usize {
+        let (first,last) = self.overlapping(range);
+        if first == last { return 0 }
+        let removed = self.vec[first..last].iter().map(|interval| {
+            let start = interval.start.max(range.start);
+            let end   = interval.end.min(range.end);
+            Self::interval_len(&Interval {start,end})
+        }).sum::<usize>();
+        let head_start = self.vec[first].start;
+        let tail_end   = self.vec[last - 1].end;
+        let head_end   = range.start.predecessor().filter(|_| head_start < range.start);
+        let tail_start = range.end.successor().filter(|_| range.end < tail_end);
+        let head       = head_end.map(|end| Interval {start:head_start,end});
+        let tail       = tail_start.map(|start| Interval {start,end:tail_end});
+        self.vec.splice(first..last,head.into_iter().chain(tail));
+        self.item_count -= removed;
+        self.update_rank_index(first);
+        removed
+    }
+
+    /// The set of values present in any of the sets. Takes time linear in the number of intervals
+    /// of both sets.
+    pub fn union(&self, other:&Self) -> Self {
+        let mut vec = Vec::<Interval<T>>::with_capacity(self.vec.len() + other.vec.len());
+        let sorted  = self.vec.iter().merge_by(&other.vec,|t1,t2| t1.start <= t2.start);
+        for interval in sorted {
+            match vec.last_mut() {
+                Some(last) if last.end.successor().map_or(true,|next| next >= interval.start) =>
+                    last.end = last.end.max(interval.end),
+                _ => vec.push(*interval),
+            }
+        }
+        Self::from_intervals(vec)
+    }
+
+    /// Insert all the values of the other set. See `union` to learn more.
+    pub fn union_with(&mut self, other:&Self) {
+        self.replace(self.union(other));
+    }
+
+    /// The set of values present in both sets. Takes time linear in the number of intervals of both
+    /// sets.
+    pub fn intersection(&self, other:&Self) -> Self {
+        let mut vec   = Vec::new();
+        let mut left  = self.vec.iter().peekable();
+        let mut right = other.vec.iter().peekable();
+        while let (Some(t1),Some(t2)) = (left.peek(),right.peek()) {
+            let start = t1.start.max(t2.start);
+            let end   = t1.end.min(t2.end);
+            if start <= end { vec.push(Interval {start,end}) }
+            if t1.end <= t2.end { left.next(); } else { right.next(); }
+        }
+        Self::from_intervals(vec)
+    }
+
+    /// Keep only the values present in the other set. See `intersection` to learn more.
+    pub fn intersection_with(&mut self, other:&Self) {
+        self.replace(self.intersection(other));
+    }
+
+    /// The set of values present in this set, but not in the other one. Takes time linear in the
+    /// number of intervals of both sets.
+    pub fn difference(&self, other:&Self) -> Self {
+        let mut vec   = Vec::new();
+        let mut right = other.vec.iter().peekable();
+        for interval in &self.vec {
+            let mut start = Some(interval.start);
+            while let Some(from) = start {
+                match right.peek() {
+                    Some(t) if t.end < from => { right.next(); }
+                    Some(t) if t.start <= interval.end => {
+                        if let Some(end) = t.start.predecessor().filter(|_| from < t.start) {
+                            vec.push(Interval {start:from,end});
+                        }
+                        start = t.end.successor().filter(|next| *next <= interval.end);
+                    }
+                    _ => {
+                        vec.push(Interval {start:from,end:interval.end});
+                        start = None;
+                    }
+                }
+            }
+        }
+        Self::from_intervals(vec)
+    }
+
+    /// The set of values present in exactly one of the sets. Takes time linear in the number of
+    /// intervals of both sets.
+    pub fn symmetric_difference(&self, other:&Self) -> Self {
+        self.difference(other).union(&other.difference(self))
+    }
+
+    /// Split the set into the values smaller than the given one and the rest. An interval
+    /// containing both kinds of values is split in two. Both sets keep the rank index enabled if it
+    /// was.
+    pub fn split_at(mut self, t:T) -> (Self,Self) {
+        let index = match self.index(t) {
+            Err(index) => index,
+            Ok(index)  => {
+                let interval = self.vec[index];
+                match t.predecessor().filter(|_| interval.start < t) {
+                    None      => index,
+                    Some(end) => {
+                        self.vec[index].end = end;
+                        self.vec.insert(index + 1,Interval {start:t,end:interval.end});
+                        index + 1
+                    }
+                }
+            }
+        };
+        let upper_vec = self.vec.split_off(index);
+        let mut lower = Self::from_intervals(mem::take(&mut self.vec));
+        let mut upper = Self::from_intervals(upper_vec);
+        if self.rank_index.is_some() {
+            lower.enable_rank_index();
+            upper.enable_rank_index();
+        }
+        (lower,upper)
+    }
+
+    /// Constructor of a set from sorted, disjoint, non-adjacent intervals.
+    fn from_intervals(vec:Vec<Interval<T>>) -> Self {
+        let item_count = vec.iter().map(Self::interval_len).fold(0,overflow::add);
+        let rank_index = None;
+        Self {vec,item_count,rank_index}
+    }
+
+    /// Replace the values of the set with the values of the other one, keeping the rank index
+    /// enabled if it was.
+    fn replace(&mut self, other:Self) {
+        let indexed = self.rank_index.is_some();
+        *self = other;
+        if indexed { self.enable_rank_index() }
+    }
+
+    /// The range of indexes of the intervals having common values with the closed interval.
+    fn overlapping(&self, range:Interval<T>) -> (usize,usize) {
+        if range.start > range.end { return (0,0) }
+        let first = match self.index(range.start) { Ok(index) | Err(index) => index };
+        let last  = match self.index(range.end)   { Ok(index) => index + 1, Err(index) => index };
+        (first,last)
+    }
+
+    /// Remove and return the smallest value in the set.
+    pub fn take_first_item(&mut self) -> Option<T> {
+        let first = self.vec.first_mut()?;
+        let item  = first.start;
+        match item.successor() {
+            Some(next) if item != first.end => first.start = next,
+            _                               => { self.vec.remove(0); }
+        }
+        self.item_count -= 1;
+        self.update_rank_index(0);
+        Some(item)
+    }
+
+    /// Remove and return the biggest value in the set.
+    pub fn take_last_item(&mut self) -> Option<T> {
+        let last = self.vec.last_mut()?;
+        let item = last.end;
+        match item.predecessor() {
+            Some(previous) if item != last.start => last.end = previous,
+            _                                    => { self.vec.pop(); }
+        }
+        self.item_count -= 1;
+        self.update_rank_index(self.vec.len());
+        Some(item)
+    }
+
+    /// Start maintaining the rank index, so `rank` and `nth_item` take logarithmic time instead of
+    /// linear. The index makes modifications of the set take time linear in the number of the
+    /// intervals after the modified one.
+    pub fn enable_rank_index(&mut self) {
+        self.rank_index.get_or_insert_with(default);
+        self.update_rank_index(0);
+    }
+
+    /// Stop maintaining the rank index. See `enable_rank_index` to learn more.
+    pub fn disable_rank_index(&mut self) {
+        self.rank_index = None;
+    }
+
+    /// Number of values in the set smaller than the value.
+    pub fn rank(&self, t:T) -> usize {
+        match self.index(t) {
+            Ok(index)  => self.items_before(index) + Self::steps(self.vec[index].start,t),
+            Err(index) => self.items_before(index),
+        }
+    }
+
+    /// The value with `n` smaller values in the set, or `None` if the set has at most `n` values.
+    pub fn nth_item(&self, n:usize) -> Option<T> {
+        if n >= self.item_count { return None }
+        let (index,before) = match &self.rank_index {
+            Some(counts) => {
+                let index = counts.partition_point(|count| *count <= n) - 1;
+                (index,counts[index])
+            }
+            None => {
+                let mut before = 0;
+                let mut index  = 0;
+                loop {
+                    let len = Self::interval_len(&self.vec[index]);
+                    if before + len > n { break }
+                    before += len;
+                    index  += 1;
+                }
+                (index,before)
+            }
+        };
+        self.vec[index].start.forward(n - before)
+    }
+
+    /// Number of values in the intervals before the interval with the given index.
+    fn items_before(&self, index:usize) -> usize {
+        match &self.rank_index {
+            Some(counts) => counts.get(index).copied().unwrap_or(self.item_count),
+            None         => self.vec[..index].iter().map(Self::interval_len).sum(),
+        }
+    }
+
+    /// Recompute the rank index, if enabled, for the intervals starting from the given index.
+    fn update_rank_index(&mut self, index:usize) {
+        if let Some(counts) = &mut self.rank_index {
+            let index = index.min(counts.len()).min(self.vec.len());
+            counts.truncate(index);
+            let mut count = match index {
+                0 => 0,
+                _ => counts[index - 1] + Self::interval_len(&self.vec[index - 1]),
+            };
+            for interval in &self.vec[index..] {
+                counts.push(count);
+                count += Self::interval_len(interval);
+            }
+        }
+    }
+
+    /// Number of steps between the values of the set. It fits in `usize`, as the item count does.
+    fn steps(start:T, end:T) -> usize {
+        start.distance(end).expect("The number of values does not fit in usize.")
+    }
+
+    /// Number of values in the interval.
+    ///
+    /// # Panics
+    /// Panics if the number does not fit in `usize`, like for the interval of all the `u64` values.
+    fn interval_len(interval:&Interval<T>) -> usize {
+        overflow::add(Self::steps(interval.start,interval.end),1)
+    }
+
+    /// Convert the set to a vector of disjoint intervals in ascending order.
+    pub fn to_vec(&self) -> Vec<Interval<T>> {
+        self.vec.clone()
+    }
+
+    /// Iterator over the disjoint intervals in ascending order.
+    pub fn iter(&self) -> Iter<T> {
+        self.vec.iter().copied()
+    }
+
+    /// Iterator over the maximal intervals of values within the bounds which are not in the set,
+    /// in ascending order. For example, the gaps of the set of used slots within `(0,capacity-1)`
+    /// are the free slots. Takes logarithmic time to start the iteration.
+    pub fn gaps(&self, bounds:impl Into<Interval<T>>) -> Gaps<T> {
+        let bounds       = bounds.into();
+        let (first,last) = self.overlapping(bounds);
+        let intervals    = self.vec[first..last].iter();
+        let start        = Some(bounds.start).filter(|_| bounds.start <= bounds.end);
+        let end          = bounds.end;
+        Gaps {intervals,start,end}
+    }
+
+    /// Iterator over all the values in ascending order. Use `items().rev()` to iterate in
+    /// descending order.
+    pub fn items(&self) -> Items<T> {
+        let intervals = self.vec.iter();
+        let front     = None;
+        let back      = None;
+        let remaining = self.item_count;
+        Items {intervals,front,back,remaining}
+    }
+}
+
+
+// === Shifting ===
+
+impl IntervalTree {
+    /// Move all the values at or after the position by the delta, like the positions of text after
+    /// an edit. A positive delta is an insertion at the position, so an interval containing the
+    /// position is split. A negative delta is a deletion of the values before the position, so
+    /// they are removed and the moved values may merge with the preceding ones.
+    ///
+    /// # Panics
+    /// Panics if the deleted values would start before 0, or the moved values would not fit in
+    /// `usize`.
+    pub fn shift_from(&mut self, position:usize, delta:isize) {
+        let result = self.shift_from_with(position,delta,overflow::Policy::Panic);
+        result.expect("The panic policy never returns errors.")
+    }
+
+    /// Like `shift_from`, but with an explicit overflow policy. The set is left unchanged on
+    /// errors. Saturated values are clamped to the range of `usize`, so they may merge with each
+    /// other.
+    pub fn shift_from_with
+    (&mut self, position:usize, delta:isize, policy:overflow::Policy) -> Result<(),OverflowError> {
+        let distance = delta.unsigned_abs();
+        // Only the extreme values can overflow, so they are checked before the set is modified.
+        if delta < 0 {
+            policy.sub(position,distance)?;
+        } else if let Some(last) = self.vec.last().filter(|last| last.end >= position) {
+            policy.add(last.end,distance)?;
+        }
+        let indexed           = self.rank_index.is_some();
+        let (mut lower,upper) = mem::take(self).split_at(position);
+        if delta < 0 && position > 0 {
+            lower.remove_range(Interval {start:position.saturating_sub(distance),end:position - 1});
+        }
+        let shift = |t:usize| match delta < 0 {
+            true  => t.saturating_sub(distance),
+            false => t.saturating_add(distance),
+        };
+        let mut vec = lower.vec;
+        for interval in upper.vec {
+            let start   = shift(interval.start);
+            let end     = shift(interval.end);
+            let touches = |last:&Interval<usize>| last.end.saturating_add(1) >= start;
+            match vec.last_mut() {
+                Some(last) if touches(last) => last.end = last.end.max(end),
+                _                           => vec.push(Interval {start,end}),
+            }
+        }
+        *self = Self::from_intervals(vec);
+        if indexed { self.enable_rank_index() }
+        Ok(())
+    }
+}
+
+/// Iterator over the intervals of an [`IntervalTree`].
+pub type Iter<'a,T> = std::iter::Copied<std::slice::Iter<'a,Interval<T>>>;
+
+impl<'a,T:Step> IntoIterator for &'a IntervalTree<T> {
+    type Item     = Interval<T>;
+    type IntoIter = Iter<'a,T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of an [`IntervalTree`]. See [`IntervalTree::items`] to learn more.
+#[derive(Clone,Debug)]
+pub struct Items<'a,T> {
+    intervals : std::slice::Iter<'a,Interval<T>>,
+    /// The not yet visited part of the interval visited from the front.
+    front     : Option<Interval<T>>,
+    /// The not yet visited part of the interval visited from the back.
+    back      : Option<Interval<T>>,
+    remaining : usize,
+}
+
+impl<'a,T:Step> Iterator for Items<'a,T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(t) = take_start(&mut self.front) { self.remaining -= 1; return Some(t) }
+            match self.intervals.next() {
+                Some(interval) => self.front = Some(*interval),
+                None           => {
+                    let t = take_start(&mut self.back)?;
+                    self.remaining -= 1;
+                    return Some(t)
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        (self.remaining,Some(self.remaining))
+    }
+}
+
+impl<'a,T:Step> DoubleEndedIterator for Items<'a,T> {
+    fn next_back(&mut self) -> Option<T> {
+        loop {
+            if let Some(t) = take_end(&mut self.back) { self.remaining -= 1; return Some(t) }
+            match self.intervals.next_back() {
+                Some(interval) => self.back = Some(*interval),
+                None           => {
+                    let t = take_end(&mut self.front)?;
+                    self.remaining -= 1;
+                    return Some(t)
+                }
+            }
+        }
+    }
+}
+
+impl<'a,T:Step> ExactSizeIterator for Items<'a,T> {}
+
+/// Iterator over the gaps of an [`IntervalTree`]. See [`IntervalTree::gaps`] to learn more.
+#[derive(Clone,Debug)]
+pub struct Gaps<'a,T> {
+    /// The intervals having common values with the bounds.
+    intervals : std::slice::Iter<'a,Interval<T>>,
+    /// The start of the next gap, if any.
+    start     : Option<T>,
+    end       : T,
+}
+
+impl<'a,T:Step> Iterator for Gaps<'a,T> {
+    type Item = Interval<T>;
+    fn next(&mut self) -> Option<Interval<T>> {
+        loop {
+            let start = self.start?;
+            match self.intervals.next() {
+                Some(interval) => {
+                    self.start = interval.end.successor();
+                    if start < interval.start {
+                        let end = interval.start.predecessor()?;
+                        return Some(Interval {start,end})
+                    }
+                }
+                None => {
+                    self.start = None;
+                    return (start <= self.end).as_some(Interval {start,end:self.end})
+                }
+            }
+        }
+    }
+}
+
+/// Remove the smallest value from the interval, clearing the slot if it was the last one.
+fn take_start<T:Step>(slot:&mut Option<Interval<T>>) -> Option<T> {
+    let interval = slot.as_mut()?;
+    let t        = interval.start;
+    match t.successor() {
+        Some(next) if t < interval.end => interval.start = next,
+        _                              => *slot = None,
+    }
+    Some(t)
+}
+
+/// Remove the biggest value from the interval, clearing the slot if it was the last one.
+fn take_end<T:Step>(slot:&mut Option<Interval<T>>) -> Option<T> {
+    let interval = slot.as_mut()?;
+    let t        = interval.end;
+    match t.predecessor() {
+        Some(previous) if interval.start < t => interval.end = previous,
+        _                                    => *slot = None,
+    }
+    Some(t)
+}
+
+impl<T:Debug> Debug for IntervalTree<T> {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.vec).finish()
+    }
+}
+
+impl<T:Step> Extend<T> for IntervalTree<T> {
+    fn extend<I:IntoIterator<Item=T>>(&mut self, iter:I) {
+        for t in iter { self.insert(t) }
+    }
+}
+
+impl<T:Step> FromIterator<T> for IntervalTree<T> {
+    fn from_iter<I:IntoIterator<Item=T>>(iter:I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+
+
+// === Metrics ===
+
+/// Statistics of the layout of an [`IntervalTree`], used to decide when a pool of indexes should
+/// be defragmented. See [`IntervalTree::metrics`] to learn more.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Metrics<T> {
+    /// Number of values in the set.
+    pub item_count     : usize,
+    /// Number of disjoint intervals the values are stored in.
+    pub interval_count : usize,
+    /// The biggest interval of values between the smallest and the biggest value of the set which
+    /// are not in the set. The first one is chosen if there are several.
+    pub largest_gap    : Option<Interval<T>>,
+}
+
+impl<T> Metrics<T> {
+    /// The average number of values in an interval, or 0 if the set is empty.
+    pub fn average_interval_len(&self) -> f64 {
+        if self.interval_count == 0 { 0.0 }
+        else { self.item_count as f64 / self.interval_count as f64 }
+    }
+
+    /// The ratio of the number of intervals to the number of values, or 0 if the set is empty. It
+    /// is 1 if no values are adjacent, and tends to 0 as the values form longer intervals.
+    pub fn fragmentation(&self) -> f64 {
+        if self.item_count == 0 { 0.0 }
+        else { self.interval_count as f64 / self.item_count as f64 }
+    }
+}
+
+impl<T:Step> IntervalTree<T> {
+    /// Statistics of the layout of the set. The counts are maintained by all the modifiers, so
+    /// only the largest gap is computed, in a single pass over the intervals.
+    pub fn metrics(&self) -> Metrics<T> {
+        let item_count     = self.item_count;
+        let interval_count = self.vec.len();
+        let mut largest    = None::<(Option<usize>,Interval<T>)>;
+        for (left,right) in self.vec.iter().zip(self.vec.iter().skip(1)) {
+            // Gaps too long to be counted in `usize` are longer than all the other ones.
+            let len    = left.end.distance(right.start).map(|t| t - 1);
+            let longer = |other:Option<usize>| (len.is_none(),len) > (other.is_none(),other);
+            if largest.map_or(true,|(largest_len,_)| longer(largest_len)) {
+                let start = left.end.successor();
+                let end   = right.start.predecessor();
+                if let (Some(start),Some(end)) = (start,end) {
+                    largest = Some((len,Interval {start,end}))
+                }
+            }
+        }
+        let largest_gap = largest.map(|(_,gap)| gap);
+        Metrics {item_count,interval_count,largest_gap}
+    }
+}
+
+
+
+// === Serialization ===
+
+/// The set is serialized as the sequence of its intervals in ascending order.
+#[cfg(feature="serde")]
+impl<T:serde::Serialize> serde::Serialize for IntervalTree<T> {
+    fn serialize<S:serde::Serializer>(&self, serializer:S) -> Result<S::Ok,S::Error> {
+        serializer.collect_seq(&self.vec)
+    }
+}
+
+/// The intervals are validated, so invalid input can not break the invariants of the set. They
+/// have to be non-empty, sorted, and neither overlapping nor adjacent, as produced by `serialize`.
+#[cfg(feature="serde")]
+impl<'de,T:Step+serde::Deserialize<'de>> serde::Deserialize<'de> for IntervalTree<T> {
+    fn deserialize<D:serde::Deserializer<'de>>(deserializer:D) -> Result<Self,D::Error> {
+        let vec = <Vec<Interval<T>> as serde::Deserialize>::deserialize(deserializer)?;
+        let error = |message| Err(serde::de::Error::custom(message));
+        if vec.iter().any(|interval| interval.start > interval.end) {
+            return error("Empty interval in an interval tree.")
+        }
+        let separated = |(left,right):(&Interval<T>,&Interval<T>)| {
+            left.end.successor().map_or(false,|next| next < right.start)
+        };
+        if !vec.iter().zip(vec.iter().skip(1)).all(separated) {
+            return error("Unsorted or touching intervals in an interval tree.")
+        }
+        Ok(Self::from_intervals(vec))
+    }
+}
+
+
+
+// === Observing ===
+
+/// A change of an [`IntervalTree`], reported to the observers of [`Observed`] trees. Every event
+/// covers a maximal interval of values which changed at once.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Event<T> {
+    /// The values were inserted to the set.
+    Inserted(Interval<T>),
+    /// The values were removed from the set.
+    Removed(Interval<T>),
+}
+
+impl<T> Observable for IntervalTree<T> {
+    type Event = Event<T>;
+}
+
+/// Modifiers of the set notifying the observers. They behave like the methods of [`IntervalTree`]
+/// with the same names. No events are reported for values which were already inserted or removed.
+impl<T:Step> Observed<IntervalTree<T>> {
+    /// Insert the value.
+    pub fn insert(&mut self, t:T) {
+        if !self.contains(t) {
+            self.data_mut().insert(t);
+            self.notify(Event::Inserted(Interval {start:t,end:t}));
+        }
+    }
+
+    /// Insert all the values in the closed interval. Returns the number of inserted values.
+    pub fn insert_interval(&mut self, interval:impl Into<Interval<T>>) -> usize {
+        let interval = interval.into();
+        let gaps     = self.gaps(interval).collect_vec();
+        let inserted = self.data_mut().insert_interval(interval);
+        for gap in gaps { self.notify(Event::Inserted(gap)) }
+        inserted
+    }
+
+    /// Remove the value. Returns false if the value was not in the set.
+    pub fn remove(&mut self, t:T) -> bool {
+        let removed = self.data_mut().remove(t);
+        if removed { self.notify(Event::Removed(Interval {start:t,end:t})) }
+        removed
+    }
+
+    /// Remove all the values in the closed interval. Returns the number of removed values.
+    pub fn remove_range(&mut self, range:Interval<T>) -> usize {
+        let (first,last) = self.overlapping(range);
+        let removed      = self.vec[first..last].iter().map(|interval| {
+            let start = interval.start.max(range.start);
+            let end   = interval.end.min(range.end);
+            Interval {start,end}
+        }).collect_vec();
+        let count = self.data_mut().remove_range(range);
+        for interval in removed { self.notify(Event::Removed(interval)) }
+        count
+    }
+
+    /// Remove and return the smallest value in the set.
+    pub fn take_first_item(&mut self) -> Option<T> {
+        let item = self.data_mut().take_first_item()?;
+        self.notify(Event::Removed(Interval {start:item,end:item}));
+        Some(item)
+    }
+
+    /// Remove and return the biggest value in the set.
+    pub fn take_last_item(&mut self) -> Option<T> {
+        let item = self.data_mut().take_last_item()?;
+        self.notify(Event::Removed(Interval {start:item,end:item}));
+        Some(item)
+    }
+
+    /// Remove all values from the set.
+    pub fn clear(&mut self) {
+        let removed = mem::take(&mut self.data_mut().vec);
+        self.data_mut().clear();
+        for interval in removed { self.notify(Event::Removed(interval)) }
+    }
+}
+
+/// Generates sets of values below 256, so that both short and long intervals appear.
+#[cfg(feature="arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for IntervalTree {
+    fn arbitrary(u:&mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let values = Vec::<u8>::arbitrary(u)?;
+        Ok(values.into_iter().map(usize::from).collect())
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(tree:&IntervalTree, expected:&[(usize,usize)]) {
+        let expected = expected.iter().copied().map(Interval::from).collect_vec();
+        let count    = expected.iter().map(|t| t.end - t.start + 1).sum::<usize>();
+        assert_eq!(tree.to_vec(),expected);
+        assert_eq!(tree.item_count(),count);
+    }
+
+    #[test]
+    fn insert_merges_neighbors() {
+        let mut tree = IntervalTree::new();
+        tree.insert(5);
+        tree.insert(1);
+        check(&tree,&[(1,1),(5,5)]);
+        tree.insert(2);
+        tree.insert(4);
+        check(&tree,&[(1,2),(4,5)]);
+        tree.insert(4);
+        check(&tree,&[(1,2),(4,5)]);
+        tree.insert(3);
+        check(&tree,&[(1,5)]);
+        tree.insert(0);
+        tree.insert(7);
+        check(&tree,&[(0,5),(7,7)]);
+    }
+
+    #[test]
+    fn index() {
+        let tree : IntervalTree = vec![2,3,4,8,9].into_iter().collect();
+        assert_eq!(tree.index(0),Err(0));
+        assert_eq!(tree.index(3),Ok(0));
+        assert_eq!(tree.index(6),Err(1));
+        assert_eq!(tree.index(9),Ok(1));
+        assert_eq!(tree.index(10),Err(2));
+    }
+
+    #[test]
+    fn iteration() {
+        let tree : IntervalTree = vec![1,2,5,7,8].into_iter().collect();
+        let mut iter = tree.iter();
+        assert_eq!(iter.len(),3);
+        assert_eq!(iter.next(),Some(Interval(1,2)));
+        assert_eq!(iter.next_back(),Some(Interval(7,8)));
+        assert_eq!(iter.len(),1);
+        let reversed = (&tree).into_iter().rev().collect_vec();
+        assert_eq!(reversed,vec![Interval(7,8),Interval(5,5),Interval(1,2)]);
+    }
+
+    #[test]
+    fn item_iteration() {
+        let tree : IntervalTree = vec![1,2,3,5,8,9].into_iter().collect();
+        assert_eq!(tree.items().collect_vec(),vec![1,2,3,5,8,9]);
+        assert_eq!(tree.items().rev().collect_vec(),vec![9,8,5,3,2,1]);
+        let mut items = tree.items();
+        assert_eq!(items.len(),6);
+        assert_eq!((items.next(),items.next_back(),items.next_back()),(Some(1),Some(9),Some(8)));
+        assert_eq!(items.len(),3);
+        assert_eq!(items.next_back(),Some(5));
+        assert_eq!(items.next_back(),Some(3));
+        assert_eq!(items.next(),Some(2));
+        assert_eq!((items.next(),items.next_back(),items.len()),(None,None,0));
+        let tree : IntervalTree<u8> = vec![u8::MAX].into_iter().collect();
+        assert_eq!(tree.items().collect_vec(),vec![u8::MAX]);
+    }
+
+    #[test]
+    fn union() {
+        let tree1 : IntervalTree = vec![1,2,6,7,12,20].into_iter().collect();
+        let tree2 : IntervalTree = vec![3,5,8,10,11,12,13].into_iter().collect();
+        let union = tree1.union(&tree2);
+        check(&union,&[(1,3),(5,8),(10,13),(20,20)]);
+        assert_eq!(tree2.union(&tree1),union);
+        assert_eq!(union.union(&default()),union);
+        let mut tree = IntervalTree::new();
+        tree.union_with(&tree1);
+        assert_eq!(tree,tree1);
+        let max : IntervalTree = vec![usize::MAX - 1,usize::MAX].into_iter().collect();
+        assert_eq!(max.union(&max),max);
+    }
+
+    #[test]
+    fn intersection() {
+        let tree1 : IntervalTree = vec![1,2,3,6,7,8,12,20].into_iter().collect();
+        let tree2 : IntervalTree = vec![2,3,4,5,7,8,9,12,13].into_iter().collect();
+        let intersection = tree1.intersection(&tree2);
+        check(&intersection,&[(2,3),(7,8),(12,12)]);
+        assert_eq!(intersection.item_count(),5);
+        assert_eq!(tree2.intersection(&tree1),intersection);
+        assert!(tree1.intersection(&default()).is_empty());
+        let mut tree = tree1.clone();
+        tree.intersection_with(&tree1);
+        assert_eq!(tree,tree1);
+    }
+
+    #[test]
+    fn difference() {
+        let tree1 : IntervalTree = (0..=10).chain(15..=20).chain(iter::once(usize::MAX)).collect();
+        let tree2 : IntervalTree = vec![0,3,4,10,11,16,17,18,usize::MAX].into_iter().collect();
+        let difference = tree1.difference(&tree2);
+        check(&difference,&[(1,2),(5,9),(15,15),(19,20)]);
+        assert_eq!(difference.item_count(),10);
+        check(&tree2.difference(&tree1),&[(11,11)]);
+        assert_eq!(tree1.difference(&default()),tree1);
+        assert!(tree1.difference(&tree1).is_empty());
+        let symmetric_difference = tree1.symmetric_difference(&tree2);
+        check(&symmetric_difference,&[(1,2),(5,9),(11,11),(15,15),(19,20)]);
+        assert_eq!(tree2.symmetric_difference(&tree1),symmetric_difference);
+    }
+
+    #[test]
+    fn gaps() {
+        let tree : IntervalTree = vec![2,3,4,6,9,10].into_iter().collect();
+        let gaps = |bounds:(usize,usize)| tree.gaps(bounds).map(|t| (t.start,t.end)).collect_vec();
+        assert_eq!(gaps((0,12)),vec![(0,1),(5,5),(7,8),(11,12)]);
+        assert_eq!(gaps((3,9)),vec![(5,5),(7,8)]);
+        assert_eq!(gaps((6,6)),vec![]);
+        assert_eq!(gaps((7,7)),vec![(7,7)]);
+        assert_eq!(gaps((5,4)),vec![]);
+        let all = Interval {start:0,end:usize::MAX};
+        assert_eq!(IntervalTree::new().gaps(all).collect_vec(),vec![all]);
+        let max : IntervalTree = iter::once(usize::MAX).collect();
+        assert_eq!(max.gaps((usize::MAX,usize::MAX)).count(),0);
+    }
+
+    #[test]
+    fn metrics() {
+        let approx  = |value:f64, expected:f64| (value - expected).abs() < f64::EPSILON;
+        let metrics = IntervalTree::<usize>::new().metrics();
+        assert_eq!(metrics.largest_gap,None);
+        assert!(approx(metrics.average_interval_len(),0.0));
+        assert!(approx(metrics.fragmentation(),0.0));
+        let tree : IntervalTree = vec![1,2,3,6,10,11,15,18].into_iter().collect();
+        let metrics = tree.metrics();
+        assert_eq!(metrics.item_count,8);
+        assert_eq!(metrics.interval_count,5);
+        assert_eq!(metrics.largest_gap,Some(Interval {start:7,end:9}));
+        assert!(approx(metrics.average_interval_len(),1.6));
+        assert!(approx(metrics.fragmentation(),0.625));
+    }
+
+    #[test]
+    fn rank_and_select() {
+        let mut tree : IntervalTree = vec![2,3,4,7,10,11].into_iter().collect();
+        for indexed in &[false,true] {
+            if *indexed { tree.enable_rank_index() }
+            let items = (0..7).map(|n| tree.nth_item(n)).collect_vec();
+            assert_eq!(items,vec![Some(2),Some(3),Some(4),Some(7),Some(10),Some(11),None]);
+            let ranks = (0..13).map(|t| tree.rank(t)).collect_vec();
+            assert_eq!(ranks,vec![0,0,0,1,2,3,3,3,4,4,4,5,6]);
+        }
+        tree.insert(5);
+        tree.remove(10);
+        tree.insert_interval((20,22));
+        tree.union_with(&iter::once(30).collect());
+        tree.take_first_item();
+        assert_eq!(tree.nth_item(3),Some(7));
+        assert_eq!(tree.nth_item(5),Some(20));
+        assert_eq!(tree.rank(30),8);
+        tree.remove_range(Interval {start:0,end:20});
+        assert_eq!(tree.nth_item(0),Some(21));
+        assert_eq!(tree.rank(usize::MAX),3);
+        tree.clear();
+        assert_eq!(tree.nth_item(0),None);
+
+        let chars : IntervalTree<char> = vec!['\u{D7FE}','\u{E000}'].into_iter().collect();
+        assert_eq!(chars.nth_item(1),Some('\u{E000}'));
+        assert_eq!(chars.rank('\u{E001}'),2);
+        assert_eq!('\u{D7FE}'.forward(2),Some('\u{E000}'));
+        assert_eq!(i8::MIN.forward(255),Some(i8::MAX));
+        assert_eq!((-1_i8).forward(129),None);
+    }
+
+    #[test]
+    fn floor_and_ceiling() {
+        let tree : IntervalTree = vec![2,3,4,7].into_iter().collect();
+        let next = (0..9).map(|t| tree.next_item_at_or_after(t)).collect_vec();
+        let prev = (0..9).map(|t| tree.prev_item_at_or_before(t)).collect_vec();
+        let n    = None;
+        assert_eq!(next,vec![Some(2),Some(2),Some(2),Some(3),Some(4),Some(7),Some(7),Some(7),n]);
+        assert_eq!(prev,vec![n,n,Some(2),Some(3),Some(4),Some(4),Some(4),Some(7),Some(7)]);
+    }
+
+    #[test]
+    fn intersects_any() {
+        let tree  : IntervalTree = vec![2,3,4,9].into_iter().collect();
+        let range = |start,end| Interval {start,end};
+        assert!(tree.intersects_any(&[range(0,1),range(4,6)]));
+        assert!(tree.intersects_any(&[range(0,1),range(1,2)]));
+        assert!(tree.intersects_any(&[range(5,6),range(5,12)]));
+        assert!(!tree.intersects_any(&[range(0,1),range(5,8),range(10,12)]));
+        assert!(!tree.intersects_any(&[range(3,2)]));
+        assert!(!tree.intersects_any(&[]));
+        assert!(!IntervalTree::new().intersects_any(&[range(0,1)]));
+    }
+
+    #[test]
+    fn split_at() {
+        let tree : IntervalTree = vec![2,3,4,7,9].into_iter().collect();
+        let (lower,upper) = tree.clone().split_at(3);
+        check(&lower,&[(2,2)]);
+        check(&upper,&[(3,4),(7,7),(9,9)]);
+        assert_eq!((lower.item_count(),upper.item_count()),(1,4));
+        let (lower,upper) = tree.clone().split_at(7);
+        check(&lower,&[(2,4)]);
+        check(&upper,&[(7,7),(9,9)]);
+        let (lower,upper) = tree.clone().split_at(0);
+        assert!(lower.is_empty());
+        assert_eq!(upper,tree);
+        let mut indexed = tree;
+        indexed.enable_rank_index();
+        let (lower,upper) = indexed.split_at(8);
+        assert_eq!((lower.nth_item(3),upper.nth_item(0)),(Some(7),Some(9)));
+    }
+
+    #[test]
+    fn shift_from() {
+        let tree : IntervalTree = vec![1,2,3,4,8,9,12].into_iter().collect();
+        let shifted = |position:usize, delta:isize| {
+            let mut tree = tree.clone();
+            tree.shift_from(position,delta);
+            tree
+        };
+        check(&shifted(3,2),&[(1,2),(5,6),(10,11),(14,14)]);
+        check(&shifted(0,0),&[(1,4),(8,9),(12,12)]);
+        check(&shifted(8,-3),&[(1,6),(9,9)]);
+        check(&shifted(10,-2),&[(1,4),(10,10)]);
+        let mut saturated = tree.clone();
+        assert_eq!(saturated.shift_from_with(2,-5,overflow::Policy::Saturate),Ok(()));
+        check(&saturated,&[(0,0),(3,4),(7,7)]);
+        let mut max : IntervalTree = vec![5,usize::MAX - 1].into_iter().collect();
+        let error = max.shift_from_with(4,2,overflow::Policy::Error).unwrap_err();
+        assert_eq!((error.lhs,error.rhs),(usize::MAX - 1,2));
+        check(&max,&[(5,5),(usize::MAX - 1,usize::MAX - 1)]);
+        assert_eq!(max.shift_from_with(4,2,overflow::Policy::Saturate),Ok(()));
+        check(&max,&[(7,7),(usize::MAX,usize::MAX)]);
+        assert_eq!(max.shift_from_with(9,-1,overflow::Policy::Error),Ok(()));
+        check(&max,&[(7,7),(usize::MAX - 1,usize::MAX - 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn shifting_before_zero_panics() {
+        let mut tree : IntervalTree = vec![1,2].into_iter().collect();
+        tree.shift_from(2,-3);
+    }
+
+    #[test]
+    #[cfg(feature="serde")]
+    fn serialization() {
+        let tree : IntervalTree = vec![1,2,3,6].into_iter().collect();
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(json,r#"[{"start":1,"end":3},{"start":6,"end":6}]"#);
+        assert_eq!(serde_json::from_str::<IntervalTree>(&json).unwrap(),tree);
+        assert_eq!(serde_json::from_str::<IntervalTree>(&json).unwrap().item_count(),4);
+        let invalid = |json| serde_json::from_str::<IntervalTree>(json).is_err();
+        assert!(invalid(r#"[{"start":3,"end":1}]"#));
+        assert!(invalid(r#"[{"start":6,"end":6},{"start":1,"end":3}]"#));
+        assert!(invalid(r#"[{"start":1,"end":3},{"start":3,"end":6}]"#));
+        assert!(invalid(r#"[{"start":1,"end":3},{"start":4,"end":6}]"#));
+    }
+
+    #[test]
+    fn membership() {
+        let tree : IntervalTree = vec![2,3,4,6].into_iter().collect();
+        assert!(tree.contains(3));
+        assert!(!tree.contains(5));
+        assert!(tree.contains_range((2,4)));
+        assert!(tree.contains_range(6..=6));
+        assert!(!tree.contains_range((3,6)));
+        assert!(!tree.contains_range((0,2)));
+        assert!(tree.contains_range((9,8)));
+    }
+
+    #[test]
+    fn take_items() {
+        let mut tree : IntervalTree = vec![1,2,5].into_iter().collect();
+        assert_eq!(tree.take_first_item(),Some(1));
+        check(&tree,&[(2,2),(5,5)]);
+        assert_eq!(tree.take_last_item(),Some(5));
+        check(&tree,&[(2,2)]);
+        assert_eq!(tree.take_last_item(),Some(2));
+        assert_eq!(tree.take_first_item(),None);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn remove_splits_intervals() {
+        let mut tree : IntervalTree = (1..=9).collect();
+        assert!(tree.remove(5));
+        check(&tree,&[(1,4),(6,9)]);
+        assert!(!tree.remove(5));
+        assert!(tree.remove(1));
+        assert!(tree.remove(9));
+        check(&tree,&[(2,4),(6,8)]);
+        assert!(tree.remove(3));
+        assert!(tree.remove(2));
+        assert!(tree.remove(4));
+        check(&tree,&[(6,8)]);
+        tree.insert(5);
+        check(&tree,&[(5,8)]);
+    }
+
+    #[test]
+    fn insert_interval_merges_neighbors() {
+        let mut tree : IntervalTree = vec![1,2,5,9,10,14].into_iter().collect();
+        assert_eq!(tree.insert_interval((3,4)),2);
+        check(&tree,&[(1,5),(9,10),(14,14)]);
+        assert_eq!(tree.insert_interval((7,12)),4);
+        check(&tree,&[(1,5),(7,12),(14,14)]);
+        assert_eq!(tree.insert_interval((8,9)),0);
+        assert_eq!(tree.insert_interval((5,2)),0);
+        assert_eq!(tree.insert_interval(0..=20),9);
+        check(&tree,&[(0,20)]);
+        assert_eq!(tree.insert_interval((22,usize::MAX)),usize::MAX - 21);
+        assert_eq!(tree.interval_count(),2);
+
+        let mut tree : IntervalTree<i8> = vec![-128,127].into_iter().collect();
+        assert_eq!(tree.insert_interval(Interval {start:-127,end:126}),254);
+        assert_eq!(tree.to_vec(),vec![Interval {start:-128,end:127}]);
+    }
+
+    #[test]
+    fn remove_range_trims_and_splits_intervals() {
+        let values       = (1..=9).chain(vec![12,13,15,16,17,20]);
+        let mut tree : IntervalTree = values.collect();
+        assert_eq!(tree.remove_range(Interval(4,6)),3);
+        check(&tree,&[(1,3),(7,9),(12,13),(15,17),(20,20)]);
+        assert_eq!(tree.remove_range(Interval(8,16)),6);
+        check(&tree,&[(1,3),(7,7),(17,17),(20,20)]);
+        assert_eq!(tree.remove_range(Interval(18,19)),0);
+        assert_eq!(tree.remove_range(Interval(5,2)),0);
+        assert_eq!(tree.remove_range(Interval(0,3)),3);
+        check(&tree,&[(7,7),(17,17),(20,20)]);
+        assert_eq!(tree.remove_range(Interval(7,usize::MAX)),3);
+        assert!(tree.is_empty());
+
+        let mut tree : IntervalTree<i8> = (i8::MIN..=i8::MAX).collect();
+        assert_eq!(tree.remove_range(Interval {start:-100,end:100}),201);
+        assert_eq!(tree.item_count(),55);
+        let mut tree : IntervalTree<char> = ('\u{D7F0}'..='\u{E00F}').collect();
+        assert_eq!(tree.remove_range(Interval {start:'\u{D7F8}',end:'\u{E007}'}),16);
+        assert_eq!(tree.interval_count(),2);
+    }
+
+    #[test]
+    fn observing_changes() {
+        let events   = Rc::new(RefCell::new(Vec::new()));
+        let mut tree = Observed::new(IntervalTree::<usize>::new());
+        let sink     = events.clone();
+        tree.observe(move |event| sink.borrow_mut().push(*event));
+        tree.insert(3);
+        tree.insert(3);
+        tree.insert_interval((1,9));
+        tree.remove_range(Interval(0,2));
+        tree.remove(5);
+        tree.take_last_item();
+        tree.clear();
+        let expected = vec![
+            Event::Inserted(Interval(3,3)),
+            Event::Inserted(Interval(1,2)),
+            Event::Inserted(Interval(4,9)),
+            Event::Removed(Interval(1,2)),
+            Event::Removed(Interval(5,5)),
+            Event::Removed(Interval(9,9)),
+            Event::Removed(Interval(3,4)),
+            Event::Removed(Interval(6,8)),
+        ];
+        assert_eq!(*events.borrow(),expected);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn other_value_types() {
+        let mut tree : IntervalTree<u64> = vec![u64::MAX,u64::MAX - 1,0].into_iter().collect();
+        let last = Interval {start:u64::MAX - 1,end:u64::MAX};
+        assert_eq!(tree.to_vec(),vec![Interval {start:0,end:0},last]);
+        assert_eq!(tree.take_last_item(),Some(u64::MAX));
+
+        let tree : IntervalTree<char> = "abcxz\u{D7FF}\u{E000}".chars().collect();
+        assert_eq!(tree.interval_count(),4);
+        assert_eq!(tree.index('\u{E000}'),tree.index('\u{D7FF}'));
+
+        let mut tree = IntervalTree::<index::Index<()>>::new();
+        tree.extend(vec![index::Index::new(3),index::Index::new(2)]);
+        assert_eq!(tree.interval_count(),1);
+        assert_eq!(tree.take_first_item(),Some(index::Index::new(2)));
+    }
+
+    #[test]
+    fn distances_are_not_truncated() {
+        assert_eq!((-5_i64).distance(5),Some(10));
+        assert_eq!(0_u128.distance(u128::MAX),None);
+        assert_eq!(0_usize.distance(usize::MAX),Some(usize::MAX));
+        assert_eq!(0_u128.forward(usize::MAX),Some(usize::MAX as u128));
+        assert_eq!(u128::MAX.forward(1),None);
+    }
+
+    #[test]
+    fn full_range_intervals() {
+        let mut tree = IntervalTree::<u16>::new();
+        assert_eq!(tree.insert_interval(Interval {start:0,end:u16::MAX}),1 << 16);
+        assert_eq!(tree.remove_range(Interval {start:1,end:u16::MAX - 1}),(1 << 16) - 2);
+        assert_eq!(tree.item_count(),2);
+
+        let mut tree = IntervalTree::<usize>::new();
+        assert_eq!(tree.insert_interval((1,usize::MAX)),usize::MAX);
+        tree.enable_rank_index();
+        assert_eq!(tree.rank(usize::MAX),usize::MAX - 1);
+        assert_eq!(tree.nth_item(usize::MAX - 1),Some(usize::MAX));
+        assert_eq!(tree.remove_range(Interval(0,usize::MAX)),usize::MAX);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn counting_more_than_usize_values_panics() {
+        let mut tree = IntervalTree::<usize>::new();
+        tree.insert_interval((1,usize::MAX));
+        tree.insert(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in usize")]
+    fn inserting_too_long_intervals_panics() {
+        IntervalTree::<u128>::new().insert_interval(Interval {start:0,end:u128::MAX});
+    }
+}
@@ -1,7 +1,6 @@
 //! Library of general data structures.
 
 #![feature(associated_type_bounds)]
-#![feature(test)]
 #![feature(trait_alias)]
 
 #![deny(unconditional_recursion)]
@@ -14,11 +13,28 @@
 #![warn(unsafe_code)]
 #![warn(unused_import_braces)]
 
+pub mod buffer;
+pub mod component_graph;
 pub mod dependency_graph;
 pub mod hash_map_tree;
+pub mod history;
+pub mod id_allocator;
 pub mod index;
+pub mod interval;
+pub mod interval_tree;
 pub mod diet;
+pub mod observed;
 pub mod opt_vec;
+pub mod ordered_opt_vec;
+pub mod overflow;
+pub mod radix_tree;
+pub mod rle;
+pub mod seg_vec;
+pub mod seq_id;
+pub mod sorted_vec;
+pub mod succinct_tree;
 pub mod text;
+pub mod tree;
+pub mod version_vector;
 
 pub use enso_prelude as prelude;
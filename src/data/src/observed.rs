@@ -0,0 +1,90 @@
+//! Data structures notifying observers about their changes.
+//!
+//! An [`Observed`] structure can be read like the wrapped one, but it has to be modified through
+//! the methods of the wrapper, which call the observers after every change. It allows connecting
+//! the structures to reactive code, like FRP networks, without wrapping every call site which
+//! modifies them.
+
+use crate::prelude::*;
+
+
+
+// ==================
+// === Observable ===
+// ==================
+
+/// A data structure which can be wrapped in [`Observed`].
+pub trait Observable {
+    /// The description of a change of the structure.
+    type Event;
+}
+
+
+
+// ================
+// === Observed ===
+// ================
+
+/// An observer of the changes of a structure of type `D`.
+pub type Observer<D> = Box<dyn FnMut(&<D as Observable>::Event)>;
+
+/// A data structure calling the observers after every change. See the module docs to learn more.
+pub struct Observed<D:Observable> {
+    data      : D,
+    observers : Vec<Observer<D>>,
+}
+
+impl<D:Observable> Observed<D> {
+    /// Constructor. The structure has no observers.
+    pub fn new(data:D) -> Self {
+        let observers = default();
+        Self {data,observers}
+    }
+
+    /// Consume the wrapper, returning the data structure.
+    pub fn into_data(self) -> D {
+        self.data
+    }
+
+    /// Add the observer. Observers are called in the order in which they were added.
+    pub fn observe(&mut self, observer:impl FnMut(&D::Event) + 'static) {
+        self.observers.push(Box::new(observer))
+    }
+
+    /// Remove all the observers.
+    pub fn clear_observers(&mut self) {
+        self.observers.clear()
+    }
+
+    /// Mutable access to the data structure, for the modifiers of the wrapper.
+    pub(crate) fn data_mut(&mut self) -> &mut D {
+        &mut self.data
+    }
+
+    /// Call all the observers with the event.
+    pub(crate) fn notify(&mut self, event:D::Event) {
+        for observer in &mut self.observers {
+            observer(&event)
+        }
+    }
+}
+
+impl<D:Observable+Default> Default for Observed<D> {
+    fn default() -> Self {
+        Self::new(default())
+    }
+}
+
+impl<D:Observable> Deref for Observed<D> {
+    type Target = D;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<D:Observable+Debug> Debug for Observed<D> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        let observers = self.observers.len();
+        f.debug_struct("Observed").field("data",&self.data).field("observers",&observers).finish()
+    }
+}
@@ -1,6 +1,10 @@
 //! A sparse vector implementation.
 
 use crate::prelude::*;
+
+use crate::observed::Observable;
+use crate::observed::Observed;
+
 use std::iter::FilterMap;
 use std::slice;
 
@@ -16,6 +20,9 @@ use std::slice;
 /// After a value is removed, it remembers the index for reuse in the future. Unlike `Vec`, it is
 /// parametrized with optional `Index` type variable which will be used for indexing the vector.
 /// Index have to implement the `Index` trait.
+///
+/// All the iterators visit the occupied indexes in ascending order, regardless of the order in
+/// which the values were inserted or the indexes reused.
 #[derive(Derivative)]
 #[derivative(Default(bound=""))]
 #[derive(Clone,Debug,Shrinkwrap)]
@@ -148,6 +155,24 @@ impl<T,I:Index> OptVec<T,I> {
 }
 
 
+// === Compaction ===
+
+impl<T,I:Index> OptVec<T,I> {
+    /// Consume the vector, moving the elements to a dense `Vec` in the order of their indexes.
+    /// Returns the mapping from the indexes of this vector to the indexes in the new one. Reserved
+    /// indexes without values are not mapped.
+    pub fn into_compact(self) -> (Vec<T>,IndexRemap<I>) {
+        let mut values  = Vec::with_capacity(self.len());
+        let new_indexes = self.items.into_iter().map(|item| item.map(|value| {
+            let index = values.len().into();
+            values.push(value);
+            index
+        })).collect();
+        (values,IndexRemap{new_indexes})
+    }
+}
+
+
 // === Indexing ===
 
 impl<T,I:Index> OptVec<T,I> {
@@ -181,15 +206,22 @@ impl<T,I:Index> std::ops::IndexMut<I> for OptVec<T,I> {
 // === Iterators ===
 
 impl<T,I:Index> OptVec<T,I> {
-    /// Iterator.
+    /// Iterator over the values, in ascending order of their indexes.
     pub fn iter(&self) -> Iter<T> {
         self.items.iter().filter_map(Option::as_ref)
     }
 
-    /// Mutable iterator.
+    /// Mutable iterator over the values, in ascending order of their indexes.
     pub fn iter_mut(&mut self) -> IterMut<T> {
         self.items.iter_mut().filter_map(Option::as_mut)
     }
+
+    /// Iterator over the indexes of the values, in ascending order. Reserved indexes without
+    /// values are skipped.
+    pub fn indices(&self) -> impl Iterator<Item=I> + '_ {
+        let occupied = |(index,item):(usize,&Option<T>)| item.as_ref().map(|_| index.into());
+        self.items.iter().enumerate().filter_map(occupied)
+    }
 }
 
 impl<'a,T,I:Index> IntoIterator for &'a OptVec<T,I> {
@@ -210,6 +242,117 @@ impl<'a,T,I:Index> IntoIterator for &'a mut OptVec<T,I> {
 
 
 
+// === Observing ===
+
+/// A change of an [`OptVec`], reported to the observers of [`Observed`] vectors.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Event<I> {
+    /// A value was inserted at the index.
+    Inserted(I),
+    /// The value at the index was removed.
+    Removed(I),
+    /// The value was moved to another index.
+    #[allow(missing_docs)]
+    Moved {from:I, to:I},
+}
+
+impl<T,I> Observable for OptVec<T,I> {
+    type Event = Event<I>;
+}
+
+/// Modifiers of the vector notifying the observers. They behave like the methods of [`OptVec`]
+/// with the same names.
+impl<T,I:Index> Observed<OptVec<T,I>> {
+    /// Inserts the provided element to the vector. It reuses free indexes if any.
+    pub fn insert(&mut self, item:T) -> I {
+        let index = self.data_mut().insert(item);
+        self.notify(Event::Inserted(index));
+        index
+    }
+
+    /// Sets the value at given index. Replacing a value is reported as its removal followed by the
+    /// insertion of the new one.
+    pub fn set(&mut self, index:I, item:T) {
+        let replaced = self.safe_index(index).is_some();
+        self.data_mut().set(index,item);
+        if replaced { self.notify(Event::Removed(index)) }
+        self.notify(Event::Inserted(index));
+    }
+
+    /// Removes the element at provided index and marks the index to be reused.
+    pub fn remove(&mut self, index:I) -> Option<T> {
+        let item = self.data_mut().remove(index);
+        if item.is_some() { self.notify(Event::Removed(index)) }
+        item
+    }
+
+    /// Move all the values to the smallest indexes, keeping their order, like
+    /// [`OptVec::into_compact`]. Reserved indexes without values are freed. Returns the mapping
+    /// from the old indexes to the new ones.
+    pub fn compact(&mut self) -> IndexRemap<I> {
+        let (values,remap) = mem::take(self.data_mut()).into_compact();
+        let items          = values.into_iter().map(Some).collect();
+        let free_ixs       = default();
+        *self.data_mut()   = OptVec {items,free_ixs};
+        for (from,to) in remap.iter() {
+            if from.into() != to.into() { self.notify(Event::Moved {from,to}) }
+        }
+        remap
+    }
+}
+
+
+
+// ==================
+// === IndexRemap ===
+// ==================
+
+/// A mapping from old to new indexes, created when elements are moved, like in
+/// [`OptVec::into_compact`]. Old indexes of removed elements are not mapped.
+#[derive(Clone,Debug,Derivative,Eq,PartialEq)]
+#[derivative(Default(bound=""))]
+pub struct IndexRemap<I=usize> {
+    new_indexes : Vec<Option<I>>,
+}
+
+impl<I:Index> IndexRemap<I> {
+    /// The new index of the element, or `None` if the element was removed.
+    pub fn get(&self, old:I) -> Option<I> {
+        self.new_indexes.get(old.into()).copied().flatten()
+    }
+
+    /// Number of old indexes, including the unmapped ones.
+    pub fn len(&self) -> usize {
+        self.new_indexes.len()
+    }
+
+    /// Check whether there are no old indexes.
+    pub fn is_empty(&self) -> bool {
+        self.new_indexes.is_empty()
+    }
+
+    /// Check whether every old index is mapped to itself.
+    pub fn is_identity(&self) -> bool {
+        let same = |(old,new):(usize,&Option<I>)| new.map(|new| new.into()) == Some(old);
+        self.new_indexes.iter().enumerate().all(same)
+    }
+
+    /// Iterator over the mapped old indexes along with their new indexes, in ascending order of
+    /// the old indexes.
+    pub fn iter(&self) -> impl Iterator<Item=(I,I)> + '_ {
+        let mapped = |(old,new):(usize,&Option<I>)| new.map(|new| (old.into(),new));
+        self.new_indexes.iter().enumerate().filter_map(mapped)
+    }
+
+    /// The mapping applying this one first, and then the `next` one. Indexes unmapped by any of
+    /// them are not mapped.
+    pub fn then(&self, next:&Self) -> Self {
+        let new_indexes = self.new_indexes.iter().map(|new| new.and_then(|t| next.get(t)));
+        Self {new_indexes:new_indexes.collect()}
+    }
+}
+
+
 // =============
 // === Tests ===
 // =============
@@ -218,6 +361,33 @@ impl<'a,T,I:Index> IntoIterator for &'a mut OptVec<T,I> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_observing_changes() {
+        let events  = Rc::new(RefCell::new(Vec::new()));
+        let mut vec = Observed::new(OptVec::<&str>::new());
+        let sink    = events.clone();
+        vec.observe(move |event| sink.borrow_mut().push(*event));
+        let ix1 = vec.insert("a");
+        let ix2 = vec.insert("b");
+        let ix3 = vec.insert("c");
+        vec.set(ix3,"d");
+        vec.remove(ix2);
+        vec.remove(ix2);
+        let remap = vec.compact();
+        assert_eq!(remap.get(ix3),Some(1));
+        assert_eq!(vec.iter().copied().collect_vec(),vec!["a","d"]);
+        let expected = vec![
+            Event::Inserted(ix1),
+            Event::Inserted(ix2),
+            Event::Inserted(ix3),
+            Event::Removed(ix3),
+            Event::Inserted(ix3),
+            Event::Removed(ix2),
+            Event::Moved {from:ix3,to:1},
+        ];
+        assert_eq!(*events.borrow(),expected);
+    }
+
     #[test]
     fn test_add() {
         let mut v = OptVec::<usize>::new();
@@ -285,4 +455,46 @@ mod tests {
             assert_eq!((i + 1) * 2, *value);
         }
     }
+
+    #[test]
+    fn test_into_compact() {
+        let mut v = OptVec::<char>::new();
+        let ix1 = v.insert('a');
+        let ix2 = v.insert('b');
+        let ix3 = v.insert('c');
+        let ix4 = v.reserve_index();
+        v.remove(ix1);
+        let (values,remap) = v.into_compact();
+        assert_eq!(values,vec!['b','c']);
+        assert_eq!(remap.get(ix1),None);
+        assert_eq!(remap.get(ix2),Some(0));
+        assert_eq!(remap.get(ix3),Some(1));
+        assert_eq!(remap.get(ix4),None);
+        assert_eq!(remap.iter().collect_vec(),vec![(ix2,0),(ix3,1)]);
+        assert_eq!(remap.len(),4);
+        assert!(!remap.is_identity());
+
+        let mut v = OptVec::<char>::new();
+        for value in values { v.insert(value); }
+        v.remove(0);
+        let (values,next) = v.into_compact();
+        assert_eq!(values,vec!['c']);
+        assert_eq!(remap.then(&next).iter().collect_vec(),vec![(ix3,0)]);
+        assert!(next.then(&IndexRemap::default()).iter().next().is_none());
+    }
+
+    #[test]
+    fn test_iteration_order() {
+        let mut v = OptVec::<usize>::new();
+        let ix1 = v.insert(1);
+        let ix2 = v.insert(2);
+        let ix3 = v.insert(3);
+        v.reserve_index();
+        v.remove(ix3);
+        v.remove(ix1);
+        assert_eq!(v.insert(4),ix1);
+        assert_eq!(v.insert(5),ix3);
+        assert_eq!(v.indices().collect_vec(),vec![ix1,ix2,ix3]);
+        assert_eq!(v.iter().copied().collect_vec(),vec![4,2,5]);
+    }
 }
@@ -0,0 +1,193 @@
+//! A sparse vector, like `OptVec`, which also maintains a custom order of its elements.
+
+use crate::prelude::*;
+
+use crate::opt_vec;
+use crate::opt_vec::OptVec;
+
+
+
+// =====================
+// === OrderedOptVec ===
+// =====================
+
+/// An element with its priority.
+#[derive(Clone,Debug)]
+struct Item<T,P> {
+    value    : T,
+    priority : P,
+}
+
+/// A sparse vector reusing the indexes of removed elements, like `OptVec`, which also keeps its
+/// elements in a custom order, so they can be iterated in that order without sorting. Every
+/// element has a priority, and elements are ordered by ascending priority. Elements of equal
+/// priority are ordered by insertion, unless they were explicitly inserted before or after another
+/// element.
+///
+/// It is a good fit for registries of event handlers, which are dispatched far more often than
+/// registered. Accessing elements by index takes `O(1)` time. Inserting and removing elements
+/// takes `O(log n + k)` time, where `k` is the number of elements following it in the order.
+#[derive(Clone,Debug,Derivative)]
+#[derivative(Default(bound=""))]
+pub struct OrderedOptVec<T,P,I=usize> {
+    items : OptVec<Item<T,P>,I>,
+    order : Vec<I>,
+}
+
+impl<T,P:Ord,I:opt_vec::Index> OrderedOptVec<T,P,I> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Check whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Insert the element after all the elements with lower or equal priority. Returns its index.
+    pub fn insert(&mut self, value:T, priority:P) -> I {
+        let position = self.order.partition_point(|t| self.items[*t].priority <= priority);
+        self.insert_at(position,value,priority)
+    }
+
+    /// Insert the element just before the `anchor` element, with the same priority. Returns its
+    /// index.
+    ///
+    /// # Panics
+    /// Panics if there is no element at the `anchor` index.
+    pub fn insert_before(&mut self, anchor:I, value:T) -> I where P:Clone {
+        let position = self.expect_position(anchor);
+        let priority = self.items[anchor].priority.clone();
+        self.insert_at(position,value,priority)
+    }
+
+    /// Insert the element just after the `anchor` element, with the same priority. Returns its
+    /// index.
+    ///
+    /// # Panics
+    /// Panics if there is no element at the `anchor` index.
+    pub fn insert_after(&mut self, anchor:I, value:T) -> I where P:Clone {
+        let position = self.expect_position(anchor) + 1;
+        let priority = self.items[anchor].priority.clone();
+        self.insert_at(position,value,priority)
+    }
+
+    /// Remove the element. Its index will be reused by the next inserted element.
+    pub fn remove(&mut self, index:I) -> Option<T> {
+        let position = self.position(index)?;
+        self.order.remove(position);
+        self.items.remove(index).map(|item| item.value)
+    }
+
+    /// Change the priority of the element, moving it after all the elements with lower or equal
+    /// priority. Returns false if there is no element at the index.
+    pub fn set_priority(&mut self, index:I, priority:P) -> bool {
+        match self.position(index) {
+            None           => false,
+            Some(position) => {
+                self.order.remove(position);
+                let position = self.order.partition_point(|t| self.items[*t].priority <= priority);
+                self.order.insert(position,index);
+                self.items[index].priority = priority;
+                true
+            }
+        }
+    }
+
+    /// Reference to the element.
+    pub fn get(&self, index:I) -> Option<&T> {
+        self.items.safe_index(index).map(|item| &item.value)
+    }
+
+    /// Mutable reference to the element.
+    pub fn get_mut(&mut self, index:I) -> Option<&mut T> {
+        self.items.safe_index_mut(index).map(|item| &mut item.value)
+    }
+
+    /// The priority of the element.
+    pub fn priority(&self, index:I) -> Option<&P> {
+        self.items.safe_index(index).map(|item| &item.priority)
+    }
+
+    /// Indexes of all the elements, in order.
+    pub fn indexes(&self) -> &[I] {
+        &self.order
+    }
+
+    /// Iterator over the elements, in order.
+    pub fn iter(&self) -> impl Iterator<Item=&T> {
+        self.order.iter().map(move |index| &self.items[*index].value)
+    }
+
+    /// Iterator over the elements along with their indexes, in order.
+    pub fn iter_with_indexes(&self) -> impl Iterator<Item=(I,&T)> {
+        self.order.iter().map(move |index| (*index,&self.items[*index].value))
+    }
+
+    fn insert_at(&mut self, position:usize, value:T, priority:P) -> I {
+        let index = self.items.insert(Item {value,priority});
+        self.order.insert(position,index);
+        index
+    }
+
+    /// Position of the element in the order. Only the elements of the same priority are searched
+    /// linearly.
+    fn position(&self, index:I) -> Option<usize> {
+        let priority = &self.items.safe_index(index)?.priority;
+        let start    = self.order.partition_point(|t| self.items[*t].priority < *priority);
+        let same     = |t:&I| (*t).into() == index.into();
+        self.order[start..].iter().position(same).map(|offset| start + offset)
+    }
+
+    fn expect_position(&self, index:I) -> usize {
+        let position = self.position(index);
+        position.unwrap_or_else(|| panic!("No element at the index {:?}.",index))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_are_kept_in_order() {
+        let mut vec = OrderedOptVec::<&str,i32>::new();
+        let normal  = vec.insert("normal",0);
+        let late    = vec.insert("late",10);
+        vec.insert("early",-10);
+        vec.insert("normal 2",0);
+        vec.insert_before(normal,"before normal");
+        vec.insert_after(late,"after late");
+        let expected = vec!["early","before normal","normal","normal 2","late","after late"];
+        assert_eq!(vec.iter().copied().collect_vec(),expected);
+        vec.set_priority(late,-20);
+        let expected = vec!["late","early","before normal","normal","normal 2","after late"];
+        assert_eq!(vec.iter().copied().collect_vec(),expected);
+    }
+
+    #[test]
+    fn indexes_are_reused() {
+        let mut vec = OrderedOptVec::<usize,i32>::new();
+        let first   = vec.insert(1,1);
+        let second  = vec.insert(2,1);
+        assert_eq!(vec.remove(first),Some(1));
+        assert_eq!(vec.remove(first),None);
+        assert_eq!(vec.insert(0,0),first);
+        assert_eq!(vec.indexes(),&[first,second]);
+        assert_eq!(vec.get(first),Some(&0));
+        assert_eq!(vec.priority(second),Some(&1));
+        assert_eq!(vec.len(),2);
+    }
+}
@@ -0,0 +1,149 @@
+//! Index arithmetic with an explicit policy for overflows.
+//!
+//! Plain `+` and `-` on `usize` panic on overflow in debug builds only, and silently wrap in
+//! release builds, while `saturating_add` and `saturating_sub` silently clamp the result. Both
+//! make off-by-one bugs in index math hard to spot. The functions of this module make the behavior
+//! on overflow explicit, and behave the same way in all build profiles.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === Operation ===
+// =================
+
+/// An arithmetic operation on indexes.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub enum Operation { Add, Sub }
+
+impl Display for Operation {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add => write!(f,"+"),
+            Self::Sub => write!(f,"-"),
+        }
+    }
+}
+
+
+
+// =====================
+// === OverflowError ===
+// =====================
+
+/// An index arithmetic operation whose result is out of the range of `usize`.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct OverflowError {
+    /// The operation which overflowed.
+    pub operation : Operation,
+    /// The left operand.
+    pub lhs       : usize,
+    /// The right operand.
+    pub rhs       : usize,
+}
+
+impl Display for OverflowError {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"Index arithmetic overflow: {} {} {}.",self.lhs,self.operation,self.rhs)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+
+
+// ==============
+// === Policy ===
+// ==============
+
+/// The behavior of index arithmetic on overflow.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Policy {
+    /// Panic with the description of the operation. The policy of the `add` and `sub` functions.
+    Panic,
+    /// Clamp the result to the range of `usize`.
+    Saturate,
+    /// Return an [`OverflowError`].
+    Error,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::Panic
+    }
+}
+
+impl Policy {
+    /// Computes `lhs + rhs`. Returns an error only for the `Error` policy.
+    pub fn add(self, lhs:usize, rhs:usize) -> Result<usize,OverflowError> {
+        self.apply(Operation::Add,lhs,rhs,lhs.checked_add(rhs),usize::MAX)
+    }
+
+    /// Computes `lhs - rhs`. Returns an error only for the `Error` policy.
+    pub fn sub(self, lhs:usize, rhs:usize) -> Result<usize,OverflowError> {
+        self.apply(Operation::Sub,lhs,rhs,lhs.checked_sub(rhs),0)
+    }
+
+    fn apply
+    (self, operation:Operation, lhs:usize, rhs:usize, result:Option<usize>, bound:usize)
+    -> Result<usize,OverflowError> {
+        result.map(Ok).unwrap_or_else(|| match self {
+            Self::Panic    => overflow(operation,lhs,rhs),
+            Self::Saturate => Ok(bound),
+            Self::Error    => Err(OverflowError {operation,lhs,rhs}),
+        })
+    }
+}
+
+
+// === Panicking Arithmetic ===
+
+/// Computes `lhs + rhs`.
+///
+/// # Panics
+/// Panics on overflow, in all build profiles.
+pub fn add(lhs:usize, rhs:usize) -> usize {
+    lhs.checked_add(rhs).unwrap_or_else(|| overflow(Operation::Add,lhs,rhs))
+}
+
+/// Computes `lhs - rhs`.
+///
+/// # Panics
+/// Panics on overflow, in all build profiles.
+pub fn sub(lhs:usize, rhs:usize) -> usize {
+    lhs.checked_sub(rhs).unwrap_or_else(|| overflow(Operation::Sub,lhs,rhs))
+}
+
+fn overflow(operation:Operation, lhs:usize, rhs:usize) -> ! {
+    panic!("{}",OverflowError {operation,lhs,rhs})
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies() {
+        let error = OverflowError {operation:Operation::Sub,lhs:1,rhs:2};
+        assert_eq!(Policy::Saturate.sub(1,2),Ok(0));
+        assert_eq!(Policy::Saturate.add(usize::MAX,1),Ok(usize::MAX));
+        assert_eq!(Policy::Error.sub(1,2),Err(error));
+        assert_eq!(Policy::Error.add(1,2),Ok(3));
+        assert_eq!(Policy::Panic.sub(3,2),Ok(1));
+        assert_eq!(error.to_string(),"Index arithmetic overflow: 1 - 2.");
+    }
+
+    #[test]
+    #[should_panic(expected = "Index arithmetic overflow: 1 - 2.")]
+    fn panic_policy() {
+        sub(1,2);
+    }
+}
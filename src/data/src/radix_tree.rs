@@ -0,0 +1,454 @@
+//! A path-compressed variant of [`HashMapTree`], storing chains of single-child nodes as single
+//! edges labeled with runs of keys.
+//!
+//! Trees of long paths, like file paths, consist mostly of chains of nodes having exactly one
+//! child and the default value. A [`HashMapTree`] allocates a hash map for every such node, and
+//! looking up a path follows a pointer for every segment. A [`RadixTree`] stores the whole chain
+//! in a single edge instead, so it allocates a node only where the tree branches or where a value
+//! was set explicitly.
+
+use crate::prelude::*;
+
+use crate::hash_map_tree::HashMapTree;
+use crate::hash_map_tree::Path;
+use crate::hash_map_tree::Segment;
+use crate::hash_map_tree::SegmentIter;
+
+use std::collections::hash_map;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::iter::FromIterator;
+
+
+
+// ============
+// === Node ===
+// ============
+
+/// A node stored explicitly in the tree.
+#[derive(Derivative)]
+#[derivative(Clone(bound   = "K:Clone, V:Clone, S:Clone"))]
+#[derivative(Debug(bound   = "K:Debug, V:Debug"))]
+#[derivative(Default(bound = "V:Default, S:Default"))]
+struct Node<K,V,S> {
+    value    : V,
+    branches : HashMap<K,Edge<K,V,S>,S>,
+}
+
+/// An edge leading to an explicit node. The edge with key `k` leads through the implicit nodes at
+/// paths `[k]`, `[k,s0]`, ..., `[k,s0,...,sn-1]`, where `s0..sn` are the skipped keys, to the
+/// explicit node at the path `[k,s0,...,sn]`. Implicit nodes have the default value and exactly
+/// one child.
+#[derive(Derivative)]
+#[derivative(Clone(bound   = "K:Clone, V:Clone, S:Clone"))]
+#[derivative(Debug(bound   = "K:Debug, V:Debug"))]
+struct Edge<K,V,S> {
+    skipped : Vec<K>,
+    node    : Node<K,V,S>,
+}
+
+impl<K:Eq+Hash,V:Default,S:BuildHasher+Default> Edge<K,V,S> {
+    /// Store the implicit node at the depth explicitly. It becomes the target of this edge.
+    fn split(&mut self, depth:usize) {
+        let mut skipped = self.skipped.split_off(depth);
+        let key         = skipped.remove(0);
+        let node        = mem::take(&mut self.node);
+        self.node.branches.insert(key,Edge{skipped,node});
+    }
+}
+
+/// Deep trees are dropped iteratively, so dropping them does not overflow the stack.
+impl<K,V,S> DeepDrop for Node<K,V,S> {
+    type Child = Self;
+    fn take_children(&mut self, children:&mut Vec<Self>) {
+        children.extend(self.branches.drain().map(|(_,edge)| edge.node));
+    }
+}
+
+impl<K,V,S> Drop for Node<K,V,S> {
+    fn drop(&mut self) {
+        self.drop_children()
+    }
+}
+
+
+
+// =================
+// === RadixTree ===
+// =================
+
+/// A tree with the same interface as [`HashMapTree`], which stores chains of single-child nodes
+/// with default values as single edges. See the module docs to learn more.
+///
+/// Chains are compressed when they are created by `set`. Setting a value in the middle of a chain
+/// splits it. Removing nodes never merges chains back, so trees which are heavily modified may be
+/// less compressed than trees built from scratch. Unlike [`HashMapTree`], the tree provides no
+/// mutable iterator, as values of the implicit nodes are not stored anywhere.
+#[derive(Derivative)]
+#[derivative(Clone(bound   = "K:Clone, V:Clone, S:Clone"))]
+#[derivative(Debug(bound   = "K:Debug, V:Debug"))]
+#[derivative(Default(bound = "V:Default, S:Default"))]
+pub struct RadixTree<K,V,S=RandomState> {
+    root    : Node<K,V,S>,
+    default : V,
+}
+
+impl<K,V,S> RadixTree<K,V,S>
+where K : Eq+Hash,
+      V : Default,
+      S : BuildHasher+Default {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Constructor with explicit root value.
+    pub fn from_value(value:V) -> Self {
+        let root    = Node {value,branches:default()};
+        let default = default();
+        Self {root,default}
+    }
+
+    /// Check if the root has no branches.
+    pub fn is_leaf(&self) -> bool {
+        self.root.branches.is_empty()
+    }
+
+    /// Number of nodes stored explicitly, including the root.
+    pub fn explicit_node_count(&self) -> usize {
+        let mut count = 0;
+        let mut nodes = vec![&self.root];
+        while let Some(node) = nodes.pop() {
+            count += 1;
+            nodes.extend(node.branches.values().map(|edge| &edge.node));
+        }
+        count
+    }
+
+    /// Sets the value at position described by `path`. Missing nodes are created with default
+    /// values.
+    pub fn set<P:Path<K>>(&mut self, path:P, value:V) {
+        self.get_or_create_node(path).value = value;
+    }
+
+    /// Gets a reference to a value at the specified path if the path exists in the tree.
+    pub fn get<P:Path<K>>(&self, path:P) -> Option<&V> {
+        let mut segments = path.segments();
+        let mut node     = &self.root;
+        while let Some(segment) = segments.next() {
+            let edge = segment.get_in(&node.branches)?;
+            for key in &edge.skipped {
+                match segments.next() {
+                    None          => return Some(&self.default),
+                    Some(segment) => if !segment.matches(key) { return None }
+                }
+            }
+            node = &edge.node;
+        }
+        Some(&node.value)
+    }
+
+    /// Gets a mutable reference to a value at the specified path if the path exists in the tree.
+    /// If the path ends in the middle of a compressed chain, the chain is split.
+    pub fn get_mut<P:Path<K>>(&mut self, path:P) -> Option<&mut V> {
+        let mut segments = path.segments();
+        let mut node     = &mut self.root;
+        while let Some(segment) = segments.next() {
+            let edge      = segment.get_mut_in(&mut node.branches)?;
+            let mut depth = 0;
+            while depth < edge.skipped.len() {
+                match segments.next() {
+                    None          => break,
+                    Some(segment) => if segment.matches(&edge.skipped[depth]) { depth += 1 }
+                                     else { return None }
+                }
+            }
+            if depth < edge.skipped.len() {
+                edge.split(depth);
+                return Some(&mut edge.node.value)
+            }
+            node = &mut edge.node;
+        }
+        Some(&mut node.value)
+    }
+
+    /// Removes the node at the specified path, along with all its branches. Returns its value.
+    pub fn remove<P:Path<K>>(&mut self, path:P) -> Option<V> {
+        let segments  = path.segments().collect_vec();
+        let start     = self.last_edge_start(&segments)?;
+        let mut node  = &mut self.root;
+        let mut index = 0;
+        while index < start {
+            let edge = segments[index].get_mut_in(&mut node.branches)?;
+            index   += 1 + edge.skipped.len();
+            node     = &mut edge.node;
+        }
+        // The number of skipped keys of the last edge which are part of the path.
+        let depth = segments.len() - start - 1;
+        let edge  = segments[start].get_mut_in(&mut node.branches)?;
+        if depth == 0 {
+            let is_explicit = edge.skipped.is_empty();
+            let mut edge    = segments[start].remove_in(&mut node.branches)?;
+            Some(if is_explicit { mem::take(&mut edge.node.value) } else { default() })
+        } else {
+            let is_explicit = depth == edge.skipped.len();
+            edge.skipped.truncate(depth - 1);
+            let mut removed = mem::take(&mut edge.node);
+            Some(if is_explicit { mem::take(&mut removed.value) } else { default() })
+        }
+    }
+
+    /// Obtain an iterator over the tree. Values of all the nodes are returned, including the
+    /// implicit ones.
+    pub fn iter(&self) -> Iter<K,V,S> {
+        let root_item = Some(&self.root.value);
+        let iters     = vec![(0,self.root.branches.iter())];
+        let path      = default();
+        let edge      = None;
+        let default   = &self.default;
+        Iter {default,root_item,iters,path,edge}
+    }
+
+    /// Iterates over keys in `path`, creating the missing nodes. All the missing nodes are stored
+    /// in a single edge. Returns the node at the end of the path, which is stored explicitly.
+    fn get_or_create_node<P:Path<K>>(&mut self, path:P) -> &mut Node<K,V,S> {
+        let mut segments = path.segments();
+        let mut node     = &mut self.root;
+        let mut pending  = None;
+        loop {
+            let segment = match pending.take().or_else(|| segments.next()) {
+                None          => return node,
+                Some(segment) => segment,
+            };
+            if segment.get_in(&node.branches).is_none() {
+                let skipped = segments.map(Segment::into_key).collect();
+                let edge    = Edge {skipped,node:default()};
+                return &mut node.branches.entry(segment.into_key()).or_insert(edge).node
+            }
+            let edge      = segment.get_mut_in(&mut node.branches).unwrap();
+            let mut depth = 0;
+            let mut ended = false;
+            while depth < edge.skipped.len() {
+                match segments.next() {
+                    None => { ended = true; break }
+                    Some(segment) => {
+                        if !segment.matches(&edge.skipped[depth]) {
+                            pending = Some(segment);
+                            break
+                        }
+                        depth += 1;
+                    }
+                }
+            }
+            if depth < edge.skipped.len() { edge.split(depth); }
+            if ended { return &mut edge.node }
+            node = &mut edge.node;
+        }
+    }
+
+    /// The index of the segment which is the key of the last edge on the path, or `None` if the
+    /// path is empty or does not exist in the tree.
+    fn last_edge_start<T:Segment<K>>(&self, segments:&[T]) -> Option<usize> {
+        let mut node  = &self.root;
+        let mut index = 0;
+        let mut start = None;
+        while index < segments.len() {
+            let edge  = segments[index].get_in(&node.branches)?;
+            let rest  = &segments[index + 1..];
+            let count = rest.len().min(edge.skipped.len());
+            let found = rest[..count].iter().zip(&edge.skipped).all(|(t,key)| t.matches(key));
+            if !found { return None }
+            start  = Some(index);
+            index += 1 + count;
+            node   = &edge.node;
+        }
+        start
+    }
+}
+
+
+// === Conversions ===
+
+impl<K,V,S> FromIterator<(Vec<K>,V)> for RadixTree<K,V,S>
+where K : Eq+Hash,
+      V : Default,
+      S : BuildHasher+Default {
+    fn from_iter<T:IntoIterator<Item=(Vec<K>,V)>>(iter:T) -> Self {
+        let mut tree = Self::new();
+        for (path,value) in iter {
+            tree.set(path,value);
+        }
+        tree
+    }
+}
+
+/// Only the leaves and the nodes with non-default values are set, so the chains of the source
+/// tree are compressed.
+impl<K,V,S> From<&HashMapTree<K,V,S>> for RadixTree<K,V,S>
+where K : Clone+Eq+Hash,
+      V : Clone+Default+PartialEq,
+      S : BuildHasher+Default {
+    fn from(tree:&HashMapTree<K,V,S>) -> Self {
+        let mut radix = Self::from_value(tree.value.clone());
+        let mut stack = vec![(Vec::new(),tree)];
+        while let Some((path,node)) = stack.pop() {
+            if node.is_leaf() || node.value != radix.default {
+                radix.set(SegmentIter(path.iter()),node.value.clone());
+            }
+            for (key,branch) in &node.branches {
+                let mut path = path.clone();
+                path.push(key.clone());
+                stack.push((path,branch));
+            }
+        }
+        radix
+    }
+}
+
+impl<K,V,S> From<&RadixTree<K,V,S>> for HashMapTree<K,V,S>
+where K : Clone+Eq+Hash,
+      V : Clone+Default,
+      S : BuildHasher+Default {
+    fn from(tree:&RadixTree<K,V,S>) -> Self {
+        let owned = |(path,value):(Vec<&K>,&V)| (path.into_iter().cloned().collect(),value.clone());
+        tree.iter().map(owned).collect()
+    }
+}
+
+
+// === Iterators ===
+
+/// Iterator over the branches of a node.
+type EdgeIter<'a,K,V,S> = hash_map::Iter<'a,K,Edge<K,V,S>>;
+
+/// Iterator over the paths and values of all the nodes of a [`RadixTree`].
+pub struct Iter<'a,K,V,S> {
+    default   : &'a V,
+    root_item : Option<&'a V>,
+    /// Iterators over the branches of explicit nodes, with the lengths of the paths of the nodes.
+    iters     : Vec<(usize,EdgeIter<'a,K,V,S>)>,
+    path      : Vec<&'a K>,
+    /// The edge being traversed, with the number of its skipped keys already in the path.
+    edge      : Option<(&'a Edge<K,V,S>,usize)>,
+}
+
+impl<'a,K,V,S> Iter<'a,K,V,S> {
+    fn enter(&mut self, edge:&'a Edge<K,V,S>, depth:usize) -> (Vec<&'a K>,&'a V) {
+        self.edge = Some((edge,depth));
+        let value = if depth < edge.skipped.len() { self.default } else { &edge.node.value };
+        (self.path.clone(),value)
+    }
+}
+
+impl<'a,K,V,S> Iterator for Iter<'a,K,V,S> {
+    type Item = (Vec<&'a K>,&'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root_item) = self.root_item.take() {
+            return Some((self.path.clone(),root_item))
+        }
+        if let Some((edge,depth)) = self.edge.take() {
+            if depth < edge.skipped.len() {
+                self.path.push(&edge.skipped[depth]);
+                return Some(self.enter(edge,depth + 1))
+            }
+            self.iters.push((self.path.len(),edge.node.branches.iter()));
+        }
+        loop {
+            let (path_len,iter) = self.iters.last_mut()?;
+            match iter.next() {
+                None => { self.iters.pop(); }
+                Some((key,edge)) => {
+                    self.path.truncate(*path_len);
+                    self.path.push(key);
+                    return Some(self.enter(edge,0))
+                }
+            }
+        }
+    }
+}
+
+impl<'a,K,V,S> IntoIterator for &'a RadixTree<K,V,S>
+where K : Eq+Hash,
+      V : Default,
+      S : BuildHasher+Default {
+    type Item     = (Vec<&'a K>,&'a V);
+    type IntoIter = Iter<'a,K,V,S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a,K,V,S> Debug for Iter<'a,K,V,S> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"Iter")
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_map_tree::DottedPath;
+
+    fn sorted<'a,K,V>(iter:impl Iterator<Item=(Vec<&'a K>,&'a V)>) -> Vec<(Vec<K>,V)>
+    where K:'a+Clone+Ord, V:'a+Clone+Ord {
+        let owned = |(path,value):(Vec<&K>,&V)| (path.into_iter().cloned().collect(),value.clone());
+        iter.map(owned).sorted().collect()
+    }
+
+    #[test]
+    fn chains_are_compressed() {
+        let mut tree = RadixTree::<String,usize>::new();
+        tree.set(DottedPath("usr.local.share.fonts"),1);
+        tree.set(DottedPath("usr.local.share.icons"),2);
+        assert_eq!(tree.explicit_node_count(),4);
+        assert_eq!(tree.get(DottedPath("usr.local.share.fonts")),Some(&1));
+        assert_eq!(tree.get(DottedPath("usr.local")),Some(&0));
+        assert_eq!(tree.get(DottedPath("usr.share")),None);
+        assert_eq!(tree.get(DottedPath("usr.local.share.fonts.ttf")),None);
+        *tree.get_mut(DottedPath("usr.local")).unwrap() = 3;
+        assert_eq!(tree.explicit_node_count(),5);
+        assert_eq!(tree.get(DottedPath("usr.local")),Some(&3));
+        assert_eq!(tree.get(DottedPath("usr.local.share")),Some(&0));
+        tree.set(DottedPath("usr.lib"),4);
+        assert_eq!(tree.explicit_node_count(),7);
+        assert_eq!(tree.get(DottedPath("usr.lib")),Some(&4));
+        assert_eq!(tree.get(DottedPath("usr.local.share.icons")),Some(&2));
+    }
+
+    #[test]
+    fn removing_nodes() {
+        let mut tree = RadixTree::<i32,usize>::new();
+        tree.set(vec![1,2,3,4],1);
+        tree.set(vec![1,5],2);
+        assert_eq!(tree.remove(vec![1,2,3]),Some(0));
+        assert_eq!(tree.get(vec![1,2]),Some(&0));
+        assert_eq!(tree.get(vec![1,2,3]),None);
+        assert_eq!(tree.remove(vec![1,2,3]),None);
+        tree.set(vec![1,2,3,4],1);
+        assert_eq!(tree.remove(vec![1,2,3,4]),Some(1));
+        assert_eq!(tree.get(vec![1,2,3]),Some(&0));
+        assert_eq!(tree.remove(vec![1,5]),Some(2));
+        assert_eq!(tree.remove(vec![1]),Some(0));
+        assert!(tree.is_leaf());
+        assert_eq!(tree.remove(Vec::<i32>::new()),None);
+    }
+
+    #[test]
+    fn conversion_round_trip() {
+        let mut tree = HashMapTree::<i32,usize>::new();
+        tree.set(vec![1,2,3,4,5],1);
+        tree.set(vec![1,2,3,6],2);
+        tree.set(vec![1,2],3);
+        tree.set(vec![7],4);
+        let radix = RadixTree::from(&tree);
+        assert_eq!(radix.explicit_node_count(),6);
+        assert_eq!(sorted(radix.iter()),sorted(tree.iter()));
+        assert_eq!(HashMapTree::from(&radix),tree);
+    }
+}
@@ -0,0 +1,187 @@
+//! A run-length encoded sequence, storing runs of equal values instead of the values themselves.
+
+use crate::prelude::*;
+
+use crate::interval::RightOpenInterval;
+
+use std::iter::FromIterator;
+
+
+
+// ===========
+// === Rle ===
+// ===========
+
+/// A sequence of values stored as runs of equal consecutive values. Runs are kept in a `BTreeMap`
+/// indexed by their start, so both point updates and assignments of whole ranges take `O(log n)`
+/// time (plus the time of removing the runs covered by the range), where `n` is the number of runs.
+/// Neighbor runs with equal values are always merged.
+///
+/// It is a good fit for long sequences of flags which change in blocks, like per-line folding or
+/// visibility flags.
+#[derive(Clone,Debug,Derivative,Eq,PartialEq)]
+#[derivative(Default(bound=""))]
+pub struct Rle<T> {
+    runs : BTreeMap<usize,T>,
+    len  : usize,
+}
+
+impl<T:Clone+PartialEq> Rle<T> {
+    /// Constructor of an empty sequence.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Constructor of a sequence of `len` copies of the value.
+    pub fn with_len(len:usize, value:T) -> Self {
+        let mut rle = Self::new();
+        rle.push_run(len,value);
+        rle
+    }
+
+    /// Number of values in the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of runs the values are stored in.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// The value at the index.
+    pub fn get(&self, index:usize) -> Option<&T> {
+        if index >= self.len { return None }
+        self.runs.range(..=index).next_back().map(|(_,value)| value)
+    }
+
+    /// Append the value.
+    pub fn push(&mut self, value:T) {
+        self.push_run(1,value)
+    }
+
+    /// Append `count` copies of the value.
+    pub fn push_run(&mut self, count:usize, value:T) {
+        if count == 0 { return }
+        let start = self.len;
+        self.len += count;
+        self.runs.insert(start,value);
+        self.merge_with_previous(start);
+    }
+
+    /// Set the value at the index.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn set(&mut self, index:usize, value:T) {
+        self.assign(index..index + 1,value)
+    }
+
+    /// Set all the values in the range.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    pub fn assign(&mut self, range:Range<usize>, value:T) {
+        let len = self.len;
+        assert!(range.end <= len, "Range end {} out of bounds ({}).",range.end,len);
+        if range.start >= range.end { return }
+        self.split_at(range.start);
+        self.split_at(range.end);
+        let covered = self.runs.range(range.start..range.end).map(|(start,_)| *start).collect_vec();
+        for start in covered { self.runs.remove(&start); }
+        self.runs.insert(range.start,value);
+        self.merge_with_previous(range.end);
+        self.merge_with_previous(range.start);
+    }
+
+    /// Iterator over the runs, as intervals of indexes along with their values, in ascending order.
+    pub fn runs(&self) -> impl Iterator<Item=(RightOpenInterval,&T)> {
+        let ends = self.runs.keys().skip(1).copied().chain(iter::once(self.len));
+        self.runs.iter().zip(ends).map(|((start,value),end)| (RightOpenInterval(*start,end),value))
+    }
+
+    /// Iterator over all the values.
+    pub fn iter(&self) -> impl Iterator<Item=&T> {
+        self.runs().flat_map(|(interval,value)| iter::repeat(value).take(interval.len()))
+    }
+
+    /// Start a new run at the index, if it is in the middle of a run.
+    fn split_at(&mut self, index:usize) {
+        if index >= self.len { return }
+        let run = self.runs.range(..=index).next_back();
+        let (start,value) = run.expect("The first run always starts at 0.");
+        if *start < index {
+            let value = value.clone();
+            self.runs.insert(index,value);
+        }
+    }
+
+    /// Merge the run starting at the index with the previous one, if their values are equal.
+    fn merge_with_previous(&mut self, index:usize) {
+        if let Some(value) = self.runs.get(&index) {
+            if let Some((_,previous)) = self.runs.range(..index).next_back() {
+                if previous == value { self.runs.remove(&index); }
+            }
+        }
+    }
+}
+
+impl<T:Clone+PartialEq> Extend<T> for Rle<T> {
+    fn extend<I:IntoIterator<Item=T>>(&mut self, iter:I) {
+        for value in iter { self.push(value); }
+    }
+}
+
+impl<T:Clone+PartialEq> FromIterator<T> for Rle<T> {
+    fn from_iter<I:IntoIterator<Item=T>>(iter:I) -> Self {
+        let mut rle = Self::new();
+        rle.extend(iter);
+        rle
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(rle:&Rle<char>) -> Vec<(usize,usize,char)> {
+        rle.runs().map(|(interval,value)| (interval.start,interval.end,*value)).collect()
+    }
+
+    #[test]
+    fn runs_are_merged() {
+        let mut rle : Rle<_> = "aabbbc".chars().collect();
+        assert_eq!(runs(&rle),vec![(0,2,'a'),(2,5,'b'),(5,6,'c')]);
+        rle.set(5,'b');
+        assert_eq!(runs(&rle),vec![(0,2,'a'),(2,6,'b')]);
+        rle.assign(1..3,'x');
+        assert_eq!(runs(&rle),vec![(0,1,'a'),(1,3,'x'),(3,6,'b')]);
+        rle.assign(0..6,'a');
+        assert_eq!(rle.run_count(),1);
+        rle.push_run(2,'a');
+        assert_eq!(runs(&rle),vec![(0,8,'a')]);
+    }
+
+    #[test]
+    fn point_queries() {
+        let mut rle = Rle::with_len(10,false);
+        rle.assign(3..6,true);
+        rle.set(8,true);
+        let expected = vec![false,false,false,true,true,true,false,false,true,false];
+        assert_eq!(rle.iter().copied().collect_vec(),expected);
+        assert_eq!((0..10).map(|index| *rle.get(index).unwrap()).collect_vec(),expected);
+        assert_eq!(rle.get(10),None);
+        assert_eq!(rle.run_count(),5);
+    }
+}
@@ -0,0 +1,257 @@
+//! A segmented vector, storing its elements in fixed-size chunks.
+
+use crate::prelude::*;
+
+use std::iter::FromIterator;
+use std::slice;
+
+
+
+// ==============
+// === SegVec ===
+// ==============
+
+/// Default number of elements in a single chunk of `SegVec`.
+pub const DEFAULT_CHUNK_SIZE : usize = 64;
+
+/// A growable array type, similar to `Vec<T>`, which stores its elements in chunks of a fixed size.
+/// Chunks are allocated with their full capacity and never grow, so pushing new elements never
+/// moves the existing ones. Addresses of the elements (and slices of a single chunk) stay valid
+/// until the elements are popped, which makes the structure a good fit for data shared with
+/// external code, like buffers uploaded to the GPU. The price is an additional indirection on each
+/// access and the lack of a single contiguous slice of all the elements.
+#[derive(Debug)]
+pub struct SegVec<T> {
+    chunks     : Vec<Vec<T>>,
+    chunk_size : usize,
+    len        : usize,
+}
+
+impl<T> SegVec<T> {
+    /// Constructor. Uses the `DEFAULT_CHUNK_SIZE`.
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Constructor of a vector storing `chunk_size` elements in every chunk.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(chunk_size:usize) -> Self {
+        assert!(chunk_size > 0, "The chunk size of a SegVec must not be zero.");
+        let chunks = default();
+        let len    = 0;
+        Self {chunks,chunk_size,len}
+    }
+
+    /// Number of elements in a single chunk.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the vector is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append the element and return its index. Existing elements are never moved.
+    pub fn push(&mut self, value:T) -> usize {
+        let index = self.len;
+        match self.chunks.last_mut() {
+            Some(chunk) if chunk.len() < self.chunk_size => chunk.push(value),
+            _ => {
+                let mut chunk = Vec::with_capacity(self.chunk_size);
+                chunk.push(value);
+                self.chunks.push(chunk);
+            }
+        }
+        self.len += 1;
+        index
+    }
+
+    /// Remove and return the last element. The last chunk is released when it becomes empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let chunk = self.chunks.last_mut()?;
+        let value = chunk.pop();
+        if chunk.is_empty() { self.chunks.pop(); }
+        self.len -= 1;
+        value
+    }
+
+    /// Remove all the elements and release all the chunks.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// Reference to the element at the index.
+    pub fn get(&self, index:usize) -> Option<&T> {
+        let (chunk,offset) = self.location(index);
+        self.chunks.get(chunk)?.get(offset)
+    }
+
+    /// Mutable reference to the element at the index.
+    pub fn get_mut(&mut self, index:usize) -> Option<&mut T> {
+        let (chunk,offset) = self.location(index);
+        self.chunks.get_mut(chunk)?.get_mut(offset)
+    }
+
+    /// Reference to the first element.
+    pub fn first(&self) -> Option<&T> {
+        self.chunks.first()?.first()
+    }
+
+    /// Reference to the last element.
+    pub fn last(&self) -> Option<&T> {
+        self.chunks.last()?.last()
+    }
+
+    /// Iterator over the elements.
+    pub fn iter(&self) -> impl Iterator<Item=&T> {
+        self.chunks.iter().flatten()
+    }
+
+    /// Iterator over mutable references to the elements.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=&mut T> {
+        self.chunks.iter_mut().flatten()
+    }
+
+    /// Iterator over the chunks, as contiguous slices of elements. All chunks but the last one are
+    /// full.
+    pub fn chunks(&self) -> impl Iterator<Item=&[T]> {
+        self.chunks.iter().map(|chunk| chunk.as_slice())
+    }
+
+    /// Index of the chunk containing the element at the index, and the offset within the chunk.
+    fn location(&self, index:usize) -> (usize,usize) {
+        (index / self.chunk_size, index % self.chunk_size)
+    }
+}
+
+impl<T:Clone> Clone for SegVec<T> {
+    /// Clones the elements into chunks allocated with their full capacity, so pushing to the clone
+    /// does not move its elements either.
+    fn clone(&self) -> Self {
+        let chunk_size = self.chunk_size;
+        let len        = self.len;
+        let chunks     = self.chunks.iter().map(|chunk| {
+            let mut clone = Vec::with_capacity(chunk_size);
+            clone.extend_from_slice(chunk);
+            clone
+        }).collect();
+        Self {chunks,chunk_size,len}
+    }
+}
+
+impl<T> Default for SegVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T:PartialEq> PartialEq for SegVec<T> {
+    fn eq(&self, other:&Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T:Eq> Eq for SegVec<T> {}
+
+impl<T> std::ops::Index<usize> for SegVec<T> {
+    type Output = T;
+    fn index(&self, index:usize) -> &T {
+        let len = self.len;
+        self.get(index).unwrap_or_else(|| panic!("Index {} out of bounds ({}).",index,len))
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for SegVec<T> {
+    fn index_mut(&mut self, index:usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(index).unwrap_or_else(|| panic!("Index {} out of bounds ({}).",index,len))
+    }
+}
+
+impl<T> Extend<T> for SegVec<T> {
+    fn extend<I:IntoIterator<Item=T>>(&mut self, iter:I) {
+        for value in iter { self.push(value); }
+    }
+}
+
+impl<T> FromIterator<T> for SegVec<T> {
+    fn from_iter<I:IntoIterator<Item=T>>(iter:I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<'a,T> IntoIterator for &'a SegVec<T> {
+    type Item     = &'a T;
+    type IntoIter = std::iter::Flatten<slice::Iter<'a,Vec<T>>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunks.iter().flatten()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_pop() {
+        let mut vec = SegVec::with_chunk_size(2);
+        assert_eq!(vec.push(1),0);
+        assert_eq!(vec.push(2),1);
+        assert_eq!(vec.push(3),2);
+        assert_eq!(vec.len(),3);
+        assert_eq!(vec.chunks().collect_vec(),vec![&[1,2][..],&[3][..]]);
+        vec[1] = 5;
+        assert_eq!(vec.get(1),Some(&5));
+        assert_eq!(vec.get(3),None);
+        assert_eq!(vec.pop(),Some(3));
+        assert_eq!(vec.chunks().count(),1);
+        assert_eq!(vec.iter().copied().collect_vec(),vec![1,5]);
+        assert_eq!(vec.pop(),Some(5));
+        assert_eq!(vec.pop(),Some(1));
+        assert_eq!(vec.pop(),None);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn elements_are_never_moved() {
+        let mut vec = SegVec::with_chunk_size(4);
+        let mut addresses : Vec<*const usize> = default();
+        for t in 0..100 {
+            vec.push(t);
+            addresses.push(&vec[t]);
+        }
+        for (index,address) in addresses.into_iter().enumerate() {
+            assert!(std::ptr::eq(&vec[index],address));
+        }
+    }
+
+    #[test]
+    fn elements_of_clones_are_never_moved() {
+        let vec       = (0..10).collect::<SegVec<usize>>();
+        let mut clone = vec.clone();
+        let mut addresses : Vec<*const usize> = default();
+        for t in &clone { addresses.push(t) }
+        for t in 10..200 { clone.push(t); }
+        for (index,address) in addresses.into_iter().enumerate() {
+            assert!(std::ptr::eq(&clone[index],address));
+        }
+        assert_eq!(vec.len(),10);
+    }
+}
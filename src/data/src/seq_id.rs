@@ -0,0 +1,301 @@
+//! Dense ordered identifiers of sequence elements, for sequences edited concurrently by several
+//! replicas, like texts in collaborative editors.
+//!
+//! An identifier is a path of digits, compared lexicographically. A new identifier can always be
+//! generated between any two different identifiers, so elements never have to be renumbered, and
+//! every digit carries the replica which generated it, so identifiers generated concurrently by
+//! different replicas are different. This is the approach of the Logoot and LSEQ algorithms. The
+//! number of digit values available doubles with every level of the path, and new digits are
+//! allocated close to the left neighbour, so the identifiers grow logarithmically when elements
+//! are appended or inserted at the same place one after another.
+
+use crate::prelude::*;
+
+
+
+
+// =============
+// === SeqId ===
+// =============
+
+/// Identifier of the replica generating `SeqId`s. Replicas editing the same sequence have to use
+/// different identifiers.
+pub type ReplicaId = u64;
+
+/// A single level of a `SeqId` path.
+#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct Digit { pub value:u32, pub replica:ReplicaId }
+
+/// Identifier of a sequence element. See the module docs to learn more.
+#[derive(Clone,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
+#[cfg_attr(feature="serde",serde(transparent))]
+pub struct SeqId {
+    digits : Vec<Digit>,
+}
+
+impl SeqId {
+    /// The digits of the path, starting from the most significant one.
+    pub fn digits(&self) -> &[Digit] {
+        &self.digits
+    }
+
+    /// The length of the path.
+    pub fn depth(&self) -> usize {
+        self.digits.len()
+    }
+}
+
+
+
+// ======================
+// === SeqIdGenerator ===
+// ======================
+
+/// The number of digit values available on the first level of the path.
+const INITIAL_BASE_BITS : u32 = 5;
+
+/// The default maximum distance between a new digit and the digit of the left neighbour.
+pub const DEFAULT_BOUNDARY : u32 = 10;
+
+/// Generator of `SeqId`s for a single replica.
+#[derive(Clone,Copy,Debug)]
+pub struct SeqIdGenerator {
+    replica  : ReplicaId,
+    boundary : u32,
+}
+
+impl SeqIdGenerator {
+    /// Constructor.
+    pub fn new(replica:ReplicaId) -> Self {
+        Self::with_boundary(replica,DEFAULT_BOUNDARY)
+    }
+
+    /// Constructor. New digits are generated at most `boundary` values after the digit of the left
+    /// neighbour. Smaller boundaries leave more space for appending, bigger ones for inserting
+    /// before existing elements.
+    ///
+    /// # Panics
+    /// Panics if `boundary` is 0.
+    pub fn with_boundary(replica:ReplicaId, boundary:u32) -> Self {
+        assert!(boundary > 0,"The boundary of a SeqIdGenerator has to be positive.");
+        Self {replica,boundary}
+    }
+
+    /// The replica of the generated identifiers.
+    pub fn replica(&self) -> ReplicaId {
+        self.replica
+    }
+
+    /// The number of digit values available on the level of the path.
+    fn base(depth:usize) -> u64 {
+        let bits = (INITIAL_BASE_BITS as usize + depth).min(32);
+        1 << bits
+    }
+
+    /// Generate an identifier between the given ones. `None` stands for the beginning or the end of
+    /// the sequence. The result is different from all the identifiers generated by other replicas,
+    /// and from the ones generated by this replica for other neighbours.
+    ///
+    /// # Panics
+    /// Panics if `left` is not smaller than `right`.
+    pub fn between(&self, left:Option<&SeqId>, right:Option<&SeqId>) -> SeqId {
+        if let (Some(left),Some(right)) = (left,right) {
+            assert!(left < right,"Cannot generate a SeqId between {:?} and {:?}.",left,right);
+        }
+        let left_digits     = left.map(|t| t.digits()).unwrap_or_default();
+        let right_digits    = right.map(|t| t.digits()).unwrap_or_default();
+        let mut digits      = Vec::new();
+        let mut left_bound  = true;
+        let mut right_bound = right.is_some();
+        for depth in 0.. {
+            let left_digit  = left_digits.get(depth).filter(|_| left_bound);
+            let right_digit = right_digits.get(depth).filter(|_| right_bound);
+            let low         = left_digit.map_or(0,|t| t.value as u64);
+            let high        = right_digit.map_or(Self::base(depth),|t| t.value as u64);
+            if high > low + 1 {
+                let step  = self.boundary.min(((high - low) / 2).max(1) as u32);
+                let value = low as u32 + step;
+                digits.push(Digit {value,replica:self.replica});
+                break
+            }
+            // Generated digits are never 0, so the filler digit is smaller than the right digit
+            // unless the latter is a filler too, which is never the last digit of the path.
+            let digit = match (left_digit,right_digit) {
+                (Some(digit),_)                        => *digit,
+                (None,Some(digit)) if digit.value == 0 => *digit,
+                (None,_)                               => Digit {value:0,replica:self.replica},
+            };
+            left_bound  = left_digit.is_some();
+            right_bound = right_bound && right_digit == Some(&digit);
+            digits.push(digit);
+        }
+        SeqId {digits}
+    }
+}
+
+
+
+// ==============
+// === SeqMap ===
+// ==============
+
+/// A sequence of elements identified by `SeqId`s. Elements inserted by this replica get new
+/// identifiers, while elements received from other replicas keep theirs, so all the replicas order
+/// the elements in the same way.
+#[derive(Clone,Debug)]
+pub struct SeqMap<T> {
+    generator : SeqIdGenerator,
+    elements  : BTreeMap<SeqId,T>,
+}
+
+impl<T> SeqMap<T> {
+    /// Constructor.
+    pub fn new(replica:ReplicaId) -> Self {
+        Self::with_generator(SeqIdGenerator::new(replica))
+    }
+
+    /// Constructor.
+    pub fn with_generator(generator:SeqIdGenerator) -> Self {
+        let elements = default();
+        Self {generator,elements}
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Check whether the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// The element with the identifier.
+    pub fn get(&self, id:&SeqId) -> Option<&T> {
+        self.elements.get(id)
+    }
+
+    /// The element with the identifier.
+    pub fn get_mut(&mut self, id:&SeqId) -> Option<&mut T> {
+        self.elements.get_mut(id)
+    }
+
+    /// The identifier of the element at the position.
+    pub fn id_at(&self, index:usize) -> Option<&SeqId> {
+        self.elements.keys().nth(index)
+    }
+
+    /// Iterator over the identifiers and elements in the sequence order.
+    pub fn iter(&self) -> impl Iterator<Item=(&SeqId,&T)> {
+        self.elements.iter()
+    }
+
+    /// Iterator over the elements in the sequence order.
+    pub fn values(&self) -> impl Iterator<Item=&T> {
+        self.elements.values()
+    }
+
+    /// Insert the element at the end of the sequence. Returns its new identifier.
+    pub fn push(&mut self, element:T) -> SeqId {
+        let left = self.elements.keys().next_back().cloned();
+        self.insert_between(left.as_ref(),None,element)
+    }
+
+    /// Insert the element at the position, shifting the following elements. Returns its new
+    /// identifier.
+    ///
+    /// # Panics
+    /// Panics if `index` is bigger than the length of the sequence.
+    pub fn insert(&mut self, index:usize, element:T) -> SeqId {
+        assert!(index <= self.len(),"Insertion index {} out of bounds.",index);
+        let mut keys = self.elements.keys().skip(index.saturating_sub(1));
+        let (left,right) = if index == 0 { (None,keys.next()) } else { (keys.next(),keys.next()) };
+        let (left,right) = (left.cloned(),right.cloned());
+        self.insert_between(left.as_ref(),right.as_ref(),element)
+    }
+
+    /// Insert the element between the given identifiers. `None` stands for the beginning or the
+    /// end of the sequence. Returns its new identifier.
+    ///
+    /// # Panics
+    /// Panics if `left` is not smaller than `right`.
+    pub fn insert_between
+    (&mut self, left:Option<&SeqId>, right:Option<&SeqId>, element:T) -> SeqId {
+        let id = self.generator.between(left,right);
+        self.elements.insert(id.clone(),element);
+        id
+    }
+
+    /// Insert the element with an identifier generated by another replica. Returns the element
+    /// previously stored with this identifier.
+    pub fn insert_with_id(&mut self, id:SeqId, element:T) -> Option<T> {
+        self.elements.insert(id,element)
+    }
+
+    /// Remove the element with the identifier.
+    pub fn remove(&mut self, id:&SeqId) -> Option<T> {
+        self.elements.remove(id)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_generated_between_neighbours() {
+        let generator = SeqIdGenerator::new(1);
+        let mut ids   = vec![generator.between(None,None)];
+        for i in 0..500 {
+            let (left,right) = match i % 3 {
+                0 => (ids.last(),None),
+                1 => (None,ids.first()),
+                _ => (ids.get(ids.len() / 2 - 1),ids.get(ids.len() / 2)),
+            };
+            let id = generator.between(left,right);
+            assert!(left.iter().all(|left| *left < &id));
+            assert!(right.iter().all(|right| &id < *right));
+            ids.push(id);
+            ids.sort();
+        }
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn appending_grows_ids_logarithmically() {
+        let mut seq = SeqMap::new(1);
+        for i in 0..10_000 { seq.push(i); }
+        assert_eq!(seq.values().copied().collect_vec(),(0..10_000).collect_vec());
+        assert!(seq.iter().all(|(id,_)| id.depth() <= 12));
+    }
+
+    #[test]
+    fn concurrent_insertions_are_merged() {
+        let mut seq1 = SeqMap::new(1);
+        let a        = seq1.push('a');
+        let c        = seq1.push('c');
+        let mut seq2 = SeqMap::new(2);
+        seq2.insert_with_id(a.clone(),'a');
+        seq2.insert_with_id(c.clone(),'c');
+        let b1 = seq1.insert(1,'b');
+        let b2 = seq2.insert_between(Some(&a),Some(&c),'B');
+        assert_ne!(b1,b2);
+        seq1.insert_with_id(b2,'B');
+        seq2.insert_with_id(b1,'b');
+        assert_eq!(seq1.values().collect::<String>(),seq2.values().collect::<String>());
+        assert_eq!(seq1.len(),4);
+        assert_eq!(seq1.id_at(0),Some(&a));
+        assert_eq!(seq1.remove(&c),Some('c'));
+        assert_eq!(seq1.insert(0,'_'),*seq1.id_at(0).unwrap());
+        assert_eq!(seq1.values().next(),Some(&'_'));
+    }
+}
@@ -0,0 +1,236 @@
+//! A set implementation based on a sorted vector.
+
+use crate::prelude::*;
+
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+use std::slice;
+
+
+
+// =================
+// === SortedVec ===
+// =================
+
+/// A set of values kept in a sorted vector. Queries are binary searches over a contiguous memory,
+/// so for small and read-heavy collections it is both faster and lighter than `BTreeSet`. Inserting
+/// and removing values is `O(n)`, as the following elements need to be moved.
+#[derive(Clone,Debug,Derivative,Eq,Hash,PartialEq)]
+#[derivative(Default(bound=""))]
+pub struct SortedVec<T> {
+    vec : Vec<T>,
+}
+
+impl<T:Ord> SortedVec<T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Insert the value. Returns false if the value was already present.
+    pub fn insert(&mut self, value:T) -> bool {
+        match self.vec.binary_search(&value) {
+            Ok(_)      => false,
+            Err(index) => { self.vec.insert(index,value); true }
+        }
+    }
+
+    /// Remove the value. Returns false if the value was not present.
+    pub fn remove(&mut self, value:&T) -> bool {
+        match self.vec.binary_search(value) {
+            Ok(index) => { self.vec.remove(index); true }
+            Err(_)    => false,
+        }
+    }
+
+    /// Check whether the value is present in the set.
+    pub fn contains(&self, value:&T) -> bool {
+        self.vec.binary_search(value).is_ok()
+    }
+
+    /// The sorted slice of values within the range.
+    pub fn range(&self, range:impl RangeBounds<T>) -> &[T] {
+        let start = match range.start_bound() {
+            Bound::Included(t) => self.vec.partition_point(|v| v < t),
+            Bound::Excluded(t) => self.vec.partition_point(|v| v <= t),
+            Bound::Unbounded   => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(t) => self.vec.partition_point(|v| v <= t),
+            Bound::Excluded(t) => self.vec.partition_point(|v| v < t),
+            Bound::Unbounded   => self.vec.len(),
+        };
+        &self.vec[start..end.max(start)]
+    }
+
+    /// The smallest value.
+    pub fn first(&self) -> Option<&T> {
+        self.vec.first()
+    }
+
+    /// The biggest value.
+    pub fn last(&self) -> Option<&T> {
+        self.vec.last()
+    }
+
+    /// Iterator over the values in ascending order.
+    pub fn iter(&self) -> slice::Iter<T> {
+        self.vec.iter()
+    }
+
+    /// The sorted slice of all values.
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
+    }
+
+    /// Convert to the sorted vector of values.
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+}
+
+
+// === Set Operations ===
+
+impl<T:Ord+Clone> SortedVec<T> {
+    /// Values present in any of the sets.
+    pub fn union(&self, other:&Self) -> Self {
+        self.merge(other,true,true,true)
+    }
+
+    /// Values present in both sets.
+    pub fn intersection(&self, other:&Self) -> Self {
+        self.merge(other,false,true,false)
+    }
+
+    /// Values present in this set, but not in the `other` one.
+    pub fn difference(&self, other:&Self) -> Self {
+        self.merge(other,true,false,false)
+    }
+
+    /// Values present in exactly one of the sets.
+    pub fn symmetric_difference(&self, other:&Self) -> Self {
+        self.merge(other,true,false,true)
+    }
+
+    /// Merge two sorted sequences in linear time, keeping the values present only in this set, in
+    /// both sets, and only in the other set, respectively, as selected by the flags.
+    fn merge(&self, other:&Self, keep_left:bool, keep_both:bool, keep_right:bool) -> Self {
+        let mut vec   = Vec::new();
+        let mut left  = self.vec.iter().peekable();
+        let mut right = other.vec.iter().peekable();
+        loop {
+            let (value,keep) = match (left.peek(),right.peek()) {
+                (None,None)       => break,
+                (Some(_),None)    => (left.next(),keep_left),
+                (None,Some(_))    => (right.next(),keep_right),
+                (Some(l),Some(r)) => match l.cmp(r) {
+                    Ordering::Less    => (left.next(),keep_left),
+                    Ordering::Greater => (right.next(),keep_right),
+                    Ordering::Equal   => { right.next(); (left.next(),keep_both) }
+                }
+            };
+            if keep { vec.extend(value.cloned()) }
+        }
+        Self {vec}
+    }
+}
+
+
+// === Conversions ===
+
+impl<T:Ord> From<Vec<T>> for SortedVec<T> {
+    fn from(mut vec:Vec<T>) -> Self {
+        vec.sort();
+        vec.dedup();
+        Self {vec}
+    }
+}
+
+impl<T:Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I:IntoIterator<Item=T>>(iter:I) -> Self {
+        iter.into_iter().collect_vec().into()
+    }
+}
+
+impl<T:Ord> Extend<T> for SortedVec<T> {
+    fn extend<I:IntoIterator<Item=T>>(&mut self, iter:I) {
+        let mut vec = mem::take(&mut self.vec);
+        vec.extend(iter);
+        *self = vec.into();
+    }
+}
+
+impl<'a,T> IntoIterator for &'a SortedVec<T> {
+    type Item     = &'a T;
+    type IntoIter = slice::Iter<'a,T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
+
+impl<T> IntoIterator for SortedVec<T> {
+    type Item     = T;
+    type IntoIter = std::vec::IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = SortedVec::new();
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(!set.insert(3));
+        assert!(set.insert(2));
+        assert_eq!(set.as_slice(),&[1,2,3]);
+        assert!(set.contains(&2));
+        assert!(set.remove(&2));
+        assert!(!set.remove(&2));
+        assert!(!set.contains(&2));
+        assert_eq!(set.as_slice(),&[1,3]);
+    }
+
+    #[test]
+    fn ranges() {
+        let set : SortedVec<_> = vec![1,3,5,7,9].into();
+        assert_eq!(set.range(3..7),&[3,5]);
+        assert_eq!(set.range(3..=7),&[3,5,7]);
+        assert_eq!(set.range(4..),&[5,7,9]);
+        assert_eq!(set.range(..=1),&[1]);
+        assert!(set.range(4..5).is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let set1 : SortedVec<_> = vec![5,1,3,3,7].into();
+        let set2 : SortedVec<_> = vec![3,4,5,6].into_iter().collect();
+        assert_eq!(set1.union(&set2).as_slice(),&[1,3,4,5,6,7]);
+        assert_eq!(set1.intersection(&set2).as_slice(),&[3,5]);
+        assert_eq!(set1.difference(&set2).as_slice(),&[1,7]);
+        assert_eq!(set1.symmetric_difference(&set2).as_slice(),&[1,4,6,7]);
+    }
+}
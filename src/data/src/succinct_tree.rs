@@ -0,0 +1,386 @@
+//! A compact, immutable tree, encoding its shape as a sequence of balanced parentheses.
+//!
+//! Every node is represented by an opening parenthesis, followed by the representations of its
+//! children and a closing parenthesis. The sequence is stored as a bit vector, so the shape of a
+//! tree of `n` nodes takes `2n` bits, plus a small index (about 6% of the bit vector) used to
+//! answer navigation queries. Keys and values are stored in flat vectors, in pre-order. This makes
+//! the tree orders of magnitude smaller than a [`HashMapTree`], which allocates a hash map for
+//! every node, at the cost of being immutable and slower to navigate.
+
+use crate::prelude::*;
+
+use crate::hash_map_tree::HashMapTree;
+
+use std::hash::BuildHasher;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const WORD_BITS   : usize = 64;
+const BLOCK_WORDS : usize = 8;
+const BLOCK_BITS  : usize = WORD_BITS * BLOCK_WORDS;
+
+
+
+// ==============
+// === Parens ===
+// ==============
+
+/// A sequence of balanced parentheses, where an opening parenthesis is stored as the `1` bit. The
+/// sequence is divided into blocks of `BLOCK_BITS` bits, and every block stores the number of
+/// opening parentheses before it, and the minimal excess (the number of opening parentheses minus
+/// the number of closing ones) within the block. Searches for matching parentheses scan the blocks
+/// linearly, but skip all the blocks which can not contain the result, so they touch only a few
+/// bits of every block.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+struct Parens {
+    words       : Vec<u64>,
+    len         : usize,
+    block_ranks : Vec<u32>,
+    block_mins  : Vec<i16>,
+}
+
+impl Parens {
+    fn push(&mut self, open:bool) {
+        if self.len % WORD_BITS == 0 { self.words.push(0); }
+        if open { self.words[self.len / WORD_BITS] |= 1 << (self.len % WORD_BITS); }
+        self.len += 1;
+    }
+
+    /// Compute the block index. Has to be called after all the parentheses were pushed.
+    fn build_index(&mut self) {
+        let block_count  = (self.len + BLOCK_BITS - 1) / BLOCK_BITS;
+        let mut rank     = 0;
+        self.block_ranks = Vec::with_capacity(block_count + 1);
+        self.block_mins  = Vec::with_capacity(block_count);
+        for block in 0..block_count {
+            self.block_ranks.push(rank as u32);
+            let start      = block * BLOCK_BITS;
+            let end        = (start + BLOCK_BITS).min(self.len);
+            let mut excess = 0;
+            let mut min    = i16::MAX;
+            for index in start..end {
+                let open = self.get(index);
+                excess  += if open { 1 } else { -1 };
+                min      = min.min(excess);
+                rank    += open as usize;
+            }
+            self.block_mins.push(min);
+        }
+        self.block_ranks.push(rank as u32);
+    }
+
+    fn get(&self, index:usize) -> bool {
+        (self.words[index / WORD_BITS] >> (index % WORD_BITS)) & 1 == 1
+    }
+
+    fn step(&self, index:usize) -> isize {
+        if self.get(index) { 1 } else { -1 }
+    }
+
+    /// Number of opening parentheses before the index.
+    fn rank(&self, index:usize) -> usize {
+        let block     = index / BLOCK_BITS;
+        let word      = index / WORD_BITS;
+        let remainder = index % WORD_BITS;
+        let full      = self.words[block * BLOCK_WORDS..word].iter();
+        let mut rank  = self.block_ranks[block] as usize;
+        rank += full.map(|word| word.count_ones() as usize).sum::<usize>();
+        if remainder > 0 {
+            rank += (self.words[word] & ((1 << remainder) - 1)).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// Position of the opening parenthesis with the given rank.
+    fn select(&self, rank:usize) -> usize {
+        let block         = self.block_ranks.partition_point(|t| *t as usize <= rank) - 1;
+        let mut remaining = rank - self.block_ranks[block] as usize;
+        for (index,word) in self.words[block * BLOCK_WORDS..].iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                let mut word = *word;
+                for _ in 0..remaining { word &= word - 1; }
+                return (block * BLOCK_WORDS + index) * WORD_BITS + word.trailing_zeros() as usize
+            }
+            remaining -= ones;
+        }
+        panic!("Rank {} out of bounds.",rank)
+    }
+
+    /// Excess of all the parentheses before the index.
+    fn excess(&self, index:usize) -> isize {
+        2 * self.rank(index) as isize - index as isize
+    }
+
+    /// Position of the closing parenthesis matching the opening one at the index.
+    fn find_close(&self, index:usize) -> usize {
+        let target     = self.excess(index);
+        let mut excess = target + 1;
+        let block      = index / BLOCK_BITS;
+        let block_end  = ((block + 1) * BLOCK_BITS).min(self.len);
+        let found      = self.scan_forward(index + 1..block_end,&mut excess,target);
+        found.or_else(|| {
+            (block + 1 .. self.block_mins.len()).find_map(|block| {
+                let start      = block * BLOCK_BITS;
+                let mut excess = self.excess(start);
+                if excess + self.block_mins[block] as isize > target { return None }
+                let end = (start + BLOCK_BITS).min(self.len);
+                self.scan_forward(start..end,&mut excess,target)
+            })
+        }).expect("Unbalanced parentheses.")
+    }
+
+    fn scan_forward(&self, range:Range<usize>, excess:&mut isize, target:isize) -> Option<usize> {
+        range.into_iter().find(|index| {
+            *excess += self.step(*index);
+            *excess == target
+        })
+    }
+
+    /// Position of the opening parenthesis of the pair enclosing the one at the index.
+    fn enclose(&self, index:usize) -> Option<usize> {
+        if index == 0 { return None }
+        let target      = self.excess(index) - 1;
+        let block       = index / BLOCK_BITS;
+        let block_start = block * BLOCK_BITS;
+        let found       = self.scan_backward(block_start..index,target);
+        let found       = found.or_else(|| {
+            (0..block).rev().find_map(|block| {
+                let start = block * BLOCK_BITS;
+                if self.excess(start) + self.block_mins[block] as isize > target { return None }
+                self.scan_backward(start..start + BLOCK_BITS,target)
+            })
+        });
+        // The pair enclosing all the others starts at 0, after the excess of 0 at index `-1`.
+        Some(found.map_or(0,|index| index + 1))
+    }
+
+    /// The last index in the range, after which the excess is equal to the target.
+    fn scan_backward(&self, range:Range<usize>, target:isize) -> Option<usize> {
+        let mut excess = self.excess(range.end);
+        range.rev().find(|index| {
+            let found = excess == target;
+            excess -= self.step(*index);
+            found
+        })
+    }
+}
+
+
+
+// ====================
+// === SuccinctTree ===
+// ====================
+
+/// Identifier of a node of a [`SuccinctTree`], equal to its index in the pre-order traversal. The
+/// root is always the node `0`.
+pub type NodeId = usize;
+
+/// A compact, immutable tree with keyed branches, like a read-only [`HashMapTree`]. See the module
+/// docs to learn more.
+///
+/// Navigation queries (`parent`, `first_child`, `next_sibling`, and `subtree_size`) skip whole
+/// blocks of the parentheses sequence, so they are fast in practice, but in the worst case they are
+/// linear in the number of blocks between the node and the result. Looking up a child by its key is
+/// linear in the number of children.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct SuccinctTree<K,V> {
+    parens : Parens,
+    keys   : Vec<K>,
+    values : Vec<V>,
+}
+
+impl<K,V> SuccinctTree<K,V> {
+    /// Number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether the tree has no nodes. Only the default tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The root node, or `None` if the tree is empty.
+    pub fn root(&self) -> Option<NodeId> {
+        (!self.is_empty()).as_some(0)
+    }
+
+    /// The value of the node.
+    pub fn value(&self, node:NodeId) -> &V {
+        &self.values[node]
+    }
+
+    /// All the values, in pre-order.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// The key of the branch leading to the node. The root has no key.
+    pub fn key(&self, node:NodeId) -> Option<&K> {
+        node.checked_sub(1).map(|index| &self.keys[index])
+    }
+
+    /// The parent of the node, or `None` for the root.
+    pub fn parent(&self, node:NodeId) -> Option<NodeId> {
+        let parent = self.parens.enclose(self.parens.select(node))?;
+        Some(self.parens.rank(parent))
+    }
+
+    /// The first child of the node.
+    pub fn first_child(&self, node:NodeId) -> Option<NodeId> {
+        let index = self.parens.select(node) + 1;
+        self.parens.get(index).as_some(node + 1)
+    }
+
+    /// The next child of the parent of the node.
+    pub fn next_sibling(&self, node:NodeId) -> Option<NodeId> {
+        let index = self.parens.find_close(self.parens.select(node)) + 1;
+        (index < self.parens.len && self.parens.get(index)).as_some_from(|| self.parens.rank(index))
+    }
+
+    /// Iterator over the children of the node.
+    pub fn children(&self, node:NodeId) -> impl Iterator<Item=NodeId> + '_ {
+        iter::successors(self.first_child(node),move |child| self.next_sibling(*child))
+    }
+
+    /// Number of nodes in the subtree of the node, including the node itself.
+    pub fn subtree_size(&self, node:NodeId) -> usize {
+        let open = self.parens.select(node);
+        (self.parens.find_close(open) - open + 1) / 2
+    }
+
+    /// Number of ancestors of the node.
+    pub fn depth(&self, node:NodeId) -> usize {
+        let open = self.parens.select(node);
+        (self.parens.excess(open + 1) - 1) as usize
+    }
+}
+
+impl<K:PartialEq,V> SuccinctTree<K,V> {
+    /// The child of the node with the given key.
+    pub fn child(&self, node:NodeId, key:&K) -> Option<NodeId> {
+        self.children(node).find(|child| self.key(*child) == Some(key))
+    }
+
+    /// The node at the end of the path of keys, starting at the root.
+    pub fn get_node<'a>(&self, path:impl IntoIterator<Item=&'a K>) -> Option<NodeId>
+    where K:'a {
+        path.into_iter().try_fold(self.root()?,|node,key| self.child(node,key))
+    }
+
+    /// The value of the node at the end of the path of keys, starting at the root.
+    pub fn get<'a>(&self, path:impl IntoIterator<Item=&'a K>) -> Option<&V>
+    where K:'a {
+        self.get_node(path).map(|node| self.value(node))
+    }
+}
+
+
+// === Conversions ===
+
+impl<K:Clone,V:Clone,S> From<&HashMapTree<K,V,S>> for SuccinctTree<K,V> {
+    fn from(tree:&HashMapTree<K,V,S>) -> Self {
+        let mut parens = Parens::default();
+        let mut keys   = Vec::new();
+        let mut values = vec![tree.value.clone()];
+        let mut stack  = vec![tree.branches.iter()];
+        parens.push(true);
+        while let Some(branches) = stack.last_mut() {
+            match branches.next() {
+                None => {
+                    parens.push(false);
+                    stack.pop();
+                }
+                Some((key,branch)) => {
+                    parens.push(true);
+                    keys.push(key.clone());
+                    values.push(branch.value.clone());
+                    stack.push(branch.branches.iter());
+                }
+            }
+        }
+        parens.build_index();
+        Self {parens,keys,values}
+    }
+}
+
+impl<K,V> SuccinctTree<K,V> {
+    /// Convert to a [`HashMapTree`]. Returns `None` if the tree is empty.
+    pub fn to_hash_map_tree<S>(&self) -> Option<HashMapTree<K,V,S>>
+    where K:Clone+Eq+Hash, V:Clone, S:BuildHasher+Default {
+        // Children follow their parents in pre-order, so they are converted first when iterating
+        // in reverse.
+        let mut nodes : Vec<Option<HashMapTree<K,V,S>>> = (0..self.len()).map(|_| None).collect();
+        for node in (0..self.len()).rev() {
+            let value    = self.value(node).clone();
+            let branches = self.children(node).filter_map(|child| {
+                let key = self.key(child)?.clone();
+                Some((key,nodes[child].take()?))
+            }).collect();
+            nodes[node] = Some(HashMapTree {value,branches});
+        }
+        nodes.into_iter().next().flatten()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_map_tree::SegmentIter;
+
+    fn sample_tree() -> HashMapTree<usize,usize> {
+        let mut tree = HashMapTree::from_value(0);
+        for child in 1..=3 {
+            tree.set(vec![child],child);
+            for grandchild in 1..=200 {
+                tree.set(vec![child,grandchild],child * 1000 + grandchild);
+            }
+        }
+        tree.set(vec![2_usize,7,7],2077);
+        tree
+    }
+
+    #[test]
+    fn navigation() {
+        let tree     = sample_tree();
+        let succinct = SuccinctTree::from(&tree);
+        assert_eq!(succinct.len(),605);
+        assert_eq!(succinct.subtree_size(0),605);
+        assert_eq!(succinct.children(0).count(),3);
+        for (path,value) in &tree {
+            let node = succinct.get_node(path.iter().copied()).unwrap();
+            assert_eq!(succinct.value(node),value);
+            assert_eq!(succinct.depth(node),path.len());
+            assert_eq!(succinct.key(node),path.last().copied());
+            let parent_path = path.split_last().map(|(_,parent_path)| parent_path);
+            let parent      = parent_path.and_then(|t| succinct.get_node(t.iter().copied()));
+            assert_eq!(succinct.parent(node),parent);
+            let children = tree.get_node(SegmentIter(path.iter().copied())).unwrap().branches.len();
+            assert_eq!(succinct.children(node).count(),children);
+        }
+        assert_eq!(succinct.get(&[2,7,7]),Some(&2077));
+        assert_eq!(succinct.subtree_size(succinct.get_node(&[2]).unwrap()),202);
+        assert_eq!(succinct.get(&[4]),None);
+    }
+
+    #[test]
+    fn conversion_round_trip() {
+        let tree     = sample_tree();
+        let succinct = SuccinctTree::from(&tree);
+        assert_eq!(succinct.to_hash_map_tree(),Some(tree));
+        let empty = SuccinctTree::<usize,usize>::default();
+        assert_eq!(empty.root(),None);
+        assert_eq!(empty.to_hash_map_tree::<std::collections::hash_map::RandomState>(),None);
+    }
+}
@@ -2,13 +2,19 @@
 
 use enso_prelude::*;
 
-use serde::Deserialize;
-use serde::Serialize;
+use crate::interval_tree::Interval;
+use crate::interval_tree::IntervalTree;
+use crate::overflow;
+use crate::overflow::OverflowError;
+use crate::rle::Rle;
+
 use std::ops::Add;
 use std::ops::AddAssign;
 use std::ops::Range;
+use std::ops::RangeInclusive;
 use std::ops::Sub;
 use std::ops::SubAssign;
+use std::collections::BTreeMap;
 
 
 
@@ -20,7 +26,8 @@ use std::ops::SubAssign;
 
 /// Strongly typed index into container.
 #[allow(missing_docs)]
-#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord,Serialize,Deserialize)]
+#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
 pub struct Index { pub value:usize }
 
 impl Index {
@@ -39,6 +46,16 @@ impl Index {
     pub fn checked_sub(self, rhs:Size) -> Option<Self> {
         self.value.checked_sub(rhs.value).map(Self::new)
     }
+
+    /// Computes `self + rhs` with an explicit overflow policy.
+    pub fn add_with(self, rhs:Size, policy:overflow::Policy) -> Result<Self,OverflowError> {
+        policy.add(self.value,rhs.value).map(Self::new)
+    }
+
+    /// Computes `self - rhs` with an explicit overflow policy.
+    pub fn sub_with(self, rhs:Size, policy:overflow::Policy) -> Result<Self,OverflowError> {
+        policy.sub(self.value,rhs.value).map(Self::new)
+    }
 }
 
 impl Display for Index {
@@ -55,7 +72,8 @@ impl Display for Index {
 //TODO[ao] We should use structures from ensogl::math::topology to represent different quantities
 // and units.
 #[allow(missing_docs)]
-#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord,Serialize,Deserialize)]
+#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
 pub struct ByteIndex { pub value:usize }
 
 impl ByteIndex {
@@ -80,7 +98,8 @@ impl ByteIndex {
 
 /// Strongly typed size of container.
 #[allow(missing_docs)]
-#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord,Serialize,Deserialize)]
+#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
 pub struct Size { pub value:usize }
 
 impl Size {
@@ -113,7 +132,7 @@ impl Size {
 impl Add for Size {
     type Output = Size;
     fn add(self, rhs:Size) -> Size {
-        Size {value:self.value + rhs.value}
+        Size {value:overflow::add(self.value,rhs.value)}
     }
 }
 
@@ -126,7 +145,7 @@ impl AddAssign for Size {
 impl Sub for Size {
     type Output = Size;
     fn sub(self, rhs:Size) -> Size {
-        Size{value: self.value - rhs.value}
+        Size {value:overflow::sub(self.value,rhs.value)}
     }
 }
 
@@ -147,7 +166,8 @@ impl Display for Size {
 
 /// Strongly typed span into container with index and size.
 #[allow(missing_docs)]
-#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord,Serialize,Deserialize)]
+#[derive(Clone,Copy,Debug,Default,Hash,PartialEq,Eq,PartialOrd,Ord)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
 pub struct Span { pub index:Index, pub size:Size }
 
 impl Span {
@@ -321,7 +341,7 @@ impl From<Range<Index>> for Span {
 impl Add<Size> for Index {
     type Output = Index;
     fn add(self, rhs:Size) -> Index {
-        Index {value:self.value + rhs.value}
+        Index {value:overflow::add(self.value,rhs.value)}
     }
 }
 
@@ -334,7 +354,7 @@ impl AddAssign<Size> for Index {
 impl Sub<Size> for Index {
     type Output = Index;
     fn sub(self, rhs:Size) -> Index {
-        Index {value:self.value - rhs.value}
+        Index {value:overflow::sub(self.value,rhs.value)}
     }
 }
 
@@ -347,7 +367,7 @@ impl SubAssign<Size> for Index {
 impl Sub for Index {
     type Output = Size;
     fn sub(self, rhs:Index) -> Size {
-        Size {value:self.value - rhs.value}
+        Size {value:overflow::sub(self.value,rhs.value)}
     }
 }
 
@@ -409,7 +429,7 @@ impl TextLocation {
             0 => 0,
             _ => {
                 let content = content.as_ref();
-                newline_indices(content).nth(self.line.saturating_sub(1)).map_or(0, |i| i + 1)
+                newline_indices(content).nth(self.line - 1).map_or(0, |i| i + 1)
             }
         };
         Index::new(line_index + self.column)
@@ -578,6 +598,418 @@ fn cut_cr_at_end_of_line(from:&str) -> &str {
 
 
 
+// ==================
+// === DirtyLines ===
+// ==================
+
+/// A set of line numbers which need to be refreshed, for example re-rendered or re-highlighted.
+/// Unlike a plain set, it keeps the marked lines in sync with the text edits, so a line marked
+/// before an edit refers to the same line of text after it.
+#[derive(Clone,Debug,Default)]
+pub struct DirtyLines {
+    lines : IntervalTree,
+}
+
+impl DirtyLines {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Check whether there are no dirty lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Check whether the line is marked as dirty.
+    pub fn is_dirty(&self, line:usize) -> bool {
+        self.lines.index(line).is_ok()
+    }
+
+    /// Mark the line as dirty.
+    pub fn mark(&mut self, line:usize) {
+        self.lines.insert(line)
+    }
+
+    /// Mark all the lines in the range as dirty.
+    pub fn mark_range(&mut self, lines:RangeInclusive<usize>) {
+        for line in lines { self.mark(line) }
+    }
+
+    /// Update the dirty lines after the `change` was applied to the `text`. The `text` is the
+    /// content before the change. All the lines touched by the change are marked as dirty, and the
+    /// already marked lines after the change are shifted by the number of added or removed lines.
+    pub fn apply_edit(&mut self, text:&str, change:&TextChange) {
+        let replaced = TextLocation::convert_range(text,&change.replaced);
+        let first    = replaced.start.line;
+        let removed  = replaced.end.line - first;
+        let inserted = newline_indices(&change.inserted).count();
+        let old      = mem::take(&mut self.lines);
+        for interval in old.to_vec() {
+            for line in interval.start..=interval.end {
+                if      line < first           { self.mark(line) }
+                else if line > first + removed { self.mark(line - removed + inserted) }
+            }
+        }
+        self.mark_range(first..=first + inserted);
+    }
+
+    /// Return all the dirty lines as disjoint intervals in ascending order and clear the set.
+    pub fn drain_dirty(&mut self) -> Vec<Interval> {
+        mem::take(&mut self.lines).to_vec()
+    }
+}
+
+
+
+// ===================
+// === LineLengths ===
+// ===================
+
+/// Length of a single line, not including the line ending.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub struct LineLength {
+    /// Number of chars in the line.
+    pub chars   : usize,
+    /// Number of visual columns the line takes, with tabs expanded to the next tab stop.
+    pub columns : usize,
+}
+
+impl LineLength {
+    /// Measure the line. It must not contain newline characters.
+    pub fn of(line:&str, tab_width:usize) -> Self {
+        line.chars().fold(default(),|length:Self,c| {
+            let chars   = length.chars + 1;
+            let columns = match c {
+                '\t' if tab_width > 0 => (length.columns / tab_width + 1) * tab_width,
+                _                      => length.columns + 1,
+            };
+            Self {chars,columns}
+        })
+    }
+}
+
+/// Position in the text, both in chars and in bytes.
+#[derive(Clone,Copy,Debug,Default)]
+struct Offset { chars:usize, bytes:usize }
+
+/// Lengths of all lines of a document, updated incrementally with text changes. Allows querying
+/// the longest line, e.g. to size the horizontal scrollbar, without rescanning the document.
+#[derive(Clone,Debug)]
+pub struct LineLengths {
+    tab_width : usize,
+    lines     : Vec<LineLength>,
+    /// Offsets of the starts of all the lines, followed by the offset of the end of the text.
+    /// These are the prefix sums of the line lengths, including the line endings.
+    starts    : Vec<Offset>,
+    /// Number of lines of each width in columns.
+    histogram : BTreeMap<usize,usize>,
+}
+
+impl LineLengths {
+    /// Constructor. Measures all the lines of the text.
+    pub fn new(text:&str, tab_width:usize) -> Self {
+        let lines     = default();
+        let starts    = vec![default()];
+        let histogram = default();
+        let mut this  = Self {tab_width,lines,starts,histogram};
+        this.splice(0..0,text,0);
+        this
+    }
+
+    /// Number of lines. An empty document has a single, empty line.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Length of the line, if it exists.
+    pub fn line(&self, line:usize) -> Option<LineLength> {
+        self.lines.get(line).copied()
+    }
+
+    /// Width of the longest line in columns.
+    pub fn max_columns(&self) -> usize {
+        self.histogram.keys().next_back().copied().unwrap_or_default()
+    }
+
+    /// Index of the first char of the line. Lines past the end of the document start at the end
+    /// of the text.
+    pub fn line_start(&self, line:usize) -> Index {
+        Index::new(self.starts[line.min(self.lines.len())].chars)
+    }
+
+    /// Update the lengths after the `change` was applied to the `text`. The `text` is the content
+    /// before the change. Only the lines touched by the change are read and measured again.
+    pub fn apply_edit(&mut self, text:&str, change:&TextChange) {
+        let start   = change.replaced.start.value;
+        let end     = change.replaced.end.value;
+        let first   = self.line_at(start);
+        let last    = self.line_at(end);
+        let begin   = self.starts[first];
+        let ending  = self.ending_len(last);
+        let touched = &text[begin.bytes..self.starts[last + 1].bytes - ending];
+        let prefix  = touched.chars().take(start - begin.chars);
+        let suffix  = touched.chars().skip(end - begin.chars);
+        let edited  = prefix.chain(change.inserted.chars()).chain(suffix).collect::<String>();
+        self.splice(first..last + 1,&edited,ending);
+    }
+
+    /// The line containing the char at the index.
+    fn line_at(&self, index:usize) -> usize {
+        self.starts[1..self.lines.len()].partition_point(|start| start.chars <= index)
+    }
+
+    /// Length of the line ending of the line, in chars. It is the same in bytes, as line endings
+    /// consist of ASCII chars only.
+    fn ending_len(&self, line:usize) -> usize {
+        self.starts[line + 1].chars - self.starts[line].chars - self.lines[line].chars
+    }
+
+    /// Replace the lengths of the lines in `range` with the lengths of the lines of `text`. The
+    /// last line of `text` gets a line ending of `last_ending` chars.
+    fn splice(&mut self, range:Range<usize>, text:&str, last_ending:usize) {
+        let tab_width  = self.tab_width;
+        let old_end    = self.starts[range.end];
+        let mut end    = self.starts[range.start];
+        let mut lines  = Vec::new();
+        let mut ends   = Vec::new();
+        let mut pieces = text.split('\n').peekable();
+        while let Some(piece) = pieces.next() {
+            let ending = if pieces.peek().is_some() { 1 } else { last_ending };
+            end.chars += piece.chars().count() + ending;
+            end.bytes += piece.len() + ending;
+            lines.push(LineLength::of(cut_cr_at_end_of_line(piece),tab_width));
+            ends.push(end);
+        }
+        let shifted_from = range.start + 1 + ends.len();
+        for length in &lines {
+            *self.histogram.entry(length.columns).or_default() += 1;
+        }
+        for length in self.lines.splice(range.clone(),lines) {
+            if let Some(count) = self.histogram.get_mut(&length.columns) {
+                *count -= 1;
+                if *count == 0 { self.histogram.remove(&length.columns); }
+            }
+        }
+        self.starts.splice(range.start + 1..range.end + 1,ends);
+        for start in &mut self.starts[shifted_from..] {
+            start.chars = start.chars - old_end.chars + end.chars;
+            start.bytes = start.bytes - old_end.bytes + end.bytes;
+        }
+    }
+}
+
+
+
+// ================
+// === Encoding ===
+// ================
+
+/// Unicode encoding of a text file, as indicated by its byte order mark (BOM).
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+#[allow(missing_docs)]
+pub enum Encoding { Utf8,Utf16Le,Utf16Be,Utf32Le,Utf32Be }
+
+impl Encoding {
+    /// The byte order mark of the encoding.
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Utf8    => &[0xEF,0xBB,0xBF],
+            Self::Utf16Le => &[0xFF,0xFE],
+            Self::Utf16Be => &[0xFE,0xFF],
+            Self::Utf32Le => &[0xFF,0xFE,0x00,0x00],
+            Self::Utf32Be => &[0x00,0x00,0xFE,0xFF],
+        }
+    }
+
+    /// Detect the encoding by the byte order mark at the beginning of the file content. Returns
+    /// `None` if there is no byte order mark, in which case the content is usually assumed to be
+    /// UTF-8. The mark is `bom().len()` bytes long and should be skipped when decoding the content.
+    pub fn from_bom(bytes:&[u8]) -> Option<Self> {
+        // UTF-32 LE has to be checked before UTF-16 LE, as their marks share a prefix.
+        let encodings = [Self::Utf8,Self::Utf32Le,Self::Utf32Be,Self::Utf16Le,Self::Utf16Be];
+        encodings.iter().copied().find(|encoding| bytes.starts_with(encoding.bom()))
+    }
+}
+
+
+
+// ===================
+// === LineEndings ===
+// ===================
+
+/// A line ending style.
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+#[allow(missing_docs)]
+pub enum LineEnding { Lf,CrLf,Cr }
+
+impl LineEnding {
+    /// The characters of the line ending.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf   => "\n",
+            Self::CrLf => "\r\n",
+            Self::Cr   => "\r",
+        }
+    }
+}
+
+/// The original line endings of a text normalized with `NewlineNormalizer`, one per line break,
+/// stored as runs, so a file with consistent line endings takes constant space. They allow saving
+/// the text with the same line endings it was loaded with.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct LineEndings {
+    endings : Rle<LineEnding>,
+}
+
+impl LineEndings {
+    /// Number of line breaks.
+    pub fn len(&self) -> usize {
+        self.endings.len()
+    }
+
+    /// Check whether the text had no line breaks.
+    pub fn is_empty(&self) -> bool {
+        self.endings.is_empty()
+    }
+
+    /// The original ending of the line.
+    pub fn get(&self, line:usize) -> Option<LineEnding> {
+        self.endings.get(line).copied()
+    }
+
+    /// The most common line ending, or `None` if the text had no line breaks. It is the ending of
+    /// the lines added by edits. Ties are resolved in favor of LF, and then CRLF.
+    pub fn dominant(&self) -> Option<LineEnding> {
+        let runs     = || self.endings.runs();
+        let count    = |ending| runs().filter(|t| *t.1 == ending).map(|t| t.0.len()).sum::<usize>();
+        let endings  = [LineEnding::Lf,LineEnding::CrLf,LineEnding::Cr];
+        let dominant = endings.iter().rev().copied().max_by_key(|ending| count(*ending));
+        dominant.filter(|_| !self.is_empty())
+    }
+
+    /// Restorer of the line endings, which can process the normalized text chunk by chunk.
+    pub fn restorer(&self) -> LineEndingRestorer<'_> {
+        LineEndingRestorer::new(self)
+    }
+
+    /// The normalized text with the original line endings restored.
+    pub fn restore(&self, normalized:&str) -> String {
+        let mut out = String::with_capacity(normalized.len() + self.len());
+        self.restorer().push_str(normalized,&mut out);
+        out
+    }
+}
+
+
+// === NewlineNormalizer ===
+
+/// Converts CRLF and CR line endings to LF, recording the original ones. The text can be passed
+/// chunk by chunk, for example while it is read from a file, and a line ending may be split
+/// between the chunks.
+#[derive(Clone,Debug,Default)]
+pub struct NewlineNormalizer {
+    endings    : Rle<LineEnding>,
+    pending_cr : bool,
+}
+
+impl NewlineNormalizer {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Normalize the chunk, appending the result to `out`.
+    pub fn push_str(&mut self, chunk:&str, out:&mut String) {
+        let mut rest = chunk;
+        if self.pending_cr {
+            self.pending_cr = false;
+            match rest.strip_prefix('\n') {
+                Some(tail) => { self.push_ending(LineEnding::CrLf,out); rest = tail; }
+                None       => self.push_ending(LineEnding::Cr,out),
+            }
+        }
+        while let Some(index) = rest.find(|c| c == '\r' || c == '\n') {
+            out.push_str(&rest[..index]);
+            let tail = &rest[index + 1..];
+            rest = if rest.as_bytes()[index] == b'\n' {
+                self.push_ending(LineEnding::Lf,out);
+                tail
+            } else if tail.is_empty() {
+                self.pending_cr = true;
+                tail
+            } else if let Some(tail) = tail.strip_prefix('\n') {
+                self.push_ending(LineEnding::CrLf,out);
+                tail
+            } else {
+                self.push_ending(LineEnding::Cr,out);
+                tail
+            };
+        }
+        out.push_str(rest);
+    }
+
+    /// Finish the normalization, appending the line ending possibly pending at the end of the last
+    /// chunk to `out`. Returns the original line endings.
+    pub fn finish(mut self, out:&mut String) -> LineEndings {
+        if self.pending_cr { self.push_ending(LineEnding::Cr,out); }
+        let endings = self.endings;
+        LineEndings {endings}
+    }
+
+    fn push_ending(&mut self, ending:LineEnding, out:&mut String) {
+        self.endings.push(ending);
+        out.push('\n');
+    }
+}
+
+/// Convert CRLF and CR line endings of the text to LF. Returns the normalized text and the original
+/// line endings. See `NewlineNormalizer` to process the text chunk by chunk.
+pub fn normalize_newlines(text:&str) -> (String,LineEndings) {
+    let mut normalizer = NewlineNormalizer::new();
+    let mut out        = String::with_capacity(text.len());
+    normalizer.push_str(text,&mut out);
+    let endings = normalizer.finish(&mut out);
+    (out,endings)
+}
+
+
+// === LineEndingRestorer ===
+
+/// Converts the LF line endings of a normalized text back to the original ones. The text can be
+/// passed chunk by chunk, for example while it is written to a file. Line endings are matched with
+/// lines by their index, and the lines beyond the recorded ones, for example added by edits, get
+/// the dominant line ending.
+#[derive(Clone,Debug)]
+pub struct LineEndingRestorer<'a> {
+    endings  : &'a LineEndings,
+    line     : usize,
+    fallback : LineEnding,
+}
+
+impl<'a> LineEndingRestorer<'a> {
+    /// Constructor.
+    pub fn new(endings:&'a LineEndings) -> Self {
+        let line     = 0;
+        let fallback = endings.dominant().unwrap_or(LineEnding::Lf);
+        Self {endings,line,fallback}
+    }
+
+    /// Restore the line endings of the chunk, appending the result to `out`.
+    pub fn push_str(&mut self, chunk:&str, out:&mut String) {
+        let mut lines = chunk.split('\n');
+        if let Some(first) = lines.next() { out.push_str(first); }
+        for line in lines {
+            let ending = self.endings.get(self.line).unwrap_or(self.fallback);
+            self.line += 1;
+            out.push_str(ending.as_str());
+            out.push_str(line);
+        }
+    }
+}
+
+
+
 // ============
 // === Text ===
 // ============
@@ -613,6 +1045,21 @@ mod test {
         assert_round_trip(str,Index::new(1), TextLocation {line:1, column:0});
     }
 
+    #[test]
+    fn index_arithmetic() {
+        let index = Index::new(2);
+        assert_eq!(index + Size::new(3),Index::new(5));
+        assert_eq!(Index::new(5) - index,Size::new(3));
+        assert_eq!(index.sub_with(Size::new(3),overflow::Policy::Saturate),Ok(Index::new(0)));
+        assert!(index.sub_with(Size::new(3),overflow::Policy::Error).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Index arithmetic overflow: 2 - 3.")]
+    fn index_underflow_panics() {
+        let _ = Index::new(2) - Size::new(3);
+    }
+
     #[test]
     fn text_location_at_end() {
         let str = "first\nsecond\nthird";
@@ -632,4 +1079,110 @@ mod test {
         assert_eq!(&"日本語"[Span::from(0..1)],"日");
         assert_eq!(&"日本語"[Span::from(2..3)],"語");
     }
+
+    #[test]
+    fn dirty_lines_follow_edits() {
+        let text      = "zero\none\ntwo\nthree\nfour";
+        let mut dirty = DirtyLines::new();
+        dirty.mark(0);
+        dirty.mark_range(3..=4);
+        assert!(dirty.is_dirty(3));
+        assert!(!dirty.is_dirty(2));
+
+        // Replace "one\ntwo" with three lines.
+        let change = TextChange::replace(Index::new(5)..Index::new(12),"1\n2\n3".into());
+        dirty.apply_edit(text,&change);
+        assert_eq!(dirty.drain_dirty(),vec![Interval(0,5)]);
+        assert!(dirty.is_empty());
+
+        // Remove the "two\n" line.
+        let text   = "zero\none\ntwo\nthree\nfour";
+        let change = TextChange::delete(Index::new(9)..Index::new(13));
+        dirty.mark(4);
+        dirty.apply_edit(text,&change);
+        assert_eq!(dirty.drain_dirty(),vec![Interval(2,3)]);
+    }
+
+    #[test]
+    fn line_lengths_follow_edits() {
+        let text        = "a\tb\nlonger line\n\nend";
+        let mut lengths = LineLengths::new(text,4);
+        assert_eq!(lengths.line_count(),4);
+        assert_eq!(lengths.line(0),Some(LineLength {chars:3, columns:5}));
+        assert_eq!(lengths.max_columns(),11);
+        assert_eq!(lengths.line_start(2),Index::new(16));
+
+        // Replace " line\n\ne" with "\t!".
+        let change = TextChange::replace(Index::new(10)..Index::new(18),"\t!".into());
+        lengths.apply_edit(text,&change);
+        let text = change.applied(text);
+        assert_eq!(text,"a\tb\nlonger\t!nd");
+        assert_eq!(lengths.line_count(),2);
+        assert_eq!(lengths.line(1),Some(LineLength {chars:10, columns:11}));
+        assert_eq!(lengths.max_columns(),11);
+
+        let change = TextChange::delete(Index::new(10)..Index::new(14));
+        lengths.apply_edit(&text,&change);
+        assert_eq!(lengths.line(1),Some(LineLength {chars:6, columns:6}));
+        assert_eq!(lengths.max_columns(),6);
+        assert_eq!(lengths.line_count(),LineLengths::new(&change.applied(&text),4).line_count());
+    }
+
+    #[test]
+    fn line_starts_follow_edits() {
+        let mut text    = "zero\r\none\n\ttwo\r\n\nfour\nfive".to_string();
+        let mut lengths = LineLengths::new(&text,4);
+        assert_eq!(lengths.line_start(1),Index::new(6));
+        assert_eq!(lengths.line_start(9),Index::new(text.chars().count()));
+        let changes = vec!
+            [ TextChange::insert(Index::new(8),"ne\r\no".into())
+            , TextChange::delete(Index::new(3)..Index::new(12))
+            , TextChange::replace(Index::new(0)..Index::new(1),"\n\n".into())
+            , TextChange::insert(Index::new(20),"unf\r\n".into())
+            , TextChange::delete(Index::new(2)..Index::new(7))
+            ];
+        for change in changes {
+            lengths.apply_edit(&text,&change);
+            text = change.applied(&text);
+            let expected = LineLengths::new(&text,4);
+            assert_eq!(lengths.lines,expected.lines);
+            for line in 0..=lengths.line_count() {
+                assert_eq!(lengths.line_start(line),expected.line_start(line));
+            }
+            assert_eq!(lengths.max_columns(),expected.max_columns());
+        }
+    }
+
+    #[test]
+    fn detecting_encoding() {
+        assert_eq!(Encoding::from_bom(&[0xEF,0xBB,0xBF,b'a']),Some(Encoding::Utf8));
+        assert_eq!(Encoding::from_bom(&[0xFF,0xFE,b'a',0x00]),Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::from_bom(&[0xFF,0xFE,0x00,0x00]),Some(Encoding::Utf32Le));
+        assert_eq!(Encoding::from_bom(&[0xFE,0xFF]),Some(Encoding::Utf16Be));
+        assert_eq!(Encoding::from_bom(b"text"),None);
+    }
+
+    #[test]
+    fn normalizing_newlines_in_chunks() {
+        let text           = "a\r\nb\rc\nd\r\n\r";
+        let mut normalizer = NewlineNormalizer::new();
+        let mut normalized = String::new();
+        for chunk in &["a\r","\nb\r","c\nd","\r","\n\r"] {
+            normalizer.push_str(chunk,&mut normalized);
+        }
+        let endings = normalizer.finish(&mut normalized);
+        assert_eq!(normalized,"a\nb\nc\nd\n\n");
+        assert_eq!(normalize_newlines(text),(normalized.clone(),endings.clone()));
+        assert_eq!(endings.len(),5);
+        assert_eq!(endings.get(1),Some(LineEnding::Cr));
+        assert_eq!(endings.dominant(),Some(LineEnding::CrLf));
+
+        let mut restorer = endings.restorer();
+        let mut restored = String::new();
+        for chunk in &["a\nb","\n","c\nd\n\n","e\nf"] {
+            restorer.push_str(chunk,&mut restored);
+        }
+        assert_eq!(restored,"a\r\nb\rc\nd\r\n\re\r\nf");
+        assert_eq!(endings.restore(&normalized),text);
+    }
 }
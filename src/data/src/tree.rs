@@ -0,0 +1,146 @@
+//! A common interface of tree structures, allowing generic traversals, visitors, and
+//! pretty-printers to be written once for all of them.
+
+use crate::prelude::*;
+
+use std::collections::VecDeque;
+
+
+
+// ================
+// === TreeLike ===
+// ================
+
+/// A tree node with a value and an ordered list of children. The trait is parametrized with the
+/// lifetime of the borrow, so the children iterator can borrow from the node.
+pub trait TreeLike<'a> : 'a {
+    /// The value stored in every node.
+    type Value : 'a;
+    /// Iterator over the direct children of a node.
+    type Children : Iterator<Item=&'a Self>;
+
+    /// The value of this node.
+    fn value(&'a self) -> &'a Self::Value;
+
+    /// Iterator over the direct children of this node.
+    fn children(&'a self) -> Self::Children;
+
+    /// Check whether this node has no children.
+    fn is_leaf(&'a self) -> bool {
+        self.children().next().is_none()
+    }
+
+    /// Depth-first, pre-order iterator over this node and all of its descendants. Every node is
+    /// paired with its depth, counting from zero for this node.
+    fn dfs(&'a self) -> Dfs<'a,Self> where Self:Sized {
+        let stack = vec![(0,self)];
+        Dfs {stack}
+    }
+
+    /// Breadth-first iterator over this node and all of its descendants. Every node is paired with
+    /// its depth, counting from zero for this node.
+    fn bfs(&'a self) -> Bfs<'a,Self> where Self:Sized {
+        let queue = std::iter::once((0,self)).collect();
+        Bfs {queue}
+    }
+
+    /// Number of nodes in the tree, including this one.
+    fn node_count(&'a self) -> usize where Self:Sized {
+        self.dfs().count()
+    }
+
+    /// Multi-line representation of the tree, with one node per line, indented by its depth.
+    fn pretty(&'a self) -> String where Self:Sized, Self::Value:Debug {
+        self.dfs().map(|(depth,node)| {
+            let indent = "  ".repeat(depth);
+            iformat!("{indent}{node.value():?}\n")
+        }).collect()
+    }
+}
+
+
+
+// ===========
+// === Dfs ===
+// ===========
+
+/// Depth-first, pre-order iterator over a tree. See `TreeLike::dfs` to learn more.
+#[derive(Debug)]
+pub struct Dfs<'a,T> {
+    stack : Vec<(usize,&'a T)>,
+}
+
+impl<'a,T:TreeLike<'a>> Iterator for Dfs<'a,T> {
+    type Item = (usize,&'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth,node) = self.stack.pop()?;
+        let children     = node.children().collect_vec();
+        self.stack.extend(children.into_iter().rev().map(|child| (depth + 1, child)));
+        Some((depth,node))
+    }
+}
+
+
+
+// ===========
+// === Bfs ===
+// ===========
+
+/// Breadth-first iterator over a tree. See `TreeLike::bfs` to learn more.
+#[derive(Debug)]
+pub struct Bfs<'a,T> {
+    queue : VecDeque<(usize,&'a T)>,
+}
+
+impl<'a,T:TreeLike<'a>> Iterator for Bfs<'a,T> {
+    type Item = (usize,&'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth,node) = self.queue.pop_front()?;
+        self.queue.extend(node.children().map(|child| (depth + 1, child)));
+        Some((depth,node))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Node {
+        value    : usize,
+        children : Vec<Node>,
+    }
+
+    fn node(value:usize, children:Vec<Node>) -> Node {
+        Node {value,children}
+    }
+
+    impl<'a> TreeLike<'a> for Node {
+        type Value    = usize;
+        type Children = std::slice::Iter<'a,Node>;
+        fn value    (&'a self) -> &'a usize      { &self.value }
+        fn children (&'a self) -> Self::Children { self.children.iter() }
+    }
+
+    fn tree() -> Node {
+        node(1,vec![node(2,vec![node(4,vec![])]),node(3,vec![])])
+    }
+
+    #[test]
+    fn traversals() {
+        let tree = tree();
+        let dfs  = tree.dfs().map(|(depth,node)| (depth,node.value)).collect_vec();
+        let bfs  = tree.bfs().map(|(depth,node)| (depth,node.value)).collect_vec();
+        assert_eq!(dfs,vec![(0,1),(1,2),(2,4),(1,3)]);
+        assert_eq!(bfs,vec![(0,1),(1,2),(1,3),(2,4)]);
+        assert_eq!(tree.node_count(),4);
+        assert!(!tree.is_leaf());
+        assert_eq!(tree.pretty(),"1\n  2\n    4\n  3\n");
+    }
+}
@@ -0,0 +1,153 @@
+//! Version vectors, tracking the causality of changes made concurrently by several replicas.
+//!
+//! Every replica, identified by a key, counts the changes it made. A version vector stores the
+//! counters of all the replicas, so comparing two vectors tells whether one of the states includes
+//! all the changes of the other one, or whether they were changed concurrently and have to be
+//! merged.
+
+use crate::prelude::*;
+
+use std::cmp::Ordering;
+
+
+
+// =====================
+// === VersionVector ===
+// =====================
+
+/// Map from replica keys to the numbers of changes made by the replicas. Missing keys have the
+/// counter equal to 0, and are not stored, so the vector is serialized as a map containing only
+/// the replicas which made any changes.
+///
+/// Vectors are partially ordered: a vector is smaller than another one if all its counters are
+/// not bigger, which means that its state happened before. Vectors which are not comparable are
+/// concurrent.
+#[derive(Clone,Debug,Derivative)]
+#[cfg_attr(feature="serde",derive(serde::Serialize,serde::Deserialize))]
+#[derivative(Default(bound=""))]
+#[cfg_attr(feature="serde",serde(transparent))]
+pub struct VersionVector<K:Ord> {
+    counters : BTreeMap<K,u64>,
+}
+
+impl<K:Ord> VersionVector<K> {
+    /// Constructor of a vector with all the counters equal to 0.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The counter of the replica.
+    pub fn get(&self, key:&K) -> u64 {
+        self.counters.get(key).copied().unwrap_or_default()
+    }
+
+    /// Record a change made by the replica. Returns the new value of its counter.
+    pub fn increment(&mut self, key:K) -> u64 {
+        let counter = self.counters.entry(key).or_default();
+        *counter += 1;
+        *counter
+    }
+
+    /// Iterator over the replicas which made any changes, along with their counters, ordered by
+    /// the keys.
+    pub fn iter(&self) -> impl Iterator<Item=(&K,u64)> {
+        self.counters.iter().filter(|(_,counter)| **counter > 0).map(|(key,t)| (key,*t))
+    }
+
+    /// Check whether the state of this vector happened before the state of the other one.
+    pub fn happens_before(&self, other:&Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Less)
+    }
+
+    /// Check whether the vectors are not comparable, so their states were changed concurrently.
+    pub fn is_concurrent(&self, other:&Self) -> bool {
+        self.partial_cmp(other).is_none()
+    }
+}
+
+impl<K:Ord+Clone> VersionVector<K> {
+    /// Include the changes of the other vector, setting every counter to the maximum of both.
+    pub fn merge(&mut self, other:&Self) {
+        for (key,counter) in other.iter() {
+            let own = self.counters.entry(key.clone()).or_default();
+            *own    = (*own).max(counter);
+        }
+    }
+
+    /// The smallest vector including the changes of both vectors. See `merge` to learn more.
+    pub fn merged(&self, other:&Self) -> Self {
+        let mut merged = self.clone();
+        merged.merge(other);
+        merged
+    }
+}
+
+impl<K:Ord> PartialOrd for VersionVector<K> {
+    fn partial_cmp(&self, other:&Self) -> Option<Ordering> {
+        let less    = other.iter().any(|(key,their)| self.get(key) < their);
+        let greater = self.iter().any(|(key,own)| own > other.get(key));
+        match (less,greater) {
+            (false,false) => Some(Ordering::Equal),
+            (true,false)  => Some(Ordering::Less),
+            (false,true)  => Some(Ordering::Greater),
+            (true,true)   => None,
+        }
+    }
+}
+
+impl<K:Ord> PartialEq for VersionVector<K> {
+    fn eq(&self, other:&Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<K:Ord> Eq for VersionVector<K> {}
+
+impl<K:Ord> FromIterator<(K,u64)> for VersionVector<K> {
+    fn from_iter<I:IntoIterator<Item=(K,u64)>>(iter:I) -> Self {
+        let counters = iter.into_iter().filter(|(_,counter)| *counter > 0).collect();
+        Self {counters}
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(counters:&[(&'static str,u64)]) -> VersionVector<&'static str> {
+        counters.iter().copied().collect()
+    }
+
+    #[test]
+    fn counters_are_incremented_and_merged() {
+        let mut v1 = VersionVector::new();
+        assert_eq!(v1.increment("a"),1);
+        assert_eq!(v1.increment("a"),2);
+        assert_eq!(v1.get(&"b"),0);
+        let v2 = vector(&[("a",1),("b",3)]);
+        v1.merge(&v2);
+        assert_eq!(v1,vector(&[("a",2),("b",3)]));
+        assert_eq!(v1.iter().collect_vec(),vec![(&"a",2),(&"b",3)]);
+        assert_eq!(v2.merged(&default()),v2);
+    }
+
+    #[test]
+    fn causality() {
+        let v1 = vector(&[("a",1)]);
+        let v2 = vector(&[("a",1),("b",1)]);
+        let v3 = vector(&[("a",2)]);
+        assert!(v1.happens_before(&v2));
+        assert!(!v2.happens_before(&v1));
+        assert!(v2.is_concurrent(&v3));
+        assert_eq!(v1.partial_cmp(&v3),Some(Ordering::Less));
+        assert_eq!(v3.partial_cmp(&v1),Some(Ordering::Greater));
+        assert_eq!(vector(&[("a",0)]),VersionVector::new());
+        assert!(v2.happens_before(&v2.merged(&v3)));
+    }
+}
@@ -0,0 +1,252 @@
+//! Property-based tests of the data structures. Every structure is compared against a simple
+//! reference implementation, using the proptest strategies defined below.
+
+use enso_data::dependency_graph::DependencyGraph;
+use enso_data::diet;
+use enso_data::hash_map_tree::HashMapTree;
+use enso_data::hash_map_tree::SegmentIter;
+use enso_data::opt_vec::OptVec;
+use enso_data::radix_tree::RadixTree;
+use enso_data::succinct_tree::SuccinctTree;
+
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+
+
+// ==================
+// === Strategies ===
+// ==================
+
+/// Maximum value of the generated points, small enough to produce many adjacent values.
+const MAX_POINT : usize = 200;
+
+/// Sequences of points, each inserted as a unit interval.
+fn points() -> impl Strategy<Value=Vec<usize>> {
+    prop::collection::vec(0..MAX_POINT,0..100)
+}
+
+/// Sequences of closed intervals.
+fn intervals() -> impl Strategy<Value=Vec<(usize,usize)>> {
+    let interval = (0..MAX_POINT,0..10_usize).prop_map(|(start,len)| (start,start+len));
+    prop::collection::vec(interval,0..50)
+}
+
+/// A single step of an edit script.
+#[derive(Clone,Copy,Debug)]
+enum Edit {
+    Insert(usize),
+    /// Remove the element at the index, taken modulo the number of used indexes.
+    Remove(usize),
+}
+
+/// Sequences of insertions and removals.
+fn edit_script() -> impl Strategy<Value=Vec<Edit>> {
+    let edit = prop_oneof![
+        any::<usize>().prop_map(Edit::Insert),
+        any::<usize>().prop_map(Edit::Remove),
+    ];
+    prop::collection::vec(edit,0..100)
+}
+
+/// Sets of dependency edges `(first,second)` without cycles, as `first < second` always holds.
+fn acyclic_edges() -> impl Strategy<Value=BTreeSet<(usize,usize)>> {
+    let edge = (0..30_usize,1..10_usize).prop_map(|(first,offset)| (first,first+offset));
+    prop::collection::btree_set(edge,0..60)
+}
+
+/// Sets of arbitrary dependency edges, possibly containing cycles.
+fn edges() -> impl Strategy<Value=BTreeSet<(usize,usize)>> {
+    prop::collection::btree_set((0..30_usize,0..30_usize),0..60)
+}
+
+
+/// Sets of tree paths over a small alphabet, producing both wide and deep trees.
+fn tree_paths() -> impl Strategy<Value=Vec<Vec<u8>>> {
+    prop::collection::vec(prop::collection::vec(0..4_u8,1..8),0..150)
+}
+
+/// Tree edits: setting the value at the path, or removing the path if there is no value. Values
+/// include the default `0`, and paths are mostly long and share prefixes.
+fn tree_edits() -> impl Strategy<Value=Vec<(Vec<u8>,Option<usize>)>> {
+    let path = prop::collection::vec(0..3_u8,0..8);
+    prop::collection::vec((path,prop::option::weighted(0.8,0..3_usize)),0..100)
+}
+
+
+// ============
+// === DIET ===
+// ============
+
+/// Values covered by the intervals of the tree. The intervals are not compared directly, as the
+/// tree does not merge intervals stored in different layers yet, so they can overlap.
+fn diet_values(tree:&diet::Tree16) -> BTreeSet<usize> {
+    tree.to_vec().into_iter().flat_map(|interval| interval.start..=interval.end).collect()
+}
+
+proptest! {
+    #[test]
+    fn diet_contains_inserted_points(points in points()) {
+        let mut tree = diet::Tree16::default();
+        for &point in &points { tree.insert(point) }
+        let model = points.iter().copied().collect::<BTreeSet<_>>();
+        prop_assert_eq!(diet_values(&tree),model);
+    }
+
+    #[test]
+    fn diet_contains_inserted_intervals(intervals in intervals()) {
+        let mut tree = diet::Tree16::default();
+        for &(start,end) in &intervals {
+            for point in start..=end { tree.insert(point) }
+        }
+        let model = intervals.iter().flat_map(|&(start,end)| start..=end).collect::<BTreeSet<_>>();
+        prop_assert_eq!(diet_values(&tree),model);
+    }
+}
+
+
+
+// ==============
+// === OptVec ===
+// ==============
+
+proptest! {
+    #[test]
+    fn opt_vec_matches_model(script in edit_script()) {
+        let mut vec   = OptVec::<usize>::new();
+        let mut model = BTreeMap::<usize,usize>::new();
+        let mut used  = Vec::<usize>::new();
+        for edit in script {
+            match edit {
+                Edit::Insert(value) => {
+                    let index = vec.insert(value);
+                    prop_assert!(model.insert(index,value).is_none(),"Index {} reused.",index);
+                    if !used.contains(&index) { used.push(index) }
+                }
+                Edit::Remove(index) => if !used.is_empty() {
+                    let index = used[index % used.len()];
+                    prop_assert_eq!(vec.remove(index),model.remove(&index));
+                }
+            }
+            prop_assert_eq!(vec.len(),model.len());
+            prop_assert_eq!(vec.is_empty(),model.is_empty());
+        }
+        for (index,value) in &model {
+            prop_assert_eq!(vec.safe_index(*index),Some(value));
+        }
+        let values  = vec.iter().copied().collect::<Vec<_>>();
+        let indices = vec.indices().collect::<Vec<_>>();
+        prop_assert_eq!(values,model.values().copied().collect::<Vec<_>>());
+        prop_assert_eq!(indices,model.keys().copied().collect::<Vec<_>>());
+    }
+}
+
+
+
+// =======================
+// === DependencyGraph ===
+// =======================
+
+fn graph_from(edges:&BTreeSet<(usize,usize)>) -> DependencyGraph<usize> {
+    let mut graph = DependencyGraph::new();
+    for &(first,second) in edges { graph.insert_dependency(first,second); }
+    graph
+}
+
+proptest! {
+    #[test]
+    fn topo_sort_respects_dependencies(edges in acyclic_edges(), extra in points()) {
+        let graph  = graph_from(&edges);
+        let nodes  = edges.iter().flat_map(|&(first,second)| vec![first,second]);
+        let keys   = nodes.chain(extra).collect::<BTreeSet<_>>().into_iter().collect::<Vec<_>>();
+        let sorted = graph.topo_sort(&keys);
+        let position = |key:usize| sorted.iter().position(|t| *t == key).unwrap();
+        prop_assert_eq!(sorted.iter().copied().collect::<BTreeSet<_>>().len(),keys.len());
+        prop_assert_eq!(sorted.len(),keys.len());
+        for &(first,second) in &edges {
+            prop_assert!(position(first) < position(second),"{} -> {} in {:?}",first,second,sorted);
+        }
+    }
+
+    #[test]
+    fn topo_sort_is_permutation(edges in edges(), keys in points()) {
+        let graph  = graph_from(&edges);
+        let keys   = keys.into_iter().collect::<BTreeSet<_>>().into_iter().collect::<Vec<_>>();
+        let mut sorted = graph.topo_sort(&keys);
+        sorted.sort_unstable();
+        prop_assert_eq!(sorted,keys);
+    }
+
+    #[test]
+    fn topo_sort_variants_agree(edges in edges(), keys in points()) {
+        let graph       = graph_from(&edges);
+        let keys        = keys.into_iter().collect::<Vec<_>>();
+        let expected    = graph.clone().into_topo_sort(&keys);
+        let mut sorted  = keys.clone();
+        graph.topo_sort_in_place(&mut sorted);
+        let from_iter   = graph.topo_sort_iter(&keys).copied().collect::<Vec<_>>();
+        prop_assert_eq!(graph.topo_sort(&keys),expected.clone());
+        prop_assert_eq!(sorted,expected.clone());
+        prop_assert_eq!(from_iter,expected);
+    }
+}
+
+
+
+// ====================
+// === SuccinctTree ===
+// ====================
+
+proptest! {
+    #[test]
+    fn succinct_tree_matches_hash_map_tree(paths in tree_paths()) {
+        let mut tree = HashMapTree::<u8,usize>::default();
+        for (index,path) in paths.iter().enumerate() { tree.set(path.clone(),index + 1); }
+        let succinct  = SuccinctTree::from(&tree);
+        let mut count = 0;
+        for (path,value) in &tree {
+            let node   = succinct.get_node(path.iter().copied()).unwrap();
+            let size   = tree.get_node(SegmentIter(path.iter().copied())).unwrap().iter().count();
+            let parent = path.split_last().map(|(_,t)| succinct.get_node(t.iter().copied()));
+            prop_assert_eq!(succinct.value(node),value);
+            prop_assert_eq!(succinct.subtree_size(node),size);
+            prop_assert_eq!(succinct.parent(node),parent.flatten());
+            count += 1;
+        }
+        prop_assert_eq!(succinct.len(),count);
+        prop_assert_eq!(succinct.to_hash_map_tree(),Some(tree));
+    }
+}
+
+
+
+// =================
+// === RadixTree ===
+// =================
+
+proptest! {
+    #[test]
+    fn radix_tree_matches_hash_map_tree(edits in tree_edits()) {
+        let mut tree  = HashMapTree::<u8,usize>::default();
+        let mut radix = RadixTree::<u8,usize>::default();
+        for (path,value) in edits {
+            match value {
+                Some(value) => {
+                    tree.set(path.clone(),value);
+                    radix.set(path,value);
+                }
+                None => {
+                    let removed = tree.remove(path.iter().copied());
+                    prop_assert_eq!(radix.remove(path.iter().copied()),removed);
+                    if !path.is_empty() { prop_assert_eq!(radix.get(path),None); }
+                }
+            }
+        }
+        for (path,value) in &tree {
+            prop_assert_eq!(radix.get(path.into_iter().copied()),Some(value));
+        }
+        prop_assert_eq!(HashMapTree::from(&radix),tree.clone());
+        prop_assert_eq!(HashMapTree::from(&RadixTree::from(&tree)),tree);
+    }
+}
@@ -0,0 +1,114 @@
+//! Time source used by processors which need to measure or timestamp entries.
+
+use crate::prelude::*;
+
+use enso_shapely::CloneRef;
+use std::time::Duration;
+#[cfg(target_arch="wasm32")]
+use wasm_bindgen::prelude::*;
+
+
+
+// ===========================
+// === JavaScript Bindings ===
+// ===========================
+
+#[cfg(target_arch="wasm32")]
+mod js {
+    use super::*;
+    #[wasm_bindgen(inline_js = "
+        export function performance_now() {
+            return performance.now()
+        }
+    ")]
+    extern "C" {
+        #[allow(unsafe_code)]
+        pub fn performance_now() -> f64;
+    }
+}
+
+
+
+// ===========
+// === Now ===
+// ===========
+
+/// Current time in microseconds. Only differences between the returned values are meaningful.
+#[cfg(target_arch="wasm32")]
+pub fn now() -> u64 {
+    (js::performance_now() * 1000.0) as u64
+}
+
+#[cfg(not(target_arch="wasm32"))]
+lazy_static! {
+    static ref START : std::time::Instant = std::time::Instant::now();
+}
+
+/// Current time in microseconds. Only differences between the returned values are meaningful. The
+/// time is monotonic, and it is measured from the first call in the process.
+#[cfg(not(target_arch="wasm32"))]
+pub fn now() -> u64 {
+    START.elapsed().as_micros() as u64
+}
+
+
+
+// =============
+// === Clock ===
+// =============
+
+/// A source of time. Processors measuring or timestamping entries are parametrized with a clock,
+/// which is the `SystemClock` by default. Tests can use the `ManualClock` instead, so their results
+/// do not depend on the real time.
+pub trait Clock {
+    /// Current time in microseconds. Only differences between the returned values are meaningful.
+    fn now(&self) -> u64;
+}
+
+
+// === SystemClock ===
+
+/// The real time, as returned by the `now` function.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        now()
+    }
+}
+
+
+// === ManualClock ===
+
+/// A clock which moves only when explicitly told to. Clones share the time, so a test can keep a
+/// clone of the clock of a processor and advance it between the submitted entries. The default
+/// clock starts at zero.
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct ManualClock {
+    time : Rc<Cell<u64>>,
+}
+
+impl ManualClock {
+    /// Constructor of a clock starting at the given time, in microseconds.
+    pub fn new(time:u64) -> Self {
+        let time = Rc::new(Cell::new(time));
+        Self {time}
+    }
+
+    /// Set the current time, in microseconds.
+    pub fn set(&self, time:u64) {
+        self.time.set(time)
+    }
+
+    /// Move the current time forward.
+    pub fn advance(&self, duration:Duration) {
+        self.time.set(self.time.get() + duration.as_micros() as u64)
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.time.get()
+    }
+}
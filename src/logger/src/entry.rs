@@ -2,13 +2,17 @@
 
 pub mod message;
 pub mod level;
+pub mod scope;
 
 pub use level::DefaultLevels;
 pub use level::DefaultFilter;
 pub use level::filter_from;
+pub use scope::TaskScope;
 
 use crate::prelude::*;
 
+use crate::path::Path;
+
 use message::Message;
 
 
@@ -34,17 +38,23 @@ pub struct Entry<Level> {
 #[allow(missing_docs)]
 pub struct GenericEntry {
     /// A dot-separated names of parent loggers and this logger.
-    pub path    : ImString,
+    pub path    : Path,
     pub content : Content,
+    /// The scope of the task which logged the entry. See `TaskScope` to learn more.
+    pub scope   : Option<TaskScope>,
+    /// Time of the entry in microseconds, set by the `Timestamper` processor. Only differences
+    /// between the times are meaningful.
+    pub time    : Option<u64>,
 }
 
-/// Content of the entry. Can either contain simple message, or grouping information.
+/// Content of the entry. Can either contain simple message, grouping information, or a table.
 #[derive(Clone,Debug)]
 #[allow(missing_docs)]
 pub enum Content {
     Message    (String),
     GroupBegin (GroupBegin),
-    GroupEnd
+    GroupEnd   (GroupEnd),
+    Table      (Table),
 }
 
 // `Content::GroupBegin` representation.
@@ -55,6 +65,78 @@ pub struct GroupBegin {
     pub message   : String,
 }
 
+/// `Content::GroupEnd` representation. The message and the depth of the closed group are not known
+/// where the group ends, so they are empty until the entry passes through the `GroupTracker`
+/// processor.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct GroupEnd {
+    /// The message of the matching `GroupBegin` entry.
+    pub message : Option<String>,
+    /// The number of groups enclosing the closed one.
+    pub depth   : usize,
+}
+
+/// `Content::Table` representation. A titled table of text cells, used to dump structured batches
+/// of data, like per-pass render statistics. It is rendered with `console.table` in the JavaScript
+/// console, and as aligned text in the native console. Rows shorter than the header are padded
+/// with empty cells.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+#[allow(missing_docs)]
+pub struct Table {
+    pub title   : String,
+    pub columns : Vec<String>,
+    pub rows    : Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Constructor of a table without rows.
+    pub fn new<S:ToString>(title:impl Into<String>, columns:impl IntoIterator<Item=S>) -> Self {
+        let title   = title.into();
+        let columns = columns.into_iter().map(|t| t.to_string()).collect();
+        let rows    = default();
+        Self {title,columns,rows}
+    }
+
+    /// Append the row.
+    pub fn push_row<S:ToString>(&mut self, cells:impl IntoIterator<Item=S>) {
+        self.rows.push(cells.into_iter().map(|t| t.to_string()).collect())
+    }
+
+    /// Append the row. A builder-style version of `push_row`.
+    pub fn row<S:ToString>(mut self, cells:impl IntoIterator<Item=S>) -> Self {
+        self.push_row(cells);
+        self
+    }
+
+    /// The header and the rows as lines of text, with the cells aligned in columns.
+    pub fn lines(&self) -> Vec<String> {
+        let row_lens   = self.rows.iter().map(|row| row.len());
+        let mut widths = vec![0;row_lens.fold(self.columns.len(),usize::max)];
+        for row in iter::once(&self.columns).chain(&self.rows) {
+            for (width,cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        iter::once(&self.columns).chain(&self.rows).map(|row| {
+            let cells = widths.iter().enumerate().map(|(index,width)| {
+                let cell = row.get(index).map(|t| t.as_str()).unwrap_or_default();
+                format!("{:1$}",cell,width)
+            });
+            cells.collect_vec().join(" | ").trim_end().to_owned()
+        }).collect()
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{}",self.title)?;
+        for line in self.lines() {
+            write!(f,"\n{}",line)?;
+        }
+        Ok(())
+    }
+}
+
 impl<Level> Deref for Entry<Level> {
     type Target = GenericEntry;
     fn deref(&self) -> &Self::Target {
@@ -80,14 +162,15 @@ impl Content {
         match self {
             Self::Message(msg)  => Some(msg),
             Self::GroupBegin(t) => Some(&t.message),
-            Self::GroupEnd      => None,
+            Self::GroupEnd(_)   => None,
+            Self::Table(t)      => Some(&t.title),
         }
     }
 }
 
 impl<Level> Entry<Level> {
     /// Constructor.
-    pub fn message(level:impl Into<Level>, path:ImString, message:impl Message) -> Self {
+    pub fn message(level:impl Into<Level>, path:Path, message:impl Message) -> Self {
         let level    = level.into();
         let gen_entry = GenericEntry::message(path,message);
         Self {level,gen_entry}
@@ -95,37 +178,58 @@ impl<Level> Entry<Level> {
 
     /// Constructor.
     pub fn group_begin
-    (level:impl Into<Level>, path:ImString, message:impl Message, collapsed:bool) -> Self {
+    (level:impl Into<Level>, path:Path, message:impl Message, collapsed:bool) -> Self {
         let level     = level.into();
         let gen_entry = GenericEntry::group_begin(path,message,collapsed);
         Self {level,gen_entry}
     }
 
     /// Constructor.
-    pub fn group_end(level:impl Into<Level>, path:ImString) -> Self {
+    pub fn group_end(level:impl Into<Level>, path:Path) -> Self {
         let level     = level.into();
         let gen_entry = GenericEntry::group_end(path);
         Self {level,gen_entry}
     }
+
+    /// Constructor.
+    pub fn table(level:impl Into<Level>, path:Path, table:Table) -> Self {
+        let level     = level.into();
+        let gen_entry = GenericEntry::table(path,table);
+        Self {level,gen_entry}
+    }
 }
 
 impl GenericEntry {
     /// Constructor.
-    pub fn message(path:ImString, message:impl Message) -> Self {
+    pub fn message(path:Path, message:impl Message) -> Self {
         let content = Content::Message(message.get());
-        Self {path,content}
+        let scope   = TaskScope::current();
+        let time    = None;
+        Self {path,content,scope,time}
     }
 
     /// Constructor.
     pub fn group_begin
-    (path:ImString, message:impl Message, collapsed:bool) -> Self {
+    (path:Path, message:impl Message, collapsed:bool) -> Self {
         let content = Content::group_begin(collapsed,message.get());
-        Self {path,content}
+        let scope   = TaskScope::current();
+        let time    = None;
+        Self {path,content,scope,time}
+    }
+
+    /// Constructor.
+    pub fn group_end(path:Path) -> Self {
+        let content = Content::GroupEnd(default());
+        let scope   = TaskScope::current();
+        let time    = None;
+        Self {path,content,scope,time}
     }
 
     /// Constructor.
-    pub fn group_end(path:ImString) -> Self {
-        let content = Content::GroupEnd;
-        Self {path,content}
+    pub fn table(path:Path, table:Table) -> Self {
+        let content = Content::Table(table);
+        let scope   = TaskScope::current();
+        let time    = None;
+        Self {path,content,scope,time}
     }
 }
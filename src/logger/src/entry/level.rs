@@ -2,10 +2,21 @@
 //! that the verbosity level mechanism is completely user-extensible and this implementation can be
 //! completely redefined by the user.
 
-use crate::prelude::*;
-use crate::processor::formatter;
-use crate::entry::Entry;
-use crate::entry::level;
+
+
+// ============
+// === Code ===
+// ============
+
+/// Compact numeric representation of a group of levels, used to send entries between processes.
+/// Implemented for all the groups defined with `define_levels_group`, where the code is the index
+/// of the level in the group.
+pub trait Code : Sized {
+    /// The code of the level.
+    fn code(&self) -> u8;
+    /// The level with the given code, if any.
+    fn from_code(code:u8) -> Option<Self>;
+}
 
 
 
@@ -69,7 +80,7 @@ macro_rules! define_levels {
 
 /// Group levels defined with `define_levels` (possibly several, possibly merging user-defined
 /// types) into a common structure for logger parametrization. Also, defines a generic formatter
-/// impl for the type, which redirects calls to more specific instances.
+/// impl and a `DispatchLevel` impl for the type, which redirect calls to more specific instances.
 ///
 /// For example, for `define_levels_group!(DefaultLevels {Trace,Debug,Info,Warning,Error});`, the
 /// following code will be generated:
@@ -83,21 +94,43 @@ macro_rules! define_levels {
 /// impl From<Warning> for DefaultLevels { fn from(_: Warning) -> Self { Self::Warning } }
 /// impl From<Error>   for DefaultLevels { fn from(_: Error)   -> Self { Self::Error } }
 ///
-/// impl<T> formatter::GenericDefinition<DefaultLevels> for T
-///     where T : formatter::Definition<level::Trace>,
-///           T : formatter::Definition<level::Debug>,
-///           T : formatter::Definition<level::Info>,
-///           T : formatter::Definition<level::Warning>,
-///           T : formatter::Definition<level::Error> {
-///     fn generic_format(entry:&Entry<DefaultLevels>) -> Option<Self::Output> {
+/// impl level::Code for DefaultLevels {
+///     fn code(&self) -> u8 { *self as u8 }
+///     fn from_code(code:u8) -> Option<Self> {
+///         let levels = [Self::Trace,Self::Debug,Self::Info,Self::Warning,Self::Error];
+///         levels.get(code as usize).copied()
+///     }
+/// }
+///
+/// impl<T:formatter::Output> formatter::GroupDefinition<T> for DefaultLevels
+///     where T : formatter::Definition<Trace>,
+///           T : formatter::Definition<Debug>,
+///           T : formatter::Definition<Info>,
+///           T : formatter::Definition<Warning>,
+///           T : formatter::Definition<Error> {
+///     fn group_format(entry:&Entry<DefaultLevels>) -> Option<T::Output> {
 ///         match entry.level {
-///             DefaultLevels::Trace   => formatter::format::<T, level::Trace>(&entry.gen_entry),
-///             DefaultLevels::Debug   => formatter::format::<T, level::Debug>(&entry.gen_entry),
-///             DefaultLevels::Info    => formatter::format::<T, level::Info>(&entry.gen_entry),
-///             DefaultLevels::Warning => formatter::format::<T, level::Warning>(&entry.gen_entry),
-///             DefaultLevels::Error   => formatter::format::<T, level::Error>(&entry.gen_entry)
+///             DefaultLevels::Trace   => formatter::format::<T,Trace>(&entry.gen_entry),
+///             DefaultLevels::Debug   => formatter::format::<T,Debug>(&entry.gen_entry),
+///             DefaultLevels::Info    => formatter::format::<T,Info>(&entry.gen_entry),
+///             DefaultLevels::Warning => formatter::format::<T,Warning>(&entry.gen_entry),
+///             DefaultLevels::Error   => formatter::format::<T,Error>(&entry.gen_entry)
+///         }
+///     }
+/// }
+///
+/// impl<T:?Sized> DispatchLevel<T> for DefaultLevels
+///     where T : LoggerOps<Trace>,
+///           ...
+///           T : LoggerOps<Error> {
+///     fn dispatch_log(self, logger:&T, msg:impl Message) {
+///         match self {
+///             DefaultLevels::Trace => LoggerOps::log(logger,Trace,msg),
+///             ...
+///             DefaultLevels::Error => LoggerOps::log(logger,Error,msg),
 ///         }
 ///     }
+///     ...
 /// }
 /// ```
 #[macro_export]
@@ -118,17 +151,129 @@ macro_rules! define_levels_group {
             }
         )*
 
-        impl<T> formatter::GenericDefinition<DefaultLevels> for T
-        where $(T : formatter::Definition<level::$name>),* {
-            fn generic_format(entry:&Entry<DefaultLevels>) -> Option<Self::Output> {
+        impl $crate::entry::level::Code for $group_name {
+            fn code(&self) -> u8 {
+                *self as u8
+            }
+
+            fn from_code(code:u8) -> Option<Self> {
+                [$(Self::$name),*].get(code as usize).copied()
+            }
+        }
+
+        impl<T> $crate::processor::formatter::GroupDefinition<T> for $group_name
+        where T : $crate::processor::formatter::Output,
+              $(T : $crate::processor::formatter::Definition<$name>),* {
+            fn group_format(entry:&$crate::entry::Entry<$group_name>) -> Option<T::Output> {
                 match entry.level {
                     $(
-                        DefaultLevels::$name =>
-                            formatter::format::<T,level::$name> (&entry.gen_entry)
+                        $group_name::$name =>
+                            $crate::processor::formatter::format::<T,$name> (&entry.gen_entry)
                     ),*
                 }
             }
         }
+
+        impl<T:?Sized> $crate::DispatchLevel<T> for $group_name
+        where $(T : $crate::LoggerOps<$name>),* {
+            fn dispatch_log(self, logger:&T, msg:impl $crate::Message) {
+                match self {
+                    $($group_name::$name => $crate::LoggerOps::log(logger,$name,msg)),*
+                }
+            }
+
+            fn dispatch_group_begin(self, logger:&T, collapsed:bool, msg:impl $crate::Message) {
+                match self {
+                    $($group_name::$name =>
+                        $crate::LoggerOps::group_begin(logger,$name,collapsed,msg)),*
+                }
+            }
+
+            fn dispatch_group_end(self, logger:&T) {
+                match self {
+                    $($group_name::$name => $crate::LoggerOps::group_end(logger,$name)),*
+                }
+            }
+
+            fn dispatch_table
+            (self, logger:&T, table:impl FnOnce() -> $crate::entry::Table) {
+                match self {
+                    $($group_name::$name => $crate::LoggerOps::table(logger,$name,table)),*
+                }
+            }
+
+            fn dispatch_enabled(self, logger:&T) -> bool {
+                match self {
+                    $($group_name::$name => $crate::LoggerOps::enabled(logger,$name)),*
+                }
+            }
+        }
+    };
+}
+
+
+/// Defines the compile-time filtering rules for levels defined with `define_levels`, listed from
+/// the least to the most important one, like `define_filtering_rules_for_levels!{[] Minor Major}`.
+/// Each `filter_from` level removes all the levels listed before it. It has to be used in the
+/// module containing the levels and the `filter_from` module. See the
+/// `define_compile_time_filtering_rules` macro to learn more.
+#[macro_export]
+macro_rules! define_filtering_rules_for_levels {
+    ([$($removed:ident)*]) => {};
+    ([$($removed:ident)*] $filter:ident $($rest:ident)*) => {
+        $crate::define_compile_time_filtering_rules! {
+            filter_from::$filter => $($removed),*;
+        }
+        $crate::define_filtering_rules_for_levels!{[$($removed)* $filter] $($rest)*}
+    };
+}
+
+
+/// Define a module with a complete set of custom verbosity levels, listed from the least to the
+/// most important one. The module contains:
+/// - The level structures and the `filter_from` module, as generated by `define_levels`.
+/// - The `Levels` enum, as generated by `define_levels_group`.
+/// - Compile-time filtering rules for all the `filter_from` levels.
+/// - The `DefaultFilter` alias, keeping all the entries.
+/// - The `Logger` alias, parametrized with the filter and the processor only.
+///
+/// The generated filtering rules specialize the generic `LoggerOps` implementation, so the crate
+/// using this macro needs to enable the `specialization` feature. For example:
+///
+/// ```
+/// # #![allow(incomplete_features)]
+/// # #![feature(specialization)]
+/// # use enso_logger::*;
+/// define_custom_logger! {
+///     /// Levels of the graph editor.
+///     pub mod graph_log { Verbose, Normal, Critical }
+/// }
+///
+/// type GraphLogger = graph_log::Logger<graph_log::filter_from::Normal>;
+/// let logger = GraphLogger::new("graph");
+/// // Removed at compile time.
+/// LoggerOps::<graph_log::Verbose>::log(&logger,graph_log::Verbose,"Redrawing nodes.");
+/// LoggerOps::<graph_log::Critical>::log(&logger,graph_log::Critical,"Node not found.");
+/// ```
+///
+/// The default processor formats and prints the entries immediately. See `DirectProcessor` to
+/// learn more.
+#[macro_export]
+macro_rules! define_custom_logger {
+    ($(#[$meta:meta])* $vis:vis mod $module:ident { $first:ident $(,$name:ident)* $(,)? }) => {
+        $(#[$meta])*
+        $vis mod $module {
+            $crate::define_levels!($first $(,$name)*);
+            $crate::define_levels_group!(Levels {$first $(,$name)*});
+            $crate::define_filtering_rules_for_levels!{[] $first $($name)*}
+
+            /// Compile-time filter keeping all the entries.
+            pub type DefaultFilter = filter_from::$first;
+
+            /// Logger using the levels defined in this module.
+            pub type Logger<Filter=DefaultFilter,Processor=$crate::processor::DirectProcessor> =
+                $crate::Logger<Filter,Processor,Levels>;
+        }
     };
 }
 
@@ -140,6 +285,7 @@ macro_rules! define_levels_group {
 
 define_levels!(Trace,Debug,Info,Warning,Error);
 define_levels_group!(DefaultLevels {Trace,Debug,Info,Warning,Error});
+define_filtering_rules_for_levels!{[] Trace Debug Info Warning Error}
 
 
 
@@ -147,5 +293,91 @@ define_levels_group!(DefaultLevels {Trace,Debug,Info,Warning,Error});
 // === DefaultFilter ===
 // =====================
 
-/// Default compile-time logger filtering. Keeps all logs.
+/// Default compile-time logger filtering. Keeps all logs, unless one of the `max-level-*` features
+/// is enabled.
+#[cfg(not(any(feature="max-level-info",feature="max-level-warn",feature="max-level-error")))]
 pub type DefaultFilter = filter_from::Trace;
+
+/// Default compile-time logger filtering. Keeps infos, warnings and errors, as the `max-level-info`
+/// feature is enabled.
+#[cfg(all(feature="max-level-info",not(any(feature="max-level-warn",feature="max-level-error"))))]
+pub type DefaultFilter = filter_from::Info;
+
+/// Default compile-time logger filtering. Keeps warnings and errors, as the `max-level-warn`
+/// feature is enabled.
+#[cfg(all(feature="max-level-warn",not(feature="max-level-error")))]
+pub type DefaultFilter = filter_from::Warning;
+
+/// Default compile-time logger filtering. Keeps errors only, as the `max-level-error` feature is
+/// enabled.
+#[cfg(feature="max-level-error")]
+pub type DefaultFilter = filter_from::Error;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::entry::Entry;
+    use crate::entry::level;
+    use crate::processor::Processor;
+
+    define_custom_logger! {
+        mod custom { Verbose, Normal, Critical }
+    }
+
+    thread_local! {
+        static LEVELS : RefCell<Vec<String>> = default();
+    }
+
+    #[derive(Debug,Default)]
+    struct Recorder;
+
+    impl<Levels:Debug> Processor<Entry<Levels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<Levels>) {
+            LEVELS.with(|levels| levels.borrow_mut().push(format!("{:?}",entry.level)));
+        }
+    }
+
+    fn take_levels() -> Vec<String> {
+        LEVELS.with(|levels| mem::take(&mut *levels.borrow_mut()))
+    }
+
+    #[test]
+    fn built_in_levels_are_filtered() {
+        let logger = WarningLogger::<Recorder>::new("test");
+        LoggerOps::<level::Info>::log(&logger,level::Info,"info");
+        LoggerOps::<level::Warning>::log(&logger,level::Warning,"warning");
+        LoggerOps::<level::Error>::log(&logger,level::Error,"error");
+        assert_eq!(take_levels(),vec!["Warning","Error"]);
+    }
+
+    #[test]
+    fn default_filter_follows_max_level_features() {
+        let logger = Logger::<DefaultFilter,Recorder>::new("test");
+        LoggerOps::<level::Debug>::log(&logger,level::Debug,"debug");
+        LoggerOps::<level::Info>::log(&logger,level::Info,"info");
+        LoggerOps::<level::Warning>::log(&logger,level::Warning,"warning");
+        LoggerOps::<level::Error>::log(&logger,level::Error,"error");
+        let expected : &[&str] =
+            if      cfg!(feature="max-level-error") { &["Error"] }
+            else if cfg!(feature="max-level-warn")  { &["Warning","Error"] }
+            else if cfg!(feature="max-level-info")  { &["Info","Warning","Error"] }
+            else                                    { &["Debug","Info","Warning","Error"] };
+        assert_eq!(take_levels(),expected);
+    }
+
+    #[test]
+    fn custom_levels_are_filtered() {
+        let logger = custom::Logger::<custom::filter_from::Normal,Recorder>::new("test");
+        LoggerOps::<custom::Verbose>::log(&logger,custom::Verbose,"verbose");
+        LoggerOps::<custom::Normal>::log(&logger,custom::Normal,"normal");
+        LoggerOps::<custom::Critical>::log(&logger,custom::Critical,"critical");
+        assert_eq!(take_levels(),vec!["Normal","Critical"]);
+    }
+}
@@ -0,0 +1,127 @@
+//! Task scopes, identifying the entries logged by different asynchronous tasks. When futures are
+//! interleaved, their entries are interleaved as well. Entries carrying the scope of their task can
+//! be reordered by the `ScopeBuffer` processor, so the groups of each task appear contiguous.
+
+use crate::prelude::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+
+
+// =================
+// === TaskScope ===
+// =================
+
+/// Identifier of an asynchronous task. All entries logged while the scope is entered are marked
+/// with it.
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+pub struct TaskScope {
+    id : u64,
+}
+
+thread_local! {
+    static NEXT_ID : Cell<u64>               = Cell::new(0);
+    static CURRENT : Cell<Option<TaskScope>> = Cell::new(None);
+}
+
+impl TaskScope {
+    /// Constructor of a new, unique scope.
+    pub fn new() -> Self {
+        let id = NEXT_ID.with(|next_id| next_id.replace(next_id.get() + 1));
+        Self {id}
+    }
+
+    /// Unique identifier of this scope.
+    pub fn id(self) -> u64 {
+        self.id
+    }
+
+    /// The scope entered in the current thread, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.with(|current| current.get())
+    }
+
+    /// Enter the scope. The previous scope is restored when the returned guard is dropped.
+    pub fn enter(self) -> Entered {
+        let previous = CURRENT.with(|current| current.replace(Some(self)));
+        Entered {previous}
+    }
+
+    /// Wrap the future, so the scope is entered every time the future is polled.
+    pub fn wrap<F:Future>(self, future:F) -> Scoped<F> {
+        let future = Box::pin(future);
+        Scoped {scope:self,future}
+    }
+}
+
+impl Default for TaskScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// === Entered ===
+
+/// Guard of the entered scope. See `TaskScope::enter` to learn more.
+#[derive(Debug)]
+pub struct Entered {
+    previous : Option<TaskScope>,
+}
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.previous))
+    }
+}
+
+
+// === Scoped ===
+
+/// Future entering its scope whenever it is polled. See `TaskScope::wrap` to learn more.
+#[derive(Debug)]
+pub struct Scoped<F> {
+    scope  : TaskScope,
+    future : Pin<Box<F>>,
+}
+
+impl<F:Future> Future for Scoped<F> {
+    type Output = F::Output;
+    fn poll(self:Pin<&mut Self>, cx:&mut Context<'_>) -> Poll<Self::Output> {
+        let this     = self.get_mut();
+        let _entered = this.scope.enter();
+        this.future.as_mut().poll(cx)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entering_scopes() {
+        let scope1 = TaskScope::new();
+        let scope2 = TaskScope::new();
+        assert_ne!(scope1,scope2);
+        assert_eq!(TaskScope::current(),None);
+        {
+            let _entered1 = scope1.enter();
+            assert_eq!(TaskScope::current(),Some(scope1));
+            {
+                let _entered2 = scope2.enter();
+                assert_eq!(TaskScope::current(),Some(scope2));
+            }
+            assert_eq!(TaskScope::current(),Some(scope1));
+        }
+        assert_eq!(TaskScope::current(),None);
+    }
+}
@@ -11,17 +11,27 @@
 #![warn(unused_import_braces)]
 #![feature(specialization)]
 
+pub mod clock;
 pub mod entry;
 pub mod macros;
+pub mod ops;
+pub mod path;
 pub mod processor;
 
 pub use enso_prelude as prelude;
 pub use entry::message::Message;
+pub use ops::OptionLogOps;
+pub use ops::ResultLogOps;
+pub use path::Path;
+pub use path::Symbol;
+pub use processor::counters::report_summary;
+pub use processor::counters::report_summary_on_panic;
 
 use prelude::*;
 
 use crate::entry::Entry;
 use crate::entry::DefaultFilter;
+use crate::entry::Table;
 use crate::entry::DefaultLevels;
 use crate::processor::DefaultProcessor;
 use crate::processor::Processor;
@@ -47,10 +57,10 @@ use std::fmt::Debug;
 #[derive(CloneRef,Debug,Derivative)]
 #[derivative(Clone(bound=""))]
 pub struct Logger<Filter=DefaultFilter, Processor=DefaultProcessor, Levels=DefaultLevels> {
-    path      : ImString,
-    filter    : PhantomData<Filter>,
-    levels    : PhantomData<Levels>,
-    processor : Rc<RefCell<Processor>>,
+    path      : Path,
+    filter    : ZST<Filter>,
+    levels    : ZST<Levels>,
+    processor : Shared<Processor>,
 }
 
 impl<Filter,Processor,Level> Logger<Filter,Processor,Level>
@@ -61,11 +71,44 @@ where Processor:Default {
     }
 }
 
+impl<Filter,Processor,Levels> Logger<Filter,Processor,Levels>
+where Processor:processor::Processor<Entry<Levels>> {
+    /// Pass a pre-formed entry to the processor, keeping its path, level, and content intact. It is
+    /// meant for adapters injecting entries created elsewhere, like bridges of other logging
+    /// libraries, receivers of entries sent by workers, or replays of recorded logs.
+    ///
+    /// Please note that the entry bypasses the compile-time filter, as its level is known only at
+    /// runtime.
+    pub fn submit_entry(&self, entry:Entry<Levels>) {
+        self.processor.with_mut(|processor| processor.submit(entry));
+    }
+
+    /// Check whether entries of the level pass the compile-time filter of the logger. It allows
+    /// skipping expensive computations of the data logged along with the message. Loggers behind
+    /// `dyn DynLoggerOps` keep their filters, and are checked with `LoggerOps::enabled`:
+    ///
+    /// ```
+    /// # use enso_logger::*;
+    /// # use enso_logger::entry::level;
+    /// let logger = DefaultWarningLogger::new("app");
+    /// assert!(logger.is_enabled::<level::Error>());
+    /// assert!(!logger.is_enabled::<level::Debug>());
+    ///
+    /// let logger : Box<dyn DynLoggerOps> = Box::new(logger);
+    /// assert!(LoggerOps::enabled(&logger,level::Error));
+    /// assert!(!LoggerOps::enabled(&logger,level::Debug));
+    /// ```
+    pub fn is_enabled<L:Default>(&self) -> bool
+    where Self:LoggerOps<L> {
+        LoggerOps::enabled(self,L::default())
+    }
+}
+
 impl<Filter,Processor,Level> AnyLogger for Logger<Filter,Processor,Level>
 where Processor:Default {
     type Owned = Self;
 
-    fn new(path:impl Into<ImString>) -> Self {
+    fn new(path:impl Into<Path>) -> Self {
         let path      = path.into();
         let filter    = default();
         let levels    = default();
@@ -73,7 +116,7 @@ where Processor:Default {
         Self {path,filter,levels,processor}
     }
 
-    fn path (&self) -> &str { &self.path }
+    fn path (&self) -> &Path { &self.path }
 }
 
 
@@ -90,23 +133,38 @@ pub trait AnyLogger {
     type Owned;
 
     /// Constructor.
-    fn new(path:impl Into<ImString>) -> Self::Owned;
+    fn new(path:impl Into<Path>) -> Self::Owned;
 
     /// Path that is used as an unique identifier of this logger.
-    fn path(&self) -> &str;
+    fn path(&self) -> &Path;
 
     /// Creates a new logger with this logger as a parent. It can be useful when we need to create
     /// a sub-logger for a generic type parameter.
     fn sub(logger:impl AnyLogger, id:impl AsRef<str>) -> Self::Owned
     where Self::Owned : AnyLogger<Owned=Self::Owned> {
-        Self::Owned::new(iformat!("{logger.path()}.{id.as_ref()}"))
+        Self::Owned::new(logger.path().sub(id.as_ref()))
+    }
+
+    /// A cheaper version of `sub`, for sub-loggers created very often, like per-node loggers. The
+    /// child segment is interned upfront, so creating the path does not touch any strings:
+    ///
+    /// ```
+    /// # use enso_logger::*;
+    /// let node   = Symbol::new("node");
+    /// let parent = DefaultInfoLogger::new("app.graph");
+    /// let child  = DefaultInfoLogger::sub_cached(&parent,node);
+    /// assert_eq!(child.path().to_string(),"app.graph.node");
+    /// ```
+    fn sub_cached(logger:impl AnyLogger, id:Symbol) -> Self::Owned
+    where Self::Owned : AnyLogger<Owned=Self::Owned> {
+        Self::Owned::new(logger.path().child(id))
     }
 }
 
 impl<T:AnyLogger> AnyLogger for &T {
     type Owned = T::Owned;
-    fn new(path:impl Into<ImString>) -> Self::Owned { T::new(path) }
-    fn path(&self) -> &str { T::path(self) }
+    fn new(path:impl Into<Path>) -> Self::Owned { T::new(path) }
+    fn path(&self) -> &Path { T::path(self) }
 }
 
 
@@ -140,12 +198,15 @@ define_logger_aliases! {
 // =================
 
 /// Primitive operations on a logger. The type parameter allows for compile-time log level filtering
-/// of the messages.
+/// of the messages. The `enabled` operation checks whether entries of the level are not filtered
+/// out.
 #[allow(missing_docs)]
 pub trait LoggerOps<Level=DefaultLevels> {
     fn log         (&self, level:Level, msg:impl Message);
     fn group_begin (&self, level:Level, collapsed:bool, msg:impl Message);
     fn group_end   (&self, level:Level);
+    fn table       (&self, level:Level, table:impl FnOnce() -> Table);
+    fn enabled     (&self, level:Level) -> bool;
 }
 
 
@@ -163,6 +224,14 @@ impl<T:LoggerOps<Level>,Level> LoggerOps<Level> for &T {
     fn group_end(&self, level:Level) {
         LoggerOps::group_end(*self,level)
     }
+
+    fn table(&self, level:Level, table:impl FnOnce() -> Table) {
+        LoggerOps::table(*self,level,table)
+    }
+
+    fn enabled(&self, level:Level) -> bool {
+        LoggerOps::enabled(*self,level)
+    }
 }
 
 
@@ -171,15 +240,137 @@ impl<T:LoggerOps<Level>,Level> LoggerOps<Level> for &T {
 impl<S,Filter,Level,L> LoggerOps<L> for Logger<Filter,S,Level>
 where S:Processor<Entry<Level>>, Level:From<L> {
     default fn log(&self, level:L, msg:impl Message) {
-        self.processor.borrow_mut().submit(Entry::message(level,self.path.clone(),msg));
+        self.submit_entry(Entry::message(level,self.path.clone(),msg));
     }
 
     default fn group_begin(&self, level:L, collapsed:bool, msg:impl Message) {
-        self.processor.borrow_mut().submit(Entry::group_begin(level,self.path.clone(),msg,collapsed));
+        self.submit_entry(Entry::group_begin(level,self.path.clone(),msg,collapsed));
     }
 
     default fn group_end(&self, level:L) {
-        self.processor.borrow_mut().submit(Entry::group_end(level,self.path.clone()));
+        self.submit_entry(Entry::group_end(level,self.path.clone()));
+    }
+
+    default fn table(&self, level:L, table:impl FnOnce() -> Table) {
+        self.submit_entry(Entry::table(level,self.path.clone(),table()));
+    }
+
+    default fn enabled(&self, _level:L) -> bool {
+        true
+    }
+}
+
+
+// === Dynamic Dispatch ===
+
+/// An object-safe version of `LoggerOps`, allowing storing loggers of different types behind
+/// `dyn`, for example to pass them to separately compiled plugins. It is implemented for all the
+/// loggers, and `dyn DynLoggerOps` implements `LoggerOps` for all the levels convertible to
+/// `Level`, so the logging macros can be used with it:
+///
+/// ```
+/// # use enso_logger::*;
+/// # use enso_logger::prelude::*;
+/// let logger : Box<dyn DynLoggerOps> = Box::new(DefaultWarningLogger::new("plugin"));
+/// warning!(logger,"Plugin loaded.");
+/// ```
+///
+/// The message and table functions are called at most once, and only if the entry is not filtered
+/// out. The levels are dispatched to the `LoggerOps` implementations of the concrete logger, so
+/// its compile-time filtering rules apply to the entries logged through `dyn` as well.
+#[allow(missing_docs)]
+pub trait DynLoggerOps<Level=DefaultLevels> {
+    fn dyn_log         (&self, level:Level, msg:&dyn Fn() -> String);
+    fn dyn_group_begin (&self, level:Level, collapsed:bool, msg:&dyn Fn() -> String);
+    fn dyn_group_end   (&self, level:Level);
+    fn dyn_table       (&self, level:Level, table:&dyn Fn() -> Table);
+    fn dyn_enabled     (&self, level:Level) -> bool;
+}
+
+impl<T,Level:DispatchLevel<T>> DynLoggerOps<Level> for T {
+    fn dyn_log(&self, level:Level, msg:&dyn Fn() -> String) {
+        level.dispatch_log(self,|| msg())
+    }
+
+    fn dyn_group_begin(&self, level:Level, collapsed:bool, msg:&dyn Fn() -> String) {
+        level.dispatch_group_begin(self,collapsed,|| msg())
+    }
+
+    fn dyn_group_end(&self, level:Level) {
+        level.dispatch_group_end(self)
+    }
+
+    fn dyn_table(&self, level:Level, table:&dyn Fn() -> Table) {
+        level.dispatch_table(self,table)
+    }
+
+    fn dyn_enabled(&self, level:Level) -> bool {
+        level.dispatch_enabled(self)
+    }
+}
+
+/// Dispatching of the operations on a logger to the `LoggerOps` implementations of the specific
+/// level, like `level::Warning`, selected by the value of a group of levels, like `DefaultLevels`.
+/// Unlike calling `LoggerOps<DefaultLevels>`, it respects the compile-time filtering rules, which
+/// are defined for the specific levels. It is used by the `DynLoggerOps` implementations.
+///
+/// ## WARNING
+/// This trait should be implemented automatically. See the macro `define_levels_group` to learn
+/// more.
+#[allow(missing_docs)]
+pub trait DispatchLevel<T:?Sized> {
+    fn dispatch_log         (self, logger:&T, msg:impl Message);
+    fn dispatch_group_begin (self, logger:&T, collapsed:bool, msg:impl Message);
+    fn dispatch_group_end   (self, logger:&T);
+    fn dispatch_table       (self, logger:&T, table:impl FnOnce() -> Table);
+    fn dispatch_enabled     (self, logger:&T) -> bool;
+}
+
+impl<L,Level:From<L>> LoggerOps<L> for dyn DynLoggerOps<Level> + '_ {
+    fn log(&self, level:L, msg:impl Message) {
+        let msg = Cell::new(Some(msg));
+        self.dyn_log(level.into(),&|| msg.take().map(Message::get).unwrap_or_default())
+    }
+
+    fn group_begin(&self, level:L, collapsed:bool, msg:impl Message) {
+        let msg = Cell::new(Some(msg));
+        let msg = || msg.take().map(Message::get).unwrap_or_default();
+        self.dyn_group_begin(level.into(),collapsed,&msg)
+    }
+
+    fn group_end(&self, level:L) {
+        self.dyn_group_end(level.into())
+    }
+
+    fn table(&self, level:L, table:impl FnOnce() -> Table) {
+        let table = Cell::new(Some(table));
+        self.dyn_table(level.into(),&|| table.take().map(|table| table()).unwrap_or_default())
+    }
+
+    fn enabled(&self, level:L) -> bool {
+        self.dyn_enabled(level.into())
+    }
+}
+
+impl<L,Level:From<L>> LoggerOps<L> for Box<dyn DynLoggerOps<Level> + '_> {
+    fn log(&self, level:L, msg:impl Message) {
+        LoggerOps::log(&**self,level,msg)
+    }
+
+    fn group_begin(&self, level:L, collapsed:bool, msg:impl Message) {
+        LoggerOps::group_begin(&**self,level,collapsed,msg)
+    }
+
+    fn group_end(&self, level:L) {
+        LoggerOps::group_end(&**self,level)
+    }
+
+    fn table(&self, level:L, table:impl FnOnce() -> Table) {
+        LoggerOps::table(&**self,level,table)
+    }
+
+    fn enabled(&self, level:L) -> bool {
+        LoggerOps::enabled(&**self,level)
     }
 }
 
@@ -187,27 +378,36 @@ where S:Processor<Entry<Level>>, Level:From<L> {
 // === Compile-time Filtering ===
 
 /// Defines specialized version of compile time filtering rules for the given filtering levels.
-/// It defines specialized implementations for the default implementation above. See the usage
-/// below to learn more.
+/// It defines specialized implementations for the default implementation above, so loggers with
+/// the given filter drop entries of the listed levels with zero runtime overhead. It can be used
+/// with any levels, also in other crates, as long as they enable the `specialization` feature:
+///
+/// ```
+/// # #![allow(incomplete_features)]
+/// # #![feature(specialization)]
+/// # use enso_logger::*;
+/// mod levels {
+///     enso_logger::define_levels!(Minor,Major);
+///     enso_logger::define_levels_group!(Levels {Minor,Major});
+///     enso_logger::define_compile_time_filtering_rules! {
+///         filter_from::Major => Minor;
+///     }
+/// }
+/// ```
+///
+/// If the levels are ordered linearly, it is simpler to use `define_filtering_rules_for_levels`,
+/// or `define_custom_logger`, which defines the levels and the rules at once.
 #[macro_export]
 macro_rules! define_compile_time_filtering_rules {
-    ($(for level::from::$filter:ident remove $($level:ident),*;)*) => {$($(
-        impl<S,Level> LoggerOps<entry::level::$level>
-        for Logger<entry::level::filter_from::$filter,S,Level>
-        where S:Processor<Entry<Level>>, Level:From<entry::level::$level> {
-            fn log         (&self, _lvl:entry::level::$level, _msg:impl Message) {}
-            fn group_begin (&self, _lvl:entry::level::$level, _collapsed:bool, _msg:impl Message) {}
-            fn group_end   (&self, _lvl:entry::level::$level) {}
+    ($($filter:path => $($level:path),*;)*) => {$($(
+        impl<S,Level> $crate::LoggerOps<$level> for $crate::Logger<$filter,S,Level>
+        where S     : $crate::processor::Processor<$crate::entry::Entry<Level>>,
+              Level : From<$level> {
+            fn log         (&self, _lvl:$level, _msg:impl $crate::Message) {}
+            fn group_begin (&self, _lvl:$level, _collapsed:bool, _msg:impl $crate::Message) {}
+            fn group_end   (&self, _lvl:$level) {}
+            fn table       (&self, _lvl:$level, _table:impl FnOnce() -> $crate::entry::Table) {}
+            fn enabled     (&self, _lvl:$level) -> bool { false }
         }
     )*)*};
 }
-
-
-// === Compile-time filtering of built-in levels ===
-
-define_compile_time_filtering_rules! {
-    for level::from::Debug   remove Trace;
-    for level::from::Info    remove Trace,Debug;
-    for level::from::Warning remove Trace,Debug,Info;
-    for level::from::Error   remove Trace,Debug,Info,Warning;
-}
@@ -42,16 +42,19 @@ macro_rules! log_template {
     };
 }
 
-/// Internal utility for logging macros.
+/// Internal utility for logging macros. The group is ended when the body is left, also by an
+/// early return or a panic.
 #[macro_export]
 macro_rules! log_template_group {
     ($expand:ident, $level:path, $logger:expr, [$($msg:tt)*], || $($body:tt)*) => {
         {
+            let logger = &$logger;
             $crate::LoggerOps::<$level>::group_begin
-                (&$logger,$level,$crate::collapsed_to_bool!($expand),$($msg)*);
-            let out = $($body)*;
-            $crate::LoggerOps::<$level>::group_end(&$logger,$level);
-            out
+                (logger,$level,$crate::collapsed_to_bool!($expand),$($msg)*);
+            let _group_end = $crate::prelude::defer(move || {
+                $crate::LoggerOps::<$level>::group_end(logger,$level)
+            });
+            $($body)*
         }
     };
 }
@@ -102,3 +105,106 @@ define_log_macros!{
     $ warning Warning collapsed;
     $ error   Error   collapsed;
 }
+
+
+// === Tables ===
+
+/// Table logging macro, like `table!(logger,level::Info,Table::new("Passes",&["name","time"]))`.
+/// The table expression is evaluated only if the level is not filtered out. See `entry::Table` to
+/// learn more.
+#[macro_export]
+macro_rules! table {
+    ($logger:expr, $level:path, $table:expr) => {
+        $crate::LoggerOps::<$level>::table(&$logger,$level,|| $table)
+    };
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::entry::Content;
+    use crate::entry::Entry;
+    use crate::entry::filter_from;
+    use crate::processor::Processor;
+
+    thread_local! {
+        static ENTRIES : RefCell<Vec<String>> = default();
+    }
+
+    #[derive(Debug,Default)]
+    struct Recorder;
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            let description = match &entry.content {
+                Content::Message(msg)      => msg.clone(),
+                Content::GroupBegin(group) => iformat!("begin {group.message}"),
+                Content::GroupEnd(_)       => "end".into(),
+                Content::Table(table)      => table.to_string(),
+            };
+            ENTRIES.with(|entries| entries.borrow_mut().push(description));
+        }
+    }
+
+    type TestLogger = Logger<filter_from::Trace,Recorder>;
+
+    fn group_with_early_return(logger:&TestLogger) -> Option<usize> {
+        info!(logger,"group",|| {
+            info!(logger,"inside");
+            None?;
+            Some(1)
+        })
+    }
+
+    fn take_entries() -> Vec<String> {
+        ENTRIES.with(|entries| mem::take(&mut *entries.borrow_mut()))
+    }
+
+    #[test]
+    fn group_is_ended_on_early_return() {
+        let logger = TestLogger::new("test");
+        assert_eq!(group_with_early_return(&logger),None);
+        assert_eq!(take_entries(),vec!["begin group","inside","end"]);
+    }
+
+    #[test]
+    fn macros_work_with_dyn_loggers() {
+        let logger : Box<dyn DynLoggerOps> = Box::new(TestLogger::new("test"));
+        warning!(logger,"group",|| {
+            debug!(logger,"inside");
+        });
+        assert_eq!(take_entries(),vec!["begin group","inside","end"]);
+    }
+
+    #[test]
+    fn dyn_loggers_are_filtered() {
+        let logger = WarningLogger::<Recorder>::new("test");
+        let logger : Box<dyn DynLoggerOps> = Box::new(logger);
+        debug!(logger,"debug");
+        trace!(logger,"group",|| {
+            warning!(logger,"warning");
+        });
+        error!(logger,"error");
+        assert_eq!(take_entries(),vec!["warning","error"]);
+    }
+
+    #[test]
+    fn tables_are_logged_lazily() {
+        use crate::entry::level;
+        use crate::entry::Table;
+        let logger = DefaultWarningLogger::new("test");
+        table!(logger,level::Info,panic!("The table should not be built."));
+        let logger  = TestLogger::new("test");
+        let columns = &["pass","time"];
+        table!(logger,level::Info,Table::new("Passes",columns).row(&["shapes","12"]).row(&["ui"]));
+        let expected = "Passes\npass   | time\nshapes | 12\nui     |";
+        assert_eq!(take_entries(),vec![expected]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Extension traits for `Result` and `Option` which log the failures, replacing the common
+//! `if let Err(e) = ... { warning!(...) }` boilerplate.
+
+use crate::prelude::*;
+
+use crate::LoggerOps;
+use crate::entry::level;
+
+
+
+// ====================
+// === ResultLogOps ===
+// ====================
+
+/// Adds logging utilities to the `Result` type. Errors are logged as warnings.
+pub trait ResultLogOps {
+    /// The `Ok` type.
+    type Item;
+
+    /// Log the error, if any, and return the result unchanged.
+    fn log_err(self, logger:impl LoggerOps<level::Warning>) -> Self;
+
+    /// Log the error, if any, and convert the result to an `Option`.
+    fn ok_or_log(self, logger:impl LoggerOps<level::Warning>) -> Option<Self::Item>;
+
+    /// Log the error, if any, prefixed with the context, like `Cannot open file: <error>`, and
+    /// convert the result to an `Option`.
+    fn ok_or_log_with
+    (self, logger:impl LoggerOps<level::Warning>, context:impl AsRef<str>) -> Option<Self::Item>;
+}
+
+impl<T,E:Display> ResultLogOps for Result<T,E> {
+    type Item = T;
+
+    fn log_err(self, logger:impl LoggerOps<level::Warning>) -> Self {
+        if let Err(error) = &self {
+            logger.log(level::Warning,|| error.to_string())
+        }
+        self
+    }
+
+    fn ok_or_log(self, logger:impl LoggerOps<level::Warning>) -> Option<T> {
+        self.log_err(logger).ok()
+    }
+
+    fn ok_or_log_with
+    (self, logger:impl LoggerOps<level::Warning>, context:impl AsRef<str>) -> Option<T> {
+        self.map_err(|error| {
+            let context = context.as_ref();
+            logger.log(level::Warning,|| iformat!("{context}: {error}"))
+        }).ok()
+    }
+}
+
+
+
+// ====================
+// === OptionLogOps ===
+// ====================
+
+/// Adds logging utilities to the `Option` type. Missing values are logged as warnings.
+pub trait OptionLogOps {
+    /// Log the message if the value is `None`, and return the option unchanged. The message can be
+    /// provided lazily, as a function.
+    fn log_none(self, logger:impl LoggerOps<level::Warning>, msg:impl crate::Message) -> Self;
+}
+
+impl<T> OptionLogOps for Option<T> {
+    fn log_none(self, logger:impl LoggerOps<level::Warning>, msg:impl crate::Message) -> Self {
+        if self.is_none() {
+            logger.log(level::Warning,msg)
+        }
+        self
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Logger recording the logged messages.
+    #[derive(Debug,Default)]
+    struct Recorder {
+        messages : RefCell<Vec<String>>,
+    }
+
+    impl LoggerOps<level::Warning> for Recorder {
+        fn log(&self, _level:level::Warning, msg:impl crate::Message) {
+            self.messages.borrow_mut().push(msg.get())
+        }
+        fn group_begin(&self, _level:level::Warning, _collapsed:bool, _msg:impl crate::Message) {}
+        fn group_end(&self, _level:level::Warning) {}
+        fn table(&self, _level:level::Warning, _table:impl FnOnce() -> crate::entry::Table) {}
+        fn enabled(&self, _level:level::Warning) -> bool { true }
+    }
+
+    #[test]
+    fn logging_failures() {
+        let logger = Recorder::default();
+        let ok     : Result<usize,String> = Ok(1);
+        let err    : Result<usize,String> = Err("failed".into());
+        assert_eq!(ok.clone().log_err(&logger),Ok(1));
+        assert_eq!(err.clone().log_err(&logger),Err("failed".into()));
+        assert_eq!(ok.ok_or_log(&logger),Some(1));
+        assert_eq!(err.clone().ok_or_log(&logger),None);
+        assert_eq!(err.ok_or_log_with(&logger,"Cannot open"),None);
+        assert_eq!(Some(1).log_none(&logger,"missing"),Some(1));
+        assert_eq!(None::<usize>.log_none(&logger,|| "missing".to_string()),None);
+        let messages = logger.messages.borrow().clone();
+        assert_eq!(messages,vec!["failed","failed","Cannot open: failed","missing"]);
+    }
+}
@@ -0,0 +1,312 @@
+//! Logger paths. A path is a sequence of segments, like `app.gui.graph`. The segments are interned
+//! in a thread-local `SymbolTable`, so paths with common segments share their memory, and the
+//! string representation is created only when the path is displayed.
+
+use crate::prelude::*;
+
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+
+
+// ==============
+// === Symbol ===
+// ==============
+
+/// A reference-counted string, usually interned in a `SymbolTable`. Symbols of the same string
+/// interned in the same table share their memory, so comparing them does not touch their content.
+/// Symbols are compared and hashed by their content otherwise, so interned and uninterned symbols
+/// of the same string are equal. As symbols are reference-counted, they cannot be sent to other
+/// threads.
+#[derive(Clone)]
+pub struct Symbol {
+    name : Rc<str>,
+}
+
+thread_local! {
+    static SYMBOLS : RefCell<SymbolTable> = default();
+}
+
+impl Symbol {
+    /// Intern the name in the thread-local symbol table used by logger paths.
+    pub fn new(name:&str) -> Self {
+        SYMBOLS.with(|symbols| symbols.borrow_mut().intern(name))
+    }
+
+    /// Constructor of a symbol which is not interned. Useful for names received from untrusted
+    /// sources, like other processes, which should not grow the symbol table.
+    pub fn uninterned(name:&str) -> Self {
+        let name = name.into();
+        Self {name}
+    }
+
+    /// The name of this symbol.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other:&Self) -> bool {
+        Rc::ptr_eq(&self.name,&other.name) || self.name == other.name
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H:Hasher>(&self, state:&mut H) {
+        self.name.hash(state)
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other:&Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other:&Self) -> Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+impl Debug for Symbol {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"Symbol({:?})",self.name())
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+
+
+// ===================
+// === SymbolTable ===
+// ===================
+
+/// The minimal number of names in a `SymbolTable` before the unused ones are purged.
+const MIN_PURGE_LEN : usize = 64;
+
+/// A table interning strings as `Symbol`s. The table forgets the names which are not used by any
+/// symbol anymore. They are purged every time the table grows twice since the last purge, so the
+/// size of the table is proportional to the number of names in use, and interning takes amortized
+/// constant time.
+#[derive(Debug)]
+pub struct SymbolTable {
+    names     : HashSet<Rc<str>>,
+    purge_len : usize,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        let names     = default();
+        let purge_len = MIN_PURGE_LEN;
+        Self {names,purge_len}
+    }
+}
+
+impl SymbolTable {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Get the symbol of the name, interning it if it is not used by any other symbol.
+    pub fn intern(&mut self, name:&str) -> Symbol {
+        let name = match self.names.get(name) {
+            Some(name) => name.clone(),
+            None       => {
+                if self.names.len() >= self.purge_len { self.purge() }
+                let name : Rc<str> = name.into();
+                self.names.insert(name.clone());
+                name
+            }
+        };
+        Symbol {name}
+    }
+
+    /// Forget the names which are not used by any symbol.
+    fn purge(&mut self) {
+        self.names.retain(|name| Rc::strong_count(name) > 1);
+        self.purge_len = (self.names.len() * 2).max(MIN_PURGE_LEN);
+    }
+
+    /// Number of interned names, including the ones which are not used anymore but were not purged
+    /// yet.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check whether no names are interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+
+
+// ============
+// === Path ===
+// ============
+
+/// A dot-separated logger path, like `app.gui.graph`, stored as a sequence of interned segments.
+/// Cloning a path is cheap, and paths sharing their segments are compared without touching their
+/// content.
+#[derive(Clone,Eq,Hash,PartialEq)]
+pub struct Path {
+    segments : Rc<[Symbol]>,
+}
+
+impl_clone_ref_as_clone_no_from!(Path);
+
+impl Path {
+    /// Constructor. Splits the path at dots and interns the segments. An empty string gives an
+    /// empty path.
+    pub fn new(path:&str) -> Self {
+        if path.is_empty() { default() } else {
+            let segments = path.split('.').map(Symbol::new).collect();
+            Self {segments}
+        }
+    }
+
+    /// Constructor which does not intern the segments. Useful for paths received from untrusted
+    /// sources, like other processes, which should not grow the symbol table. See `Symbol` to
+    /// learn more.
+    pub fn uninterned(path:&str) -> Self {
+        if path.is_empty() { default() } else {
+            let segments = path.split('.').map(Symbol::uninterned).collect();
+            Self {segments}
+        }
+    }
+
+    /// Path of a child, extended with the given segment. No strings are formatted or hashed.
+    pub fn child(&self, segment:Symbol) -> Self {
+        let segments = self.segments.iter().cloned().chain(iter::once(segment)).collect();
+        Self {segments}
+    }
+
+    /// Path of a descendant, extended with the segments of the given dot-separated path.
+    pub fn sub(&self, path:&str) -> Self {
+        if path.is_empty() { return self.clone_ref() }
+        let new_segments = path.split('.').map(Symbol::new);
+        let segments     = self.segments.iter().cloned().chain(new_segments).collect();
+        Self {segments}
+    }
+
+    /// The interned segments of this path.
+    pub fn segments(&self) -> &[Symbol] {
+        &self.segments
+    }
+
+    /// Check whether this path is equal to the `prefix` path or is its descendant.
+    pub fn starts_with(&self, prefix:&Path) -> bool {
+        self.segments.starts_with(&prefix.segments)
+    }
+
+    /// Check whether the path has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        let segments = Rc::new([]);
+        Self {segments}
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index,segment) in self.segments.iter().enumerate() {
+            if index > 0 { f.write_char('.')?; }
+            f.write_str(segment.name())?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Path {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"Path({:?})",self.to_string())
+    }
+}
+
+impl From<&str>      for Path { fn from(t:&str)      -> Self { Self::new(t) } }
+impl From<&String>   for Path { fn from(t:&String)   -> Self { Self::new(t) } }
+impl From<String>    for Path { fn from(t:String)    -> Self { Self::new(&t) } }
+impl From<&ImString> for Path { fn from(t:&ImString) -> Self { Self::new(t) } }
+impl From<ImString>  for Path { fn from(t:ImString)  -> Self { Self::new(&t) } }
+impl From<&Path>     for Path { fn from(t:&Path)     -> Self { t.clone_ref() } }
+
+impl PartialEq<&str> for Path {
+    fn eq(&self, other:&&str) -> bool {
+        if other.is_empty() { return self.is_empty() }
+        self.segments.iter().map(|segment| segment.name()).eq(other.split('.'))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_table() {
+        let mut table = SymbolTable::new();
+        let app       = table.intern("app");
+        let gui       = table.intern("gui");
+        assert!(Rc::ptr_eq(&table.intern("app").name,&app.name));
+        assert_ne!(app,gui);
+        assert_eq!(app,Symbol::uninterned("app"));
+        assert_eq!(table.len(),2);
+        assert_eq!(gui.name(),"gui");
+    }
+
+    #[test]
+    fn unused_symbols_are_purged() {
+        let mut table = SymbolTable::new();
+        let app       = table.intern("app");
+        for index in 0..10_000 {
+            table.intern(&index.to_string());
+        }
+        assert!(table.len() <= MIN_PURGE_LEN);
+        assert!(Rc::ptr_eq(&table.intern("app").name,&app.name));
+    }
+
+    #[test]
+    fn paths_share_segments() {
+        let path1 = Path::new("app.gui.graph");
+        let path2 = Path::new("app.gui.node");
+        assert_eq!(path1.segments()[..2],path2.segments()[..2]);
+        assert_ne!(path1,path2);
+        assert_eq!(path1,"app.gui.graph");
+        assert_eq!(path1.to_string(),"app.gui.graph");
+        assert!(Path::new("").is_empty());
+        assert_eq!(Path::new("").to_string(),"");
+    }
+
+    #[test]
+    fn child_paths() {
+        let path = Path::new("app.gui");
+        assert_eq!(path.child(Symbol::new("graph")),"app.gui.graph");
+        assert_eq!(path.sub("graph.node"),"app.gui.graph.node");
+        assert_eq!(path.sub(""),path);
+        assert_eq!(Path::default().child(Symbol::new("app")),"app");
+        assert!(path.sub("graph").starts_with(&path));
+        assert!(path.starts_with(&path));
+        assert!(!Path::new("app.guide").starts_with(&path));
+        assert!(Path::uninterned("app.gui.graph").starts_with(&path));
+    }
+}
@@ -1,10 +1,31 @@
 //! Logger processor implementation.
 
 pub mod consumer;
+pub mod counters;
+pub mod error_flush;
 pub mod formatter;
+pub mod frame_profiler;
+pub mod group_tracker;
+pub mod ipc;
+pub mod redactor;
+pub mod router;
+pub mod stats;
+pub mod timestamper;
+
+pub use counters::Counters;
+pub use error_flush::ErrorFlush;
+pub use frame_profiler::FrameProfiler;
+pub use group_tracker::GroupTracker;
+pub use ipc::IpcReader;
+pub use ipc::IpcWriter;
+pub use redactor::Redactor;
+pub use router::Router;
+pub use timestamper::Timestamper;
 
 use crate::prelude::*;
+use crate::entry::Content;
 use crate::entry::Entry;
+use crate::entry::TaskScope;
 use crate::entry::level::DefaultLevels;
 use wasm_bindgen::prelude::*;
 
@@ -257,17 +278,20 @@ where C:consumer::Definition<Levels,Message> {
 
 // === Buffer ===
 
+/// Processor buffering the inputs until the user calls `showLogs()` in the JavaScript console.
+/// Inputs chosen by the `Flush` policy, like errors, flush the relevant part of the buffer
+/// immediately and drop the rest, so the context of the problem is visible when it happens. See
+/// the `error_flush` module to learn more.
 #[derive(Debug,Derivative)]
-#[allow(missing_docs)]
-pub struct Buffer<Input,Next> {
-    model   : Rc<RefCell<BufferModel<Input,Next>>>,
+pub struct Buffer<Input,Next,Flush=error_flush::NoFlush> {
+    model   : Rc<RefCell<BufferModel<Input,Next,Flush>>>,
     closure : Closure<dyn Fn()>,
 }
 
-impl<Input,Next> Default for Buffer<Input,Next>
-    where Input:'static, Next:'static+Default+Processor<Input> {
+impl<Input,Next,Flush> Default for Buffer<Input,Next,Flush>
+    where Input:'static, Next:'static+Default+Processor<Input>, Flush:'static {
     fn default() -> Self {
-        let model   = Rc::new(RefCell::new(BufferModel::<Input,Next>::default()));
+        let model   = Rc::new(RefCell::new(BufferModel::<Input,Next,Flush>::default()));
         let closure = Closure::new(f!(model.borrow_mut().flush_and_enable_auto_flush()));
         js::setup_logs_flush(&closure);
         if cfg!(debug_assertions) {
@@ -278,8 +302,8 @@ impl<Input,Next> Default for Buffer<Input,Next>
     }
 }
 
-impl<Input,Next> Processor<Input> for Buffer<Input,Next>
-    where Next:Processor<Input> {
+impl<Input,Next,Flush> Processor<Input> for Buffer<Input,Next,Flush>
+    where Next:Processor<Input>, Flush:ErrorFlush<Input> {
     type Output = ();
     #[inline(always)]
     fn submit(&mut self, input:Input) {
@@ -289,13 +313,14 @@ impl<Input,Next> Processor<Input> for Buffer<Input,Next>
 
 #[derive(Debug)]
 #[allow(missing_docs)]
-pub struct BufferModel<Input,Next> {
+pub struct BufferModel<Input,Next,Flush=error_flush::NoFlush> {
     buffer     : Vec<Input>,
     auto_flush : bool,
     next       : Next,
+    flush      : PhantomData<Flush>,
 }
 
-impl<Input,Next> BufferModel<Input,Next>
+impl<Input,Next,Flush> BufferModel<Input,Next,Flush>
 where Next:Processor<Input> {
     /// Constructor.
     pub fn new() -> Self
@@ -303,14 +328,22 @@ where Next:Processor<Input> {
         let auto_flush = js::check_auto_flush();
         let buffer     = default();
         let next       = default();
-        Self {buffer,auto_flush,next}
+        let flush      = default();
+        Self {buffer,auto_flush,next,flush}
     }
 
     /// Submit the input to the buffer or the subsequent processor in case the `auto_flush` is
-    /// enabled.
-    pub fn submit(&mut self, input:Input) {
+    /// enabled. If the `Flush` policy is triggered by the input, the chosen buffered inputs are
+    /// passed to the subsequent processor before it, and the other ones are dropped.
+    pub fn submit(&mut self, input:Input)
+    where Flush:ErrorFlush<Input> {
         if self.auto_flush {
             self.next.submit(input);
+        } else if Flush::is_trigger(&input) {
+            for buffered in Flush::take(mem::take(&mut self.buffer),&input) {
+                self.next.submit(buffered);
+            }
+            self.next.submit(input);
         } else {
             self.buffer.push(input);
         }
@@ -330,7 +363,7 @@ where Next:Processor<Input> {
     }
 }
 
-impl<Input,Next> Default for BufferModel<Input,Next>
+impl<Input,Next,Flush> Default for BufferModel<Input,Next,Flush>
 where Next : Processor<Input> + Default {
     fn default() -> Self {
         Self::new()
@@ -338,12 +371,74 @@ where Next : Processor<Input> + Default {
 }
 
 
+// === ScopeBuffer ===
+
+/// Processor keeping the groups of asynchronous tasks contiguous. Entries logged inside a group in
+/// a `TaskScope` are buffered until the outermost group of the scope ends, and then they are passed
+/// to the subsequent processor at once. Entries without a scope, or logged outside of any group,
+/// are passed immediately.
+#[derive(Debug,Derivative)]
+#[derivative(Default(bound="Next:Default"))]
+pub struct ScopeBuffer<Levels,Next> {
+    scopes : HashMap<TaskScope,ScopeBufferModel<Levels>>,
+    next   : Next,
+}
+
+#[derive(Debug,Derivative)]
+#[derivative(Default(bound=""))]
+struct ScopeBufferModel<Levels> {
+    depth   : usize,
+    entries : Vec<Entry<Levels>>,
+}
+
+impl<Levels,Next> ScopeBuffer<Levels,Next>
+where Next:Processor<Entry<Levels>> {
+    /// Pass all buffered entries to the subsequent processor, even if their groups did not end.
+    /// Useful when tasks were cancelled in the middle of a group.
+    pub fn flush(&mut self) {
+        for (_,scope) in mem::take(&mut self.scopes) {
+            for entry in scope.entries {
+                self.next.submit(entry);
+            }
+        }
+    }
+}
+
+impl<Levels,Next> Processor<Entry<Levels>> for ScopeBuffer<Levels,Next>
+where Next:Processor<Entry<Levels>> {
+    type Output = ();
+    fn submit(&mut self, entry:Entry<Levels>) {
+        let scope_id = match entry.scope {
+            Some(scope_id) => scope_id,
+            None           => { self.next.submit(entry); return }
+        };
+        let scope = self.scopes.entry(scope_id).or_default();
+        match entry.content {
+            Content::GroupBegin(_) => scope.depth += 1,
+            Content::GroupEnd(_)   => scope.depth = scope.depth.saturating_sub(1),
+            Content::Message(_) | Content::Table(_) => if scope.depth == 0 {
+                self.next.submit(entry);
+                return
+            }
+        }
+        scope.entries.push(entry);
+        if scope.depth == 0 {
+            if let Some(scope) = self.scopes.remove(&scope_id) {
+                for entry in scope.entries {
+                    self.next.submit(entry);
+                }
+            }
+        }
+    }
+}
+
+
 // === Global ===
 
 #[derive(Debug,Default)]
 #[allow(missing_docs)]
 pub struct Global<Processor> {
-    processor : PhantomData<Processor>
+    processor : ZST<Processor>
 }
 
 impl<P,Input> Processor<Input> for Global<P>
@@ -351,7 +446,7 @@ impl<P,Input> Processor<Input> for Global<P>
     type Output = <<P as GlobalProcessor>::Processor as Processor<Input>>::Output;
     #[inline(always)]
     fn submit(&mut self, entry:Input) -> Self::Output {
-        global_processor::<P>().submit(entry)
+        P::with_mut(|processor| processor.submit(entry))
     }
 }
 
@@ -362,42 +457,30 @@ impl<P,Input> Processor<Input> for Global<P>
 #[allow(missing_docs)]
 pub trait GlobalProcessor {
     type Processor;
-    fn get_mut() -> &'static mut Self::Processor;
+    fn with_mut<R>(f:impl FnOnce(&mut Self::Processor) -> R) -> R;
 }
 
-/// Get a reference to a global processor. Read docs of `GlobalProcessor` to learn more.
-pub fn global_processor<T:GlobalProcessor>() -> &'static mut T::Processor {
-    T::get_mut()
+/// Run the function with a mutable reference to a global processor. Read docs of
+/// `GlobalProcessor` to learn more.
+pub fn with_global_processor<T,R,F>(f:F) -> R
+where T:GlobalProcessor, F:FnOnce(&mut T::Processor) -> R {
+    T::with_mut(f)
 }
 
 /// Define a global processor based on the provided type. Read the docs of `GlobalProcessor` to
-/// learn more.
+/// learn more. The processor is stored with `define_global`, so it is created on the first use.
 #[macro_export]
 macro_rules! define_global_processor {
     ($name:ident = $tp:ty;) => {
-        /// Global processor definition.
-        #[derive(Copy,Clone,Debug,Default)]
-        pub struct $name;
-        paste::item! {
-            #[allow(non_upper_case_globals)]
-            static mut [<$name _STATIC_MUT>]: Option<$tp> = None;
+        $crate::prelude::define_global! {
+            /// Global processor definition.
+            pub $name : $tp;
         }
+
         impl GlobalProcessor for $name {
             type Processor = $tp;
-            paste::item! {
-                #[allow(unsafe_code)]
-                fn get_mut() -> &'static mut Self::Processor {
-                    unsafe {
-                        match &mut [<$name _STATIC_MUT>] {
-                            Some(t) => t,
-                            None    => {
-                                let processor = default();
-                                [<$name _STATIC_MUT>] = Some(processor);
-                                [<$name _STATIC_MUT>].as_mut().unwrap()
-                            }
-                        }
-                    }
-                }
+            fn with_mut<R>(f:impl FnOnce(&mut Self::Processor) -> R) -> R {
+                $name::with_mut(f)
             }
         }
     };
@@ -409,17 +492,42 @@ macro_rules! define_global_processor {
 // === DefaultProcessor ===
 // ========================
 
-/// Default processor implementation.
+/// Default processor implementation. Warnings and errors are counted, so they can be summarized
+/// with `report_summary`, and group ends carry the messages of the closed groups.
+#[cfg(target_arch="wasm32")]
+pub type DefaultProcessor = Counters<GroupTracker<DefaultJsProcessor>>;
+
+/// Default processor implementation. Warnings and errors are counted, so they can be summarized
+/// with `report_summary`, and group ends carry the messages of the closed groups.
+#[cfg(not(target_arch="wasm32"))]
+pub type DefaultProcessor = Counters<GroupTracker<DefaultNativeProcessor>>;
+
+/// Processor formatting and printing the entries immediately, without buffering. Unlike
+/// `DefaultProcessor`, it can be used with custom levels.
 #[cfg(target_arch="wasm32")]
-pub type DefaultProcessor = DefaultJsProcessor;
+pub type DirectProcessor = DirectJsProcessor;
 
-/// Default processor implementation.
+/// Processor formatting and printing the entries immediately, without buffering. Unlike
+/// `DefaultProcessor`, it can be used with custom levels.
 #[cfg(not(target_arch="wasm32"))]
-pub type DefaultProcessor = DefaultNativeProcessor;
+pub type DirectProcessor = DefaultNativeProcessor;
+
+/// Formatter of the JavaScript processors. The `minimal` feature replaces the styled output with
+/// plain text, which keeps the logging code in WASM binaries small.
+#[cfg(not(feature="minimal"))]
+type JsFormatter = formatter::JsConsole;
+
+/// Formatter of the JavaScript processors. The `minimal` feature replaces the styled output with
+/// plain text, which keeps the logging code in WASM binaries small.
+#[cfg(feature="minimal")]
+type JsFormatter = formatter::Plain;
 
 #[allow(dead_code)]
 type DefaultJsProcessor = Global<DefaultGlobalJsProcessor>;
 
+#[allow(dead_code)]
+type DirectJsProcessor = Seq<Formatter<JsFormatter>,Consumer<consumer::JsConsole>>;
+
 #[allow(dead_code)]
 type DefaultNativeProcessor =
     Seq<Formatter<formatter::NativeConsole>,Consumer<consumer::NativeConsole>>;
@@ -428,8 +536,81 @@ define_global_processor! {
     DefaultGlobalJsProcessor =
         Buffer<Entry<DefaultLevels>,
             Seq <
-                Formatter<formatter::JsConsole>,
+                Formatter<JsFormatter>,
                 Consumer<consumer::JsConsole>
-            >
+            >,
+            error_flush::FlushAll
         >;
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug,Default)]
+    pub struct Recorder {
+        inputs : Vec<usize>,
+    }
+
+    impl Processor<usize> for Recorder {
+        type Output = ();
+        fn submit(&mut self, input:usize) {
+            self.inputs.push(input)
+        }
+    }
+
+    define_global_processor! {
+        GlobalRecorder = Recorder;
+    }
+
+    #[derive(Debug,Default)]
+    pub struct MessageRecorder {
+        messages : Vec<String>,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for MessageRecorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            self.messages.push(entry.content.message().unwrap_or("end").into())
+        }
+    }
+
+    #[test]
+    fn global_processor_is_shared() {
+        Global::<GlobalRecorder>::default().submit(1);
+        Global::<GlobalRecorder>::default().submit(2);
+        let inputs = with_global_processor::<GlobalRecorder,_,_>(|t| mem::take(&mut t.inputs));
+        assert_eq!(inputs,vec![1,2]);
+    }
+
+    #[test]
+    fn scope_buffer_keeps_groups_contiguous() {
+        use crate::entry::level::Info;
+        let mut buffer = ScopeBuffer::<DefaultLevels,MessageRecorder>::default();
+        let task1      = TaskScope::new();
+        let task2      = TaskScope::new();
+        let mut log    = |scope:Option<TaskScope>, message:Option<&str>| {
+            let _entered = scope.map(|scope| scope.enter());
+            let entry    = match message {
+                Some(message) => Entry::group_begin(Info,"test".into(),message,false),
+                None          => Entry::group_end(Info,"test".into()),
+            };
+            buffer.submit(entry);
+        };
+        log(Some(task1),Some("group1"));
+        log(Some(task2),Some("group2"));
+        log(Some(task1),Some("nested1"));
+        log(Some(task1),None);
+        log(Some(task2),None);
+        log(None,Some("unscoped"));
+        log(Some(task1),None);
+        let expected = vec!["group2","end","unscoped","group1","nested1","end","end"];
+        assert_eq!(buffer.next.messages,expected);
+    }
+}
@@ -1,11 +1,14 @@
 //! JavaScript console consumer implementation.
 
-use web_sys::console;
+use crate::prelude::*;
 
 use crate::entry::Entry;
 use crate::entry;
 use crate::processor::consumer;
+use crate::entry::level::DefaultLevels;
+
 use wasm_bindgen::prelude::*;
+use web_sys::console;
 
 
 
@@ -29,21 +32,114 @@ mod js {
 
 
 
+// ==============
+// === Method ===
+// ==============
+
+/// JavaScript console method used to print an entry. Using a method matching the entry level
+/// allows the browser-level filtering and error counters to work with the logged entries.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[allow(missing_docs)]
+pub enum Method { Log,Debug,Info,Warn,Error }
+
+impl Method {
+    /// Write the message using this console method.
+        pub fn write(self, message:&js_sys::Array) {
+        match self {
+            Self::Log   => console::log(message),
+            Self::Debug => console::debug(message),
+            Self::Info  => console::info(message),
+            Self::Warn  => console::warn(message),
+            Self::Error => console::error(message),
+        }
+    }
+
+    /// Write the plain text message using this console method.
+    pub fn write_str(self, message:&str) {
+        let message = JsValue::from_str(message);
+        match self {
+            Self::Log   => console::log_1(&message),
+            Self::Debug => console::debug_1(&message),
+            Self::Info  => console::info_1(&message),
+            Self::Warn  => console::warn_1(&message),
+            Self::Error => console::error_1(&message),
+        }
+    }
+}
+
+
+
+// ===============
+// === Mapping ===
+// ===============
+
+/// Type-level definition of the console method used for the given level. Consumers are
+/// constructed with `Default`, so the mapping is provided as a type parameter of `JsConsole`.
+pub trait Mapping<Levels> {
+    /// Console method used for the given level.
+    fn method(level:&Levels) -> Method;
+}
+
+/// Default mapping. Uses `console.debug` for trace and debug levels, `console.info`,
+/// `console.warn`, and `console.error` for the other built-in levels, and `console.log` for any
+/// custom levels.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct ByLevel;
+
+impl<Levels> Mapping<Levels> for ByLevel {
+    default fn method(_level:&Levels) -> Method {
+        Method::Log
+    }
+}
+
+impl Mapping<DefaultLevels> for ByLevel {
+    fn method(level:&DefaultLevels) -> Method {
+        match level {
+            DefaultLevels::Trace   => Method::Debug,
+            DefaultLevels::Debug   => Method::Debug,
+            DefaultLevels::Info    => Method::Info,
+            DefaultLevels::Warning => Method::Warn,
+            DefaultLevels::Error   => Method::Error,
+        }
+    }
+}
+
+/// Mapping which uses `console.log` for all levels.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct LogOnly;
+
+impl<Levels> Mapping<Levels> for LogOnly {
+    fn method(_level:&Levels) -> Method {
+        Method::Log
+    }
+}
+
+
+
 // ==========================
 // === JsConsole Consumer ===
 // ==========================
 
 /// A simple consumer which uses JavaScript console API to print hierarchical logs in a browser.
-#[derive(Clone,Copy,Debug,Default)]
-pub struct JsConsole;
+/// The console method used for each entry is chosen by the `Mapping` type parameter. Tables are
+/// printed with `console.table`, below their formatted title.
+///
+/// The consumer accepts plain text messages too, like the ones of the `Plain` formatter. They are
+/// printed without groups, so group titles are printed like messages, and tables are printed as
+/// aligned text.
+#[derive(Derivative)]
+#[derivative(Clone(bound=""),Copy(bound=""),Debug(bound=""),Default(bound=""))]
+pub struct JsConsole<M=ByLevel> {
+    mapping : PhantomData<M>,
+}
 
-impl<Levels> consumer::Definition<Levels,js_sys::Array> for JsConsole
-where Levels:Writer {
+impl<Levels,M> consumer::Definition<Levels,js_sys::Array> for JsConsole<M>
+where M:Mapping<Levels> {
     fn consume(&mut self, event:Entry<Levels>, message:Option<js_sys::Array>) {
         match &event.content {
             entry::Content::Message(_) => {
                 if let Some(msg) = message {
-                    event.level.write_by_level(&msg)
+                    M::method(&event.level).write(&msg)
                 }
             },
             entry::Content::GroupBegin(group) => {
@@ -52,35 +148,99 @@ where Levels:Writer {
                     else               { console::group(&msg) }
                 }
             },
-            entry::Content::GroupEnd => {
+            entry::Content::GroupEnd(_) => {
                 js::console_group_end()
+            },
+            entry::Content::Table(table) => {
+                if let Some(msg) = message {
+                    M::method(&event.level).write(&msg);
+                    let columns : js_sys::Array = table.columns.iter().map(JsValue::from).collect();
+                    console::table_2(&table_data(table),&columns)
+                }
             }
         }
     }
 }
 
-/// Trait that is used to determine how the JS logging is dispatched for different log levels.
-/// Default blanket implementation uses `console.log`.
+impl<Levels,M> consumer::Definition<Levels,String> for JsConsole<M>
+where M:Mapping<Levels> {
+    fn consume(&mut self, event:Entry<Levels>, message:Option<String>) {
+        let method = M::method(&event.level);
+        if let Some(msg) = message {
+            method.write_str(&msg)
+        }
+        if let entry::Content::Table(table) = &event.content {
+            method.write_str(&table.lines().join("\n"))
+        }
+    }
+}
+
+/// The rows of the table as an array of objects, indexed by the column names, as expected by
+/// `console.table`.
+fn table_data(table:&entry::Table) -> js_sys::Array {
+    table.rows.iter().map(|row| {
+        let object = js_sys::Object::new();
+        for (column,cell) in table.columns.iter().zip(row) {
+            let column = JsValue::from(column);
+            let cell   = JsValue::from(cell);
+            js_sys::Reflect::set(&object,&column,&cell).ok();
+        }
+        JsValue::from(object)
+    }).collect()
+}
+
+
+
+// ==============
+// === Writer ===
+// ==============
+
+/// Legacy way of choosing the console method per level. The `JsConsole` consumer no longer
+/// consults this trait, so custom implementations have no effect. Implement `Mapping` and use
+/// `JsConsole<YourMapping>` instead.
+#[deprecated(note="Implement `Mapping` and parametrize `JsConsole` with it instead.")]
 pub trait Writer {
     /// Write message using the appropriate console method.
     fn write_by_level(&self, message:&js_sys::Array);
 }
 
+#[allow(deprecated)]
 impl<T> Writer for T {
     default fn write_by_level(&self, message:&js_sys::Array) {
-        console::log(message)
+        <ByLevel as Mapping<T>>::method(self).write(message)
     }
 }
 
-impl Writer for crate::entry::level::DefaultLevels {
-    fn write_by_level(&self, message:&js_sys::Array) {
-        use crate::entry::level::DefaultLevels::*;
-        match *self {
-            Trace   => console::trace(message),
-            Debug   => console::debug(message),
-            Info    => console::info(message),
-            Warning => console::warn(message),
-            Error   => console::error(message),
-        }
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone,Copy,Debug)]
+    struct CustomLevel;
+
+    #[test]
+    fn by_level_mapping() {
+        let method = <ByLevel as Mapping<DefaultLevels>>::method;
+        assert_eq!(method(&DefaultLevels::Trace)   , Method::Debug);
+        assert_eq!(method(&DefaultLevels::Debug)   , Method::Debug);
+        assert_eq!(method(&DefaultLevels::Info)    , Method::Info);
+        assert_eq!(method(&DefaultLevels::Warning) , Method::Warn);
+        assert_eq!(method(&DefaultLevels::Error)   , Method::Error);
+        assert_eq!(<ByLevel as Mapping<CustomLevel>>::method(&CustomLevel), Method::Log);
+    }
+
+    #[test]
+    fn log_only_mapping() {
+        let method = <LogOnly as Mapping<DefaultLevels>>::method;
+        assert_eq!(method(&DefaultLevels::Trace)   , Method::Log);
+        assert_eq!(method(&DefaultLevels::Info)    , Method::Log);
+        assert_eq!(method(&DefaultLevels::Error)   , Method::Log);
+        assert_eq!(<LogOnly as Mapping<CustomLevel>>::method(&CustomLevel), Method::Log);
     }
 }
@@ -10,7 +10,8 @@ use crate::processor::consumer;
 // === Native Console Consumer ===
 // ===============================
 
-/// A simple consumer which uses `println!` to simulate hierarchical logging.
+/// A simple consumer which uses `println!` to simulate hierarchical logging. Tables are printed
+/// below their formatted title, as aligned text.
 #[derive(Clone,Copy,Debug,Default)]
 pub struct NativeConsole {
     depth           : usize,
@@ -48,12 +49,20 @@ impl<Levels> consumer::Definition<Levels,String> for NativeConsole {
                     self.depth += 1
                 }
             },
-            entry::Content::GroupEnd => {
+            entry::Content::GroupEnd(_) => {
                 if self.collapsed_depth > 0 {
                     self.collapsed_depth -= 1
                 } else {
                     self.depth -= 1
                 }
+            },
+            entry::Content::Table(table) => {
+                if let Some(msg) = message {
+                    self.print(msg);
+                    for line in table.lines() {
+                        self.print(line);
+                    }
+                }
             }
         }
     }
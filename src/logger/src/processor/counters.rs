@@ -0,0 +1,208 @@
+//! Counting of warnings and errors per logger path. The counts are printed as a summary at the end
+//! of a session, so problems are visible without reading all the logs, for example in headless CI
+//! runs.
+
+use crate::prelude::*;
+
+use crate::entry::Entry;
+use crate::entry::level;
+use crate::path::Path;
+use crate::processor::Processor;
+
+
+
+// ==================
+// === PathCounts ===
+// ==================
+
+/// Counts of problems reported by a single logger path.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct PathCounts {
+    /// Number of warnings.
+    pub warnings : usize,
+    /// Number of errors.
+    pub errors   : usize,
+}
+
+
+
+// ===============
+// === Summary ===
+// ===============
+
+/// Snapshot of the counts of all logger paths which reported any problems, ordered by path.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct Summary {
+    /// Counts of every path which reported at least one warning or error.
+    pub paths : BTreeMap<String,PathCounts>,
+}
+
+impl Summary {
+    /// Sum of the counts of all paths.
+    pub fn total(&self) -> PathCounts {
+        self.paths.values().fold(default(),|mut total,counts| {
+            total.warnings += counts.warnings;
+            total.errors   += counts.errors;
+            total
+        })
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+        writeln!(f,"Logging summary: {} error(s), {} warning(s).",total.errors,total.warnings)?;
+        if !self.paths.is_empty() {
+            writeln!(f,"{:<40} {:>8} {:>8}","path","errors","warnings")?;
+            for (path,counts) in &self.paths {
+                writeln!(f,"{:<40} {:>8} {:>8}",path,counts.errors,counts.warnings)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+
+// ==============
+// === Counts ===
+// ==============
+
+thread_local! {
+    static COUNTS : RefCell<HashMap<Path,PathCounts>> = default();
+}
+
+/// Snapshot of the counts gathered by all `Counters` processors in the current thread.
+pub fn summary() -> Summary {
+    COUNTS.with(|counts| summary_of(&counts.borrow()))
+}
+
+/// Like `summary`, but returns `None` instead of panicking when the counts are already destroyed,
+/// which happens if the thread panics during its shutdown, or are being modified. It does not
+/// access any other thread-local, as paths are displayed without the symbol table.
+fn try_summary() -> Option<Summary> {
+    COUNTS.try_with(|counts| counts.try_borrow().ok().map(|counts| summary_of(&counts))).ok()?
+}
+
+fn summary_of(counts:&HashMap<Path,PathCounts>) -> Summary {
+    let paths = counts.iter().map(|(path,t)| (path.to_string(),*t)).collect();
+    Summary {paths}
+}
+
+/// Clear the gathered counts.
+pub fn reset() {
+    COUNTS.with(|counts| counts.borrow_mut().clear())
+}
+
+/// Print the summary of the gathered counts to the console and return it.
+pub fn report_summary() -> Summary {
+    let summary = summary();
+    print(&summary.to_string());
+    summary
+}
+
+/// Report the summary when the current thread panics, after running the previously set panic
+/// hook. The counts are kept per thread, so panics of other threads report their own counts.
+pub fn report_summary_on_panic() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        // Panicking in the hook would abort the process, so the summary is skipped if the counts
+        // are not accessible.
+        if let Some(summary) = try_summary() {
+            print(&summary.to_string());
+        }
+    }))
+}
+
+#[cfg(target_arch="wasm32")]
+fn print(text:&str) {
+    web_sys::console::log_1(&text.into())
+}
+
+#[cfg(not(target_arch="wasm32"))]
+fn print(text:&str) {
+    eprint!("{}",text)
+}
+
+
+
+// ================
+// === Counters ===
+// ================
+
+/// A processor counting the warnings and errors per logger path, and passing all the entries to
+/// the next processor unchanged. The counts can be accessed with the `summary` function, or
+/// printed with `report_summary`.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Counters<Next> {
+    next : Next,
+}
+
+impl<Levels,Next> Processor<Entry<Levels>> for Counters<Next>
+where Next   : Processor<Entry<Levels>>,
+      Levels : PartialEq + From<level::Warning> + From<level::Error> {
+    type Output = Next::Output;
+    fn submit(&mut self, entry:Entry<Levels>) -> Self::Output {
+        let is_warning = entry.level == level::Warning.into();
+        let is_error   = entry.level == level::Error.into();
+        if is_warning || is_error {
+            COUNTS.with(|counts| {
+                let mut counts  = counts.borrow_mut();
+                let path_counts = counts.entry(entry.path.clone()).or_default();
+                if is_warning { path_counts.warnings += 1 }
+                if is_error   { path_counts.errors   += 1 }
+            })
+        }
+        self.next.submit(entry)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::DefaultLevels;
+    use crate::processor::Drop;
+
+    #[test]
+    fn warnings_and_errors_are_counted() {
+        reset();
+        let mut counters = Counters::<Drop>::default();
+        let mut submit   = |level:DefaultLevels, path:&str| {
+            counters.submit(Entry::<DefaultLevels>::message(level,path.into(),"msg"))
+        };
+        submit(DefaultLevels::Error,"app.gui");
+        submit(DefaultLevels::Warning,"app.gui");
+        submit(DefaultLevels::Warning,"app.gui");
+        submit(DefaultLevels::Error,"app.io");
+        submit(DefaultLevels::Info,"app.net");
+
+        let summary = summary();
+        let total   = PathCounts {warnings:2,errors:2};
+        assert_eq!(summary.paths.len(),2);
+        assert_eq!(summary.paths["app.gui"],PathCounts {warnings:2,errors:1});
+        assert_eq!(summary.paths["app.io"],PathCounts {warnings:0,errors:1});
+        assert_eq!(summary.total(),total);
+        let text = summary.to_string();
+        assert!(text.starts_with("Logging summary: 2 error(s), 2 warning(s).\n"));
+        assert_eq!(text.lines().count(),4);
+    }
+
+    #[test]
+    fn summary_is_skipped_while_counts_are_modified() {
+        reset();
+        let mut counters = Counters::<Drop>::default();
+        counters.submit(Entry::<DefaultLevels>::message(DefaultLevels::Error,"app".into(),"msg"));
+        assert_eq!(try_summary(),Some(summary()));
+        COUNTS.with(|counts| {
+            let _borrow = counts.borrow_mut();
+            assert_eq!(try_summary(),None);
+        });
+    }
+}
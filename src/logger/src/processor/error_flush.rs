@@ -0,0 +1,182 @@
+//! Policies of flushing the `Buffer` processor when an error is logged. The buffer keeps the
+//! entries hidden until the user asks for them, so the context of an error would not be visible
+//! in the console when the error happens. These policies choose which of the buffered entries are
+//! passed to the subsequent processor right before the error. The other buffered entries are
+//! dropped, as passing them later would put them after the newer entries.
+
+use crate::prelude::*;
+
+use crate::entry::Content;
+use crate::entry::Entry;
+use crate::entry::level;
+
+
+
+// ==================
+// === ErrorFlush ===
+// ==================
+
+/// A policy of flushing the buffered inputs when an error is submitted. Policies are chosen on
+/// the type level, like processors.
+pub trait ErrorFlush<Input> {
+    /// Check whether the input should trigger the flush.
+    fn is_trigger(input:&Input) -> bool;
+
+    /// Choose the buffered inputs to be flushed, keeping their order. They are passed to the
+    /// subsequent processor before the trigger, while the rest is dropped.
+    fn take(buffer:Vec<Input>, trigger:&Input) -> Vec<Input>;
+}
+
+fn is_error<Levels>(entry:&Entry<Levels>) -> bool
+where Levels:PartialEq+From<level::Error> {
+    entry.level == level::Error.into()
+}
+
+/// The entries chosen by the predicate, extended with whole groups, so the groups opened and
+/// closed in the console stay balanced. If any entry of a group begin and end pair is chosen, both
+/// are. The entries beginning groups which are still open, and the entries ending groups which
+/// began before the buffered ones, are always chosen.
+fn take_with_groups<Levels>
+(buffer:Vec<Entry<Levels>>, mut chosen:impl FnMut(usize,&Entry<Levels>)->bool)
+-> Vec<Entry<Levels>> {
+    let mut taken = buffer.iter().enumerate().map(|(index,t)| chosen(index,t)).collect_vec();
+    let mut open  = Vec::new();
+    for (index,entry) in buffer.iter().enumerate() {
+        match entry.content {
+            Content::GroupBegin(_) => open.push(index),
+            Content::GroupEnd(_)   => match open.pop() {
+                Some(begin) => {
+                    let pair = taken[begin] || taken[index];
+                    taken[begin] = pair;
+                    taken[index] = pair;
+                }
+                None => taken[index] = true,
+            },
+            Content::Message(_) | Content::Table(_) => {}
+        }
+    }
+    for begin in open { taken[begin] = true }
+    buffer.into_iter().zip(taken).filter_map(|(entry,taken)| taken.as_some(entry)).collect()
+}
+
+
+
+// ================
+// === Policies ===
+// ================
+
+/// Errors are buffered like all the other inputs. Works with inputs of any type.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct NoFlush;
+
+impl<Input> ErrorFlush<Input> for NoFlush {
+    fn is_trigger(_input:&Input) -> bool {
+        false
+    }
+
+    fn take(_buffer:Vec<Input>, _trigger:&Input) -> Vec<Input> {
+        default()
+    }
+}
+
+/// An error flushes the whole buffer.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct FlushAll;
+
+impl<Levels> ErrorFlush<Entry<Levels>> for FlushAll
+where Levels:PartialEq+From<level::Error> {
+    fn is_trigger(entry:&Entry<Levels>) -> bool {
+        is_error(entry)
+    }
+
+    fn take(buffer:Vec<Entry<Levels>>, _trigger:&Entry<Levels>) -> Vec<Entry<Levels>> {
+        buffer
+    }
+}
+
+/// An error flushes the `N` most recently buffered entries, and the entries needed to keep their
+/// groups whole.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct FlushLast<const N:usize>;
+
+impl<Levels,const N:usize> ErrorFlush<Entry<Levels>> for FlushLast<N>
+where Levels:PartialEq+From<level::Error> {
+    fn is_trigger(entry:&Entry<Levels>) -> bool {
+        is_error(entry)
+    }
+
+    fn take(buffer:Vec<Entry<Levels>>, _trigger:&Entry<Levels>) -> Vec<Entry<Levels>> {
+        let first = buffer.len().saturating_sub(N);
+        take_with_groups(buffer,|index,_| index >= first)
+    }
+}
+
+/// An error flushes the buffered entries logged by the same logger path, and the entries needed to
+/// keep their groups whole.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct FlushSamePath;
+
+impl<Levels> ErrorFlush<Entry<Levels>> for FlushSamePath
+where Levels:PartialEq+From<level::Error> {
+    fn is_trigger(entry:&Entry<Levels>) -> bool {
+        is_error(entry)
+    }
+
+    fn take(buffer:Vec<Entry<Levels>>, trigger:&Entry<Levels>) -> Vec<Entry<Levels>> {
+        take_with_groups(buffer,|_,entry| entry.path == trigger.path)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::DefaultLevels;
+
+    fn entries() -> Vec<Entry<DefaultLevels>> {
+        let entry = |path:&str, message:&str| Entry::message(level::Info,path.into(),message);
+        vec![entry("app.ui","1"),entry("app.io","2"),entry("app.ui","3")]
+    }
+
+    fn take<F:ErrorFlush<Entry<DefaultLevels>>>(buffer:Vec<Entry<DefaultLevels>>) -> Vec<String> {
+        let trigger = Entry::message(level::Error,"app.ui".into(),"error");
+        assert!(F::is_trigger(&trigger));
+        assert!(!F::is_trigger(&buffer[0]));
+        let message = |entry:&Entry<DefaultLevels>| entry.content.message().unwrap_or("end").into();
+        F::take(buffer,&trigger).iter().map(message).collect()
+    }
+
+    #[test]
+    fn policies_choose_flushed_entries() {
+        assert_eq!(take::<FlushAll>(entries()),vec!["1","2","3"]);
+        assert_eq!(take::<FlushLast<2>>(entries()),vec!["2","3"]);
+        assert_eq!(take::<FlushLast<5>>(entries()),vec!["1","2","3"]);
+        assert_eq!(take::<FlushSamePath>(entries()),vec!["1","3"]);
+        let error = Entry::<DefaultLevels>::message(level::Error,"app".into(),"error");
+        assert!(!<NoFlush as ErrorFlush<_>>::is_trigger(&error));
+    }
+
+    #[test]
+    fn groups_split_by_the_flush_are_flushed_whole() {
+        let begin  = |message:&str| Entry::group_begin(level::Info,"app.io".into(),message,false);
+        let end    = || Entry::group_end(level::Info,"app.io".into());
+        let buffer = || {
+            let mut buffer = entries();
+            buffer.insert(1,begin("closed"));
+            buffer.insert(3,end());
+            buffer.insert(4,begin("open"));
+            buffer
+        };
+        assert_eq!(take::<FlushLast<3>>(buffer()),vec!["closed","end","open","3"]);
+        assert_eq!(take::<FlushLast<1>>(buffer()),vec!["open","3"]);
+        assert_eq!(take::<FlushSamePath>(buffer()),vec!["1","open","3"]);
+        let mut unmatched = buffer();
+        unmatched.insert(1,end());
+        assert_eq!(take::<FlushLast<1>>(unmatched),vec!["end","open","3"]);
+    }
+}
@@ -1,10 +1,17 @@
 //! Log formatter implementation.
 
+pub mod compact;
+pub mod decorator;
 pub mod js_console;
 pub mod native_console;
+pub mod plain;
 
+pub use compact::Compact;
+pub use decorator::Decorated;
+pub use decorator::Decorator;
 pub use js_console::JsConsole;
 pub use native_console::NativeConsole;
+pub use plain::Plain;
 
 use crate::entry::Entry;
 use crate::entry::GenericEntry;
@@ -37,13 +44,30 @@ pub trait Output {
 /// display a visual indicator when  a group is closed, while others will use API for that.
 ///
 /// ## WARNING
-/// This trait should be implemented automatically. See the macro `define_levels_group` to learn
-/// more.
+/// This trait should not be implemented manually. It is implemented for all the level groups
+/// implementing `GroupDefinition`.
 #[allow(missing_docs)]
 pub trait GenericDefinition<Level> : Output {
     fn generic_format(entry:&Entry<Level>) -> Option<Self::Output>;
 }
 
+impl<Fmt:Output,Level:GroupDefinition<Fmt>> GenericDefinition<Level> for Fmt {
+    fn generic_format(entry:&Entry<Level>) -> Option<Self::Output> {
+        Level::group_format(entry)
+    }
+}
+
+/// Formatting of a group of levels with the `Fmt` formatter. It is implemented by the level group
+/// instead of the formatter, so groups defined in other crates can provide it as well.
+///
+/// ## WARNING
+/// This trait should be implemented automatically. See the macro `define_levels_group` to learn
+/// more.
+#[allow(missing_docs)]
+pub trait GroupDefinition<Fmt:Output> : Sized {
+    fn group_format(entry:&Entry<Self>) -> Option<Fmt::Output>;
+}
+
 /// A formatter narrowed to a specific type. While `Definition` can be parametrized with a generic
 /// type, like `AllPossibleLevels`, this trait is parametrized with a specific level only, like
 /// `level::Error`. Read docs of `Definition` to learn more.
@@ -0,0 +1,350 @@
+//! Compact binary formatter implementation. It is meant to be used by consumers sending the logs
+//! to another process or thread, where emitting a JSON document per entry is too costly.
+//!
+//! ## Wire format
+//! Every entry is encoded as a standalone frame. All integers are encoded as unsigned LEB128
+//! varints, and all strings as a varint byte length followed by UTF-8 bytes.
+//!
+//! ```text
+//! frame   := level:u8 kind:u8 path time content
+//! path    := id:varint [name:string] // The name is present only if `id` is a new id.
+//! time    := delta:varint             // Microseconds since the previous frame of the stream.
+//! content := message:string           // For all kinds except `GROUP_END`. Tables are rendered
+//!                                     // as text.
+//!          |                          // For `GROUP_END`.
+//! ```
+//!
+//! Paths are interned per stream. Path ids are assigned consecutively, starting from zero, so the
+//! decoder knows that an id equal to the number of paths it has seen so far introduces a new path.
+//! Use `Compact::reset` and `Decoder::reset` to start a new stream, for example after reconnecting
+//! to the remote side. The encoder state is thread-local, as formatters are stateless.
+//!
+//! The timestamps are taken from the `time` of the entries, so the formatter should be preceded by
+//! a `Timestamper`. Its clock decides the deltas, so for example a `Timestamper` with a
+//! `ManualClock` makes the output fully deterministic, as needed by golden-file tests. Entries
+//! without a time are stamped with the system time when they are formatted. All the entries of a
+//! stream should be timestamped by the same clock, as times of different clocks cannot be
+//! compared.
+
+use crate::prelude::*;
+
+use crate::clock;
+use crate::entry::level;
+use crate::entry::Content;
+use crate::entry::GenericEntry;
+use crate::path::Path;
+use crate::processor::formatter;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Kind byte of a message entry.
+pub const MESSAGE : u8 = 0;
+/// Kind byte of a group begin entry.
+pub const GROUP_BEGIN : u8 = 1;
+/// Kind byte of a collapsed group begin entry.
+pub const GROUP_BEGIN_COLLAPSED : u8 = 2;
+/// Kind byte of a group end entry.
+pub const GROUP_END : u8 = 3;
+/// Kind byte of a table entry. The message of the entry is the table rendered as text.
+pub const TABLE : u8 = 4;
+
+/// Level byte used for levels which do not implement `LevelCode`.
+pub const CUSTOM_LEVEL : u8 = u8::MAX;
+
+
+
+// =================
+// === LevelCode ===
+// =================
+
+/// Numeric representation of a verbosity level used in the wire format. Levels that do not
+/// provide their own implementation are encoded as `CUSTOM_LEVEL`.
+#[allow(missing_docs)]
+pub trait LevelCode {
+    fn code() -> u8;
+}
+
+impl<T> LevelCode for T {
+    default fn code() -> u8 { CUSTOM_LEVEL }
+}
+
+impl LevelCode for level::Trace   { fn code() -> u8 { 0 } }
+impl LevelCode for level::Debug   { fn code() -> u8 { 1 } }
+impl LevelCode for level::Info    { fn code() -> u8 { 2 } }
+impl LevelCode for level::Warning { fn code() -> u8 { 3 } }
+impl LevelCode for level::Error   { fn code() -> u8 { 4 } }
+
+
+
+// ===============
+// === Encoder ===
+// ===============
+
+/// State of the stream encoder.
+#[derive(Debug,Default)]
+struct EncoderState {
+    paths     : HashMap<Path,u32>,
+    last_time : Option<u64>,
+}
+
+thread_local! {
+    static ENCODER : RefCell<EncoderState> = default();
+}
+
+impl EncoderState {
+    fn encode(&mut self, level:u8, entry:&GenericEntry, time:u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        let kind    = match &entry.content {
+            Content::Message(_)                           => MESSAGE,
+            Content::GroupBegin(group) if group.collapsed => GROUP_BEGIN_COLLAPSED,
+            Content::GroupBegin(_)                        => GROUP_BEGIN,
+            Content::GroupEnd(_)                          => GROUP_END,
+            Content::Table(_)                             => TABLE,
+        };
+        out.push(level);
+        out.push(kind);
+        match self.paths.get(&entry.path) {
+            Some(id) => write_varint(&mut out,*id as u64),
+            None     => {
+                let id = self.paths.len() as u32;
+                self.paths.insert(entry.path.clone_ref(),id);
+                write_varint(&mut out,id as u64);
+                write_string(&mut out,&entry.path.to_string());
+            }
+        }
+        let delta = self.last_time.map(|last| time.saturating_sub(last)).unwrap_or_default();
+        self.last_time = Some(time);
+        write_varint(&mut out,delta);
+        match &entry.content {
+            Content::Table(table) => write_string(&mut out,&table.to_string()),
+            content               => if let Some(message) = content.message() {
+                write_string(&mut out,message);
+            }
+        }
+        out
+    }
+}
+
+fn write_varint(out:&mut Vec<u8>, mut value:u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_string(out:&mut Vec<u8>, value:&str) {
+    write_varint(out,value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+
+
+// ===============
+// === Compact ===
+// ===============
+
+/// A formatter producing compact binary frames. See the module docs to learn about the format.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Compact;
+
+impl formatter::Output for Compact {
+    type Output = Vec<u8>;
+}
+
+impl Compact {
+    /// Start a new stream in the current thread. The path interning table and the timestamp base
+    /// are cleared, so the following frames can be decoded by a fresh `Decoder`.
+    pub fn reset() {
+        ENCODER.with(|encoder| *encoder.borrow_mut() = default());
+    }
+}
+
+impl<Level> formatter::Definition<Level> for Compact {
+    fn format(entry:&GenericEntry) -> Option<Self::Output> {
+        let level = <Level as LevelCode>::code();
+        let time  = entry.time.unwrap_or_else(clock::now);
+        Some(ENCODER.with(|encoder| encoder.borrow_mut().encode(level,entry,time)))
+    }
+}
+
+
+
+// ===============
+// === Decoder ===
+// ===============
+
+/// Entry decoded from a compact frame.
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[allow(missing_docs)]
+pub struct DecodedEntry {
+    pub level   : u8,
+    pub kind    : u8,
+    pub path    : ImString,
+    /// Microseconds since the previous frame of the stream.
+    pub delta   : u64,
+    pub message : Option<String>,
+}
+
+/// Stream decoder, the receiving counterpart of the `Compact` formatter. Frames have to be
+/// decoded in the order they were produced.
+#[derive(Clone,Debug,Default)]
+pub struct Decoder {
+    paths : Vec<ImString>,
+}
+
+impl Decoder {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Forget all interned paths. See `Compact::reset` to learn more.
+    pub fn reset(&mut self) {
+        self.paths.clear()
+    }
+
+    /// Decode a single frame. Returns `None` if the frame is malformed. Malformed frames do not
+    /// change the state of the decoder, so the following frames can still be decoded.
+    pub fn decode(&mut self, frame:&[u8]) -> Option<DecodedEntry> {
+        let mut input = frame;
+        let level     = read_u8(&mut input)?;
+        let kind      = read_u8(&mut input)?;
+        let id        = read_varint(&mut input)?;
+        let is_new    = id == self.paths.len() as u64;
+        let path      = match is_new {
+            true  => ImString::new(read_string(&mut input)?),
+            false => self.paths.get(usize::try_from(id).ok()?)?.clone(),
+        };
+        let delta   = read_varint(&mut input)?;
+        let message = match kind {
+            MESSAGE | GROUP_BEGIN | GROUP_BEGIN_COLLAPSED | TABLE => Some(read_string(&mut input)?),
+            GROUP_END                                             => None,
+            _                                                     => return None,
+        };
+        if !input.is_empty() { return None }
+        if is_new { self.paths.push(path.clone()) }
+        Some(DecodedEntry{level,kind,path,delta,message})
+    }
+}
+
+fn read_u8(input:&mut &[u8]) -> Option<u8> {
+    let (byte,rest) = input.split_first()?;
+    *input = rest;
+    Some(*byte)
+}
+
+/// Read a varint. Returns `None` if it is truncated or does not fit in `u64`.
+fn read_varint(input:&mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(input)?;
+        let bits = (byte & 0x7f) as u64;
+        if bits > u64::MAX >> shift { return None }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some(value)
+        }
+    }
+    None
+}
+
+fn read_string(input:&mut &[u8]) -> Option<String> {
+    let len = read_varint(input)? as usize;
+    if len > input.len() {
+        return None
+    }
+    let (bytes,rest) = input.split_at(len);
+    *input = rest;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<Level>(entry:&GenericEntry) -> Vec<u8> {
+        formatter::format::<Compact,Level>(entry).unwrap()
+    }
+
+    fn at(mut entry:GenericEntry, time:u64) -> GenericEntry {
+        entry.time = Some(time);
+        entry
+    }
+
+    #[test]
+    fn varint_round_trip() {
+        for value in &[0,1,127,128,300,u32::MAX as u64,u64::MAX] {
+            let mut out = Vec::new();
+            write_varint(&mut out,*value);
+            assert_eq!(read_varint(&mut out.as_slice()),Some(*value));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        Compact::reset();
+        let mut decoder = Decoder::new();
+        let path        = Path::new("app.scene");
+        let entry1      = at(GenericEntry::message(path.clone(),"hello"),1000);
+        let entry2      = at(GenericEntry::group_begin(path.clone(),"group",true),1250);
+        let entry3      = at(GenericEntry::group_end(path.clone()),1250);
+        let frame1      = encode::<level::Warning>(&entry1);
+        let frame2      = encode::<level::Trace>(&entry2);
+        let frame3      = encode::<()>(&entry3);
+        assert!(frame2.len() < frame1.len(), "Path should be interned after the first frame.");
+
+        let decoded1 = decoder.decode(&frame1).unwrap();
+        let decoded2 = decoder.decode(&frame2).unwrap();
+        let decoded3 = decoder.decode(&frame3).unwrap();
+        assert_eq!(decoded1.level   , 3);
+        assert_eq!(decoded1.kind    , MESSAGE);
+        assert_eq!(decoded1.path    , path.to_string());
+        assert_eq!(decoded1.delta   , 0);
+        assert_eq!(decoded1.message , Some("hello".into()));
+        assert_eq!(decoded2.level   , 0);
+        assert_eq!(decoded2.kind    , GROUP_BEGIN_COLLAPSED);
+        assert_eq!(decoded2.delta   , 250);
+        assert_eq!(decoded2.message , Some("group".into()));
+        assert_eq!(decoded3.level   , CUSTOM_LEVEL);
+        assert_eq!(decoded3.kind    , GROUP_END);
+        assert_eq!(decoded3.message , None);
+        let expected = [3,MESSAGE,0,9,b'a',b'p',b'p',b'.',b's',b'c',b'e',b'n',b'e',0,5,b'h',b'e'];
+        assert_eq!(frame1[..17],expected);
+        assert_eq!(frame3,[CUSTOM_LEVEL,GROUP_END,0,0]);
+    }
+
+    #[test]
+    fn malformed_frames() {
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.decode(&[]),None);
+        assert_eq!(decoder.decode(&[0,MESSAGE,5]),None);
+        assert_eq!(decoder.decode(&[0,42,0,1,b'a',0]),None);
+        let mut overflow = vec![0xff;9];
+        overflow.push(0x02);
+        assert_eq!(read_varint(&mut overflow.as_slice()),None);
+    }
+
+    #[test]
+    fn malformed_frames_do_not_intern_paths() {
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.decode(&[0,MESSAGE,0,1,b'a',0]),None);
+        let entry = decoder.decode(&[0,MESSAGE,0,1,b'b',0,1,b'x']).unwrap();
+        assert_eq!(entry.path,"b");
+        assert_eq!(decoder.decode(&[0,GROUP_END,0,0]).unwrap().path,"b");
+    }
+}
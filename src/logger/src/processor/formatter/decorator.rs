@@ -0,0 +1,130 @@
+//! Decorator stages, adding text to the output of other formatters. Decorators are composed by
+//! nesting [`Decorated`] formatters, so a prefix can be added to the output of any formatter
+//! without reimplementing it. For example, `Decorated<Timestamp,Decorated<Scope,JsConsole>>`
+//! prefixes the styled console output with the time and the task scope of the entry.
+
+use crate::prelude::*;
+
+use crate::clock;
+use crate::entry::GenericEntry;
+use crate::processor::formatter;
+
+
+
+// ===================
+// === Decoratable ===
+// ===================
+
+/// Output of a formatter which can be decorated with text.
+pub trait Decoratable {
+    /// Add the text at the beginning of the output.
+    fn prepend(&mut self, text:&str);
+}
+
+impl Decoratable for String {
+    fn prepend(&mut self, text:&str) {
+        self.insert_str(0,text)
+    }
+}
+
+
+
+// =================
+// === Decorator ===
+// =================
+
+/// A decorator stage. Decorators are stateless, like formatters.
+pub trait Decorator {
+    /// The text to add to the formatted entry, or `None` if the entry should not be decorated.
+    fn decoration(entry:&GenericEntry) -> Option<String>;
+}
+
+
+// === Decorated ===
+
+/// A formatter prefixing the output of the `Fmt` formatter with the decoration of `D`. Entries not
+/// formatted by `Fmt` are not decorated either.
+#[derive(Derivative)]
+#[derivative(Clone(bound=""),Copy(bound=""),Debug(bound=""),Default(bound=""))]
+pub struct Decorated<D,Fmt> {
+    phantom : PhantomData<(D,Fmt)>,
+}
+
+impl<D,Fmt:formatter::Output> formatter::Output for Decorated<D,Fmt> {
+    type Output = Fmt::Output;
+}
+
+impl<D,Fmt,Level> formatter::Definition<Level> for Decorated<D,Fmt>
+where D   : Decorator,
+      Fmt : formatter::Definition<Level>,
+      Fmt::Output : Decoratable {
+    fn format(entry:&GenericEntry) -> Option<Self::Output> {
+        let mut output = formatter::format::<Fmt,Level>(entry)?;
+        if let Some(decoration) = D::decoration(entry) {
+            output.prepend(&decoration);
+        }
+        Some(output)
+    }
+}
+
+
+
+// ==================
+// === Decorators ===
+// ==================
+
+/// Prefixes entries with their time, in seconds with millisecond precision, like `[12.345] `. Only
+/// differences between the times are meaningful. The time is set by the `Timestamper` processor,
+/// and entries without it are stamped with the system time when they are formatted.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Timestamp;
+
+impl Decorator for Timestamp {
+    fn decoration(entry:&GenericEntry) -> Option<String> {
+        let time = entry.time.unwrap_or_else(clock::now);
+        Some(format!("[{}.{:03}] ",time / 1_000_000,time / 1000 % 1000))
+    }
+}
+
+/// Prefixes entries logged in a task scope with the id of the scope, like `[task 3] `.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Scope;
+
+impl Decorator for Scope {
+    fn decoration(entry:&GenericEntry) -> Option<String> {
+        entry.scope.map(|scope| format!("[task {}] ",scope.id()))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::entry::level;
+    use crate::entry::scope::TaskScope;
+    use crate::processor::formatter::Plain;
+
+    type Formatter = Decorated<Timestamp,Decorated<Scope,Plain>>;
+
+    #[test]
+    fn decorators_are_composed() {
+        let format    = |entry:&GenericEntry| formatter::format::<Formatter,level::Warning>(entry);
+        let mut entry = GenericEntry::message("app.ui".into(),"Slow frame.");
+        entry.time    = Some(12_345_678);
+        assert_eq!(format(&entry).as_deref(),Some("[12.345] app.ui Slow frame."));
+
+        let scope     = TaskScope::new();
+        let _entered  = scope.enter();
+        let mut entry = GenericEntry::message("app.ui".into(),"Slow frame.");
+        entry.time    = Some(0);
+        let expected  = format!("[0.000] [task {}] app.ui Slow frame.",scope.id());
+        assert_eq!(format(&entry),Some(expected));
+        assert_eq!(format(&GenericEntry::group_end("app.ui".into())),None);
+    }
+}
@@ -5,6 +5,7 @@ use crate::prelude::*;
 use crate::entry::level;
 use crate::entry::GenericEntry;
 use crate::processor::formatter;
+use crate::processor::formatter::decorator::Decoratable;
 
 
 
@@ -33,13 +34,22 @@ impl JsConsole {
     }
 }
 
+/// The text is added to the beginning of the first element of the array, which is the format
+/// string of the console call, so it is displayed without styling.
+impl Decoratable for js_sys::Array {
+    fn prepend(&mut self, text:&str) {
+        let format = self.get(0).as_string().unwrap_or_default();
+        self.set(0,format!("{}{}",text,format).into());
+    }
+}
+
 
 // === Impls ===
 
 impl formatter::Definition<level::Warning> for JsConsole {
     fn format(entry:&GenericEntry) -> Option<Self::Output> {
         entry.content.message().map(|msg|
-            Self::format_color(&entry.path,Some("orange"),format!("[W] {}",msg))
+            Self::format_color(&entry.path.to_string(),Some("orange"),format!("[W] {}",msg))
         )
     }
 }
@@ -47,13 +57,15 @@ impl formatter::Definition<level::Warning> for JsConsole {
 impl formatter::Definition<level::Error> for JsConsole {
     fn format(entry:&GenericEntry) -> Option<Self::Output> {
         entry.content.message().map(|msg|
-            Self::format_color(&entry.path,Some("orangered"),format!("[E] {}",msg))
+            Self::format_color(&entry.path.to_string(),Some("orangered"),format!("[E] {}",msg))
         )
     }
 }
 
 impl<Level> formatter::Definition<Level> for JsConsole {
     default fn format(entry:&GenericEntry) -> Option<Self::Output> {
-        entry.content.message().map(|msg| Self::format_color(&entry.path,None,msg.to_owned()))
+        entry.content.message().map(|msg| {
+            Self::format_color(&entry.path.to_string(),None,msg.to_owned())
+        })
     }
 }
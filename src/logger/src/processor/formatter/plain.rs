@@ -0,0 +1,68 @@
+//! Plain text formatter implementation.
+
+use crate::prelude::*;
+
+use crate::entry::Content;
+use crate::entry::GenericEntry;
+use crate::processor::formatter;
+
+
+
+// =============
+// === Plain ===
+// =============
+
+/// The smallest possible formatter, printing the logger path followed by the message, with no
+/// styling and no level markers. The default JavaScript processors use it when the `minimal`
+/// feature is enabled, as it keeps the logging code in WASM binaries small. The level is still
+/// visible in the JavaScript console, as the consumer uses the console method matching the level.
+/// Group ends are formatted like `app.ui end of Rendering` if they carry the message of the closed
+/// group.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Plain;
+
+impl formatter::Output for Plain {
+    type Output = String;
+}
+
+impl<Level> formatter::Definition<Level> for Plain {
+    fn format(entry:&GenericEntry) -> Option<Self::Output> {
+        match &entry.content {
+            Content::GroupEnd(group) => {
+                group.message.as_ref().map(|msg| iformat!("{entry.path} end of {msg}"))
+            }
+            content => content.message().map(|msg| iformat!("{entry.path} {msg}")),
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::entry::level;
+    use crate::entry::Entry;
+    use crate::entry::GroupEnd;
+
+    #[test]
+    fn plain_formatting() {
+        let path   = "app.ui".into();
+        let entry  = Entry::<level::DefaultLevels>::message(level::Warning,path,"Slow frame.");
+        let output = formatter::format::<Plain,level::Warning>(&entry);
+        assert_eq!(output.as_deref(),Some("app.ui Slow frame."));
+
+        let mut entry = Entry::<level::DefaultLevels>::group_end(level::Info,"app.ui".into());
+        let output    = formatter::format::<Plain,level::Info>(&entry);
+        assert_eq!(output,None);
+        let group     = GroupEnd {message:Some("Render".into()),depth:0};
+        entry.gen_entry.content = Content::GroupEnd(group);
+        let output = formatter::format::<Plain,level::Info>(&entry);
+        assert_eq!(output.as_deref(),Some("app.ui end of Render"));
+    }
+}
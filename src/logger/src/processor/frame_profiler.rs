@@ -0,0 +1,249 @@
+//! Per-frame aggregation of the time spent in logger groups. Instead of printing hundreds of
+//! timing entries every frame, the durations of groups are summed up per group path and reported
+//! once per frame.
+
+use crate::prelude::*;
+
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::entry::Content;
+use crate::entry::Entry;
+use crate::entry::level;
+use crate::processor::Drop;
+use crate::processor::Processor;
+
+use std::time::Duration;
+
+
+
+// =================
+// === SpanStats ===
+// =================
+
+/// Timings of all the occurrences of a single group path within a frame.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct SpanStats {
+    /// Number of times the group ended in the frame.
+    pub count : usize,
+    /// Cumulative duration of the group.
+    pub total : Duration,
+    /// The longest single duration of the group.
+    pub max   : Duration,
+}
+
+impl SpanStats {
+    /// Record a single occurrence of the group.
+    pub fn record(&mut self, duration:Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.max    = self.max.max(duration);
+    }
+}
+
+
+
+// ===================
+// === FrameReport ===
+// ===================
+
+/// Aggregated timings of all groups which ended within a single frame. Nested groups are reported
+/// separately, with their paths consisting of the messages of all the enclosing groups, separated
+/// with `SPAN_SEPARATOR`.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct FrameReport {
+    /// Number of the frame, starting from 0 for the entries submitted before the first
+    /// `FrameProfiler::frame_start` call.
+    pub frame : u64,
+    /// Timings of every group path, ordered by path.
+    pub spans : BTreeMap<String,SpanStats>,
+}
+
+impl Display for FrameReport {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f,"Frame {}:",self.frame)?;
+        writeln!(f,"{:<60} {:>6} {:>12} {:>12}","span","count","total","max")?;
+        for (path,stats) in &self.spans {
+            let total = format!("{:?}",stats.total);
+            let max   = format!("{:?}",stats.max);
+            writeln!(f,"{:<60} {:>6} {:>12} {:>12}",path,stats.count,total,max)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+// =====================
+// === FrameProfiler ===
+// =====================
+
+/// Separator of the group messages in the span paths of `FrameReport`.
+pub const SPAN_SEPARATOR : &str = " / ";
+
+/// Path of the summary entries emitted by the `FrameProfiler`.
+pub const SELF_PATH : &str = "logger.frame_profiler";
+
+/// A processor measuring the time between the beginning and the end of every group, and
+/// aggregating the durations per frame. Frames are delimited by explicit `frame_start` calls, which
+/// store the aggregate of the finished frame (accessible with `last_frame`), and pass a single
+/// summary entry with the `SELF_PATH` path to the subsequent processor. Group entries are consumed,
+/// while other entries are passed to the subsequent processor unchanged.
+///
+/// As the profiler has to be accessed on every frame, it is usually defined as a global processor,
+/// for example `Branch<Global<Profiler>,Console>`, where `Profiler` is defined with
+/// `define_global_processor`. The time is measured with the `Clock` when the entries reach the
+/// profiler, so it should not be preceded by any buffering processors.
+#[derive(Debug,Derivative)]
+#[derivative(Default(bound="Next:Default, C:Default"))]
+pub struct FrameProfiler<Next=Drop,C=SystemClock> {
+    frame      : u64,
+    stack      : Vec<(String,u64)>,
+    spans      : BTreeMap<String,SpanStats>,
+    last_frame : Option<FrameReport>,
+    clock      : C,
+    next       : Next,
+}
+
+impl<Next,C> FrameProfiler<Next,C> {
+    /// The clock measuring the durations of groups.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Number of the current frame.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Aggregated timings of the groups which ended in the current frame so far.
+    pub fn current_frame(&self) -> FrameReport {
+        let frame = self.frame;
+        let spans = self.spans.clone();
+        FrameReport {frame,spans}
+    }
+
+    /// Aggregated timings of the last finished frame.
+    pub fn last_frame(&self) -> Option<&FrameReport> {
+        self.last_frame.as_ref()
+    }
+
+    /// Finish the current frame and start a new one. If any group ended in the finished frame, its
+    /// summary is passed to the subsequent processor. Groups which did not end yet are reported
+    /// in the frame they end in.
+    pub fn frame_start<Levels>(&mut self)
+    where Next:Processor<Entry<Levels>>, Levels:From<level::Debug> {
+        let frame  = self.frame;
+        let spans  = mem::take(&mut self.spans);
+        let report = FrameReport {frame,spans};
+        if !report.spans.is_empty() {
+            let summary = Entry::message(level::Debug,SELF_PATH.into(),report.to_string());
+            self.next.submit(summary);
+        }
+        self.last_frame = Some(report);
+        self.frame     += 1;
+    }
+}
+
+impl<Levels,Next,C> Processor<Entry<Levels>> for FrameProfiler<Next,C>
+where Next:Processor<Entry<Levels>>, C:Clock {
+    type Output = ();
+    fn submit(&mut self, entry:Entry<Levels>) {
+        match entry.gen_entry.content {
+            Content::Message(_)        => { self.next.submit(entry); }
+            Content::Table(_)          => { self.next.submit(entry); }
+            Content::GroupBegin(group) => self.stack.push((group.message,self.clock.now())),
+            Content::GroupEnd(_)       => if let Some((_,start)) = self.stack.last() {
+                let duration = Duration::from_micros(self.clock.now().saturating_sub(*start));
+                let path     = self.stack.iter().map(|(message,_)| message).join(SPAN_SEPARATOR);
+                self.spans.entry(path).or_default().record(duration);
+                self.stack.pop();
+            }
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::entry::DefaultLevels;
+
+    fn begin(message:&str) -> Entry<DefaultLevels> {
+        Entry::group_begin(level::Info,"app".into(),message,true)
+    }
+
+    fn end() -> Entry<DefaultLevels> {
+        Entry::group_end(level::Info,"app".into())
+    }
+
+    #[derive(Debug,Default)]
+    struct Recorder {
+        entries : Vec<(String,String)>,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            let message = entry.content.message().unwrap_or_default().to_owned();
+            self.entries.push((entry.path.to_string(),message));
+        }
+    }
+
+    #[test]
+    fn aggregates_groups_per_frame() {
+        let mut profiler = FrameProfiler::<Recorder>::default();
+        profiler.submit(begin("render"));
+        for _ in 0..3 {
+            profiler.submit(begin("shapes"));
+            profiler.submit(end());
+        }
+        profiler.submit(Entry::message(level::Info,"app".into(),"message"));
+        profiler.submit(end());
+        profiler.frame_start::<DefaultLevels>();
+        profiler.frame_start::<DefaultLevels>();
+
+        let report = profiler.last_frame().unwrap();
+        assert_eq!(report.frame,1);
+        assert!(report.spans.is_empty());
+        assert_eq!(profiler.frame(),2);
+        let entries = &profiler.next.entries;
+        assert_eq!(entries.len(),2);
+        assert_eq!(entries[0],("app".into(),"message".into()));
+        assert_eq!(entries[1].0,SELF_PATH);
+        assert!(entries[1].1.starts_with("Frame 0:"));
+        assert!(entries[1].1.contains("render / shapes"));
+    }
+
+    #[test]
+    fn reports_span_stats() {
+        let mut profiler = FrameProfiler::<Drop,ManualClock>::default();
+        let clock        = profiler.clock().clone_ref();
+        profiler.submit(begin("render"));
+        profiler.submit(begin("shapes"));
+        clock.advance(Duration::from_millis(2));
+        profiler.submit(end());
+        profiler.submit(begin("shapes"));
+        clock.advance(Duration::from_millis(1));
+        profiler.submit(end());
+        clock.advance(Duration::from_millis(1));
+        let current = profiler.current_frame();
+        assert_eq!(current.spans.keys().collect_vec(),vec!["render / shapes"]);
+        profiler.submit(end());
+        profiler.frame_start::<DefaultLevels>();
+
+        let report = profiler.last_frame().unwrap();
+        let shapes = report.spans["render / shapes"];
+        let render = report.spans["render"];
+        assert_eq!(report.frame,0);
+        assert_eq!(shapes.count,2);
+        assert_eq!(shapes.max,Duration::from_millis(2));
+        assert_eq!(shapes.total,Duration::from_millis(3));
+        assert_eq!(render.total,Duration::from_millis(4));
+    }
+}
@@ -0,0 +1,110 @@
+//! Tracking of the open groups. Group ends are logged without any information about the group
+//! they close, so processors consuming them, like plain text formatters writing to a file, cannot
+//! tell which group ended. The `GroupTracker` fills it in.
+
+use crate::prelude::*;
+
+use crate::entry::Content;
+use crate::entry::Entry;
+use crate::entry::TaskScope;
+use crate::processor::Processor;
+
+
+
+// ====================
+// === GroupTracker ===
+// ====================
+
+/// A processor keeping the stack of open groups, and attaching the message and the depth of the
+/// closed group to every group end entry. Groups of asynchronous tasks are tracked separately for
+/// every `TaskScope`, so they may interleave. Group ends without a matching group beginning are
+/// passed unchanged.
+#[derive(Debug,Derivative)]
+#[derivative(Default(bound="Next:Default"))]
+pub struct GroupTracker<Next> {
+    stacks : HashMap<Option<TaskScope>,Vec<String>>,
+    next   : Next,
+}
+
+impl<Next> GroupTracker<Next> {
+    /// The number of groups open in the scope.
+    pub fn depth(&self, scope:Option<TaskScope>) -> usize {
+        self.stacks.get(&scope).map_or(0,|stack| stack.len())
+    }
+}
+
+impl<Levels,Next> Processor<Entry<Levels>> for GroupTracker<Next>
+where Next:Processor<Entry<Levels>> {
+    type Output = Next::Output;
+    fn submit(&mut self, mut entry:Entry<Levels>) -> Self::Output {
+        let scope = entry.scope;
+        match &mut entry.gen_entry.content {
+            Content::GroupBegin(group) => {
+                self.stacks.entry(scope).or_default().push(group.message.clone())
+            }
+            Content::GroupEnd(group) => if let Some(stack) = self.stacks.get_mut(&scope) {
+                group.message = stack.pop();
+                group.depth   = stack.len();
+                if stack.is_empty() { self.stacks.remove(&scope); }
+            }
+            Content::Message(_) | Content::Table(_) => {}
+        }
+        self.next.submit(entry)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::DefaultLevels;
+    use crate::entry::GroupEnd;
+    use crate::entry::level::Info;
+
+    #[derive(Debug,Default)]
+    struct Recorder {
+        ends : Vec<GroupEnd>,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            if let Content::GroupEnd(group) = entry.gen_entry.content {
+                self.ends.push(group)
+            }
+        }
+    }
+
+    fn end(message:&str, depth:usize) -> GroupEnd {
+        GroupEnd {message:Some(message.into()),depth}
+    }
+
+    #[test]
+    fn group_ends_carry_the_group_message() {
+        let mut tracker = GroupTracker::<Recorder>::default();
+        let task        = TaskScope::new();
+        let mut log     = |scope:Option<TaskScope>, message:Option<&str>| {
+            let _entered = scope.map(|scope| scope.enter());
+            let entry    = match message {
+                Some(message) => Entry::group_begin(Info,"test".into(),message,false),
+                None          => Entry::group_end(Info,"test".into()),
+            };
+            tracker.submit(entry);
+        };
+        log(None,Some("outer"));
+        log(Some(task),Some("task"));
+        log(None,Some("inner"));
+        log(Some(task),None);
+        log(None,None);
+        log(None,None);
+        log(None,None);
+        let expected = vec![end("task",0),end("inner",1),end("outer",0),default()];
+        assert_eq!(tracker.next.ends,expected);
+        assert_eq!(tracker.depth(None),0);
+    }
+}
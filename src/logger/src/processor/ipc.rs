@@ -0,0 +1,373 @@
+//! Processors passing entries between processes, like the native helper processes and the main
+//! application. The writer serializes the entries to a pipe or a socket, while the reader on the
+//! other end deserializes them and passes them to its own pipeline, so the logs of all the
+//! processes end up in a single place.
+//!
+//! Every entry is sent as a single frame: its length as a little-endian `u32`, followed by the
+//! level code (see `level::Code`), the path, and the content. Strings are sent as their length as a
+//! little-endian `u32`, followed by their UTF-8 bytes, and lists, like table rows, as their length
+//! followed by their elements. Group ends carry the depth and the optional message of the closed
+//! group, as filled by the `GroupTracker`. Task scopes are local to a process and are not sent, so
+//! if groups of asynchronous tasks should stay contiguous, the writer should be preceded by a
+//! `ScopeBuffer`. Times are not sent either, as clocks of different processes cannot be compared,
+//! so the received entries can be timestamped by a `Timestamper` following the reader. The paths
+//! of the received entries are not interned, so the remote side cannot grow the symbol table of
+//! the reader.
+
+use crate::prelude::*;
+
+use crate::entry::Content;
+use crate::entry::Entry;
+use crate::entry::GenericEntry;
+use crate::entry::GroupEnd;
+use crate::entry::Table;
+use crate::entry::level;
+use crate::path::Path;
+use crate::processor::Processor;
+
+use std::convert::TryInto;
+use std::io;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Maximum length of a single frame. Longer frames are rejected by the reader, as they most likely
+/// mean that the stream is corrupted.
+pub const MAX_FRAME_LEN : usize = 16 * 1024 * 1024;
+
+const MESSAGE_TAG     : u8 = 0;
+const GROUP_BEGIN_TAG : u8 = 1;
+const GROUP_END_TAG   : u8 = 2;
+const TABLE_TAG       : u8 = 3;
+
+
+
+// ================
+// === Encoding ===
+// ================
+
+/// Serialize the entry as a single frame, appending it to the buffer.
+pub fn encode<Levels:level::Code>(entry:&Entry<Levels>, buffer:&mut Vec<u8>) {
+    let start = buffer.len();
+    buffer.extend_from_slice(&[0;4]);
+    buffer.push(entry.level.code());
+    encode_str(&entry.path.to_string(),buffer);
+    match &entry.content {
+        Content::Message(message) => {
+            buffer.push(MESSAGE_TAG);
+            encode_str(message,buffer);
+        }
+        Content::GroupBegin(group) => {
+            buffer.push(GROUP_BEGIN_TAG);
+            buffer.push(group.collapsed as u8);
+            encode_str(&group.message,buffer);
+        }
+        Content::GroupEnd(group) => {
+            buffer.push(GROUP_END_TAG);
+            encode_len(group.depth,buffer);
+            buffer.push(group.message.is_some() as u8);
+            if let Some(message) = &group.message { encode_str(message,buffer) }
+        }
+        Content::Table(table) => {
+            buffer.push(TABLE_TAG);
+            encode_str(&table.title,buffer);
+            encode_strings(&table.columns,buffer);
+            encode_len(table.rows.len(),buffer);
+            for row in &table.rows { encode_strings(row,buffer); }
+        }
+    }
+    let len = (buffer.len() - start - 4) as u32;
+    buffer[start..start + 4].copy_from_slice(&len.to_le_bytes());
+}
+
+/// Deserialize the payload of a single frame, without its length prefix.
+pub fn decode<Levels:level::Code>(mut frame:&[u8]) -> io::Result<Entry<Levels>> {
+    let code    = decode_u8(&mut frame)?;
+    let level   = Levels::from_code(code).ok_or_else(|| invalid_data("Unknown level code."))?;
+    let path    = Path::uninterned(&decode_string(&mut frame)?);
+    let content = match decode_u8(&mut frame)? {
+        MESSAGE_TAG     => Content::Message(decode_string(&mut frame)?),
+        GROUP_BEGIN_TAG => {
+            let collapsed = decode_u8(&mut frame)? != 0;
+            Content::group_begin(collapsed,decode_string(&mut frame)?)
+        }
+        GROUP_END_TAG   => {
+            let depth   = decode_len(&mut frame)?;
+            let message = match decode_u8(&mut frame)? {
+                0 => None,
+                _ => Some(decode_string(&mut frame)?),
+            };
+            Content::GroupEnd(GroupEnd {message,depth})
+        }
+        TABLE_TAG       => {
+            let title     = decode_string(&mut frame)?;
+            let columns   = decode_strings(&mut frame)?;
+            let row_count = decode_len(&mut frame)?;
+            let rows      = (0..row_count).map(|_| decode_strings(&mut frame));
+            let rows      = rows.collect::<io::Result<_>>()?;
+            Content::Table(Table {title,columns,rows})
+        }
+        _               => return Err(invalid_data("Unknown content tag.")),
+    };
+    if !frame.is_empty() { return Err(invalid_data("Unexpected bytes at the end of the frame.")) }
+    let scope     = None;
+    let time      = None;
+    let gen_entry = GenericEntry {path,content,scope,time};
+    Ok(Entry {level,gen_entry})
+}
+
+fn encode_len(len:usize, buffer:&mut Vec<u8>) {
+    buffer.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn encode_str(str:&str, buffer:&mut Vec<u8>) {
+    encode_len(str.len(),buffer);
+    buffer.extend_from_slice(str.as_bytes());
+}
+
+fn encode_strings(strings:&[String], buffer:&mut Vec<u8>) {
+    encode_len(strings.len(),buffer);
+    for str in strings { encode_str(str,buffer); }
+}
+
+fn decode_u8(frame:&mut &[u8]) -> io::Result<u8> {
+    Ok(take(frame,1)?[0])
+}
+
+fn decode_len(frame:&mut &[u8]) -> io::Result<usize> {
+    Ok(u32::from_le_bytes(take(frame,4)?.try_into().unwrap()) as usize)
+}
+
+fn decode_string(frame:&mut &[u8]) -> io::Result<String> {
+    let len   = decode_len(frame)?;
+    let bytes = take(frame,len)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data("Invalid UTF-8 string."))
+}
+
+fn decode_strings(frame:&mut &[u8]) -> io::Result<Vec<String>> {
+    let len = decode_len(frame)?;
+    (0..len).map(|_| decode_string(frame)).collect()
+}
+
+fn take<'a>(frame:&mut &'a [u8], len:usize) -> io::Result<&'a [u8]> {
+    if frame.len() < len { return Err(invalid_data("Unexpected end of the frame.")) }
+    let (bytes,rest) = frame.split_at(len);
+    *frame = rest;
+    Ok(bytes)
+}
+
+fn invalid_data(message:&str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData,message)
+}
+
+
+
+// =================
+// === IpcWriter ===
+// =================
+
+/// Processor writing the serialized entries to the writer, usually a pipe or a socket connected to
+/// an `IpcReader` in another process. Entries are written immediately, one frame per entry. The
+/// first failed write disconnects the writer: the error is stored and all the subsequent entries
+/// are dropped, so a dead receiver never breaks the logging process.
+#[derive(Debug)]
+pub struct IpcWriter<W> {
+    writer : Option<W>,
+    buffer : Vec<u8>,
+    error  : Option<io::Error>,
+}
+
+impl<W:io::Write> IpcWriter<W> {
+    /// Constructor.
+    pub fn new(writer:W) -> Self {
+        let writer = Some(writer);
+        let buffer = default();
+        let error  = default();
+        Self {writer,buffer,error}
+    }
+
+    /// Check whether the entries are still written.
+    pub fn is_connected(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// The error which disconnected the writer, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Consume the processor, returning the writer if it is still connected.
+    pub fn into_inner(self) -> Option<W> {
+        self.writer
+    }
+}
+
+impl<W,Levels> Processor<Entry<Levels>> for IpcWriter<W>
+where W:io::Write, Levels:level::Code {
+    type Output = ();
+    fn submit(&mut self, entry:Entry<Levels>) {
+        if let Some(writer) = &mut self.writer {
+            self.buffer.clear();
+            encode(&entry,&mut self.buffer);
+            let result = writer.write_all(&self.buffer).and_then(|_| writer.flush());
+            if let Err(error) = result {
+                self.writer = None;
+                self.error  = Some(error);
+            }
+        }
+    }
+}
+
+
+
+// =================
+// === IpcReader ===
+// =================
+
+/// Reader of the entries written by an `IpcWriter`, passing them to the subsequent processor. The
+/// paths of the entries can be prefixed, so the entries of different processes can be told apart
+/// and routed separately, for example with the `Router`.
+///
+/// The reader blocks on the underlying reader, so it is usually run on a separate thread, owning
+/// its own pipeline.
+#[derive(Debug)]
+pub struct IpcReader<R,Next> {
+    reader : R,
+    prefix : Path,
+    next   : Next,
+}
+
+impl<R:io::Read,Next> IpcReader<R,Next> {
+    /// Constructor.
+    pub fn new(reader:R, next:Next) -> Self {
+        let prefix = default();
+        Self {reader,prefix,next}
+    }
+
+    /// Prefix the paths of all the read entries with the given path, like `backend`.
+    pub fn with_prefix(mut self, prefix:impl Into<Path>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// The subsequent processor.
+    pub fn next(&self) -> &Next {
+        &self.next
+    }
+
+    /// Read a single entry. Returns `None` if the stream ended between frames.
+    pub fn read_entry<Levels:level::Code>(&mut self) -> io::Result<Option<Entry<Levels>>> {
+        let mut len = [0;4];
+        match self.reader.read_exact(&mut len) {
+            Ok(())                                                     => {}
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error)                                                 => return Err(error),
+        }
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_FRAME_LEN { return Err(invalid_data("Frame exceeds the maximum length.")) }
+        let mut frame = vec![0;len];
+        self.reader.read_exact(&mut frame)?;
+        let mut entry = decode::<Levels>(&frame)?;
+        if !self.prefix.is_empty() {
+            entry.gen_entry.path = self.prefix.sub(&entry.path.to_string());
+        }
+        Ok(Some(entry))
+    }
+
+    /// Read a single entry and pass it to the subsequent processor. Returns false if the stream
+    /// ended.
+    pub fn forward<Levels>(&mut self) -> io::Result<bool>
+    where Levels:level::Code, Next:Processor<Entry<Levels>> {
+        let entry = self.read_entry()?;
+        let found = entry.is_some();
+        if let Some(entry) = entry { self.next.submit(entry); }
+        Ok(found)
+    }
+
+    /// Pass all the entries to the subsequent processor, until the stream ends. Returns the number
+    /// of passed entries.
+    pub fn forward_all<Levels>(&mut self) -> io::Result<usize>
+    where Levels:level::Code, Next:Processor<Entry<Levels>> {
+        let mut count = 0;
+        while self.forward::<Levels>()? { count += 1; }
+        Ok(count)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::DefaultLevels;
+
+    #[derive(Debug,Default)]
+    struct Recorder {
+        entries : Vec<(DefaultLevels,String,Option<String>)>,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            let message = entry.content.message().map(|t| t.to_owned());
+            self.entries.push((entry.level,entry.path.to_string(),message));
+        }
+    }
+
+    #[test]
+    fn entries_are_passed_between_processes() {
+        let mut writer = IpcWriter::new(Vec::new());
+        writer.submit(Entry::<DefaultLevels>::message(level::Warning,"app".into(),"Ąę message"));
+        writer.submit(Entry::<DefaultLevels>::group_begin(level::Info,"app.io".into(),"read",true));
+        writer.submit(Entry::<DefaultLevels>::group_end(level::Info,"app.io".into()));
+        let bytes = writer.into_inner().unwrap();
+
+        let reader     = IpcReader::new(bytes.as_slice(),Recorder::default());
+        let mut reader = reader.with_prefix("backend");
+        assert_eq!(reader.forward_all::<DefaultLevels>().unwrap(),3);
+        let entries = &reader.next().entries;
+        let warning = (DefaultLevels::Warning,"backend.app".into(),Some("Ąę message".into()));
+        assert_eq!(entries[0],warning);
+        assert_eq!(entries[1],(DefaultLevels::Info,"backend.app.io".into(),Some("read".into())));
+        assert_eq!(entries[2],(DefaultLevels::Info,"backend.app.io".into(),None));
+    }
+
+    #[test]
+    fn tables_are_passed_between_processes() {
+        let table     = Table::new("Passes",&["pass","time"]).row(&["shapes","12"]).row(&["ui"]);
+        let entry     = Entry::<DefaultLevels>::table(level::Info,"app".into(),table.clone());
+        let mut bytes = Vec::new();
+        encode(&entry,&mut bytes);
+        let decoded = decode::<DefaultLevels>(&bytes[4..]).unwrap();
+        assert!(matches!(decoded.gen_entry.content, Content::Table(t) if t == table));
+    }
+
+    #[test]
+    fn group_ends_are_passed_between_processes() {
+        let mut entry = Entry::<DefaultLevels>::group_end(level::Info,"app".into());
+        let group     = GroupEnd {message:Some("read".into()),depth:2};
+        entry.gen_entry.content = Content::GroupEnd(group.clone());
+        let mut bytes = Vec::new();
+        encode(&entry,&mut bytes);
+        let decoded = decode::<DefaultLevels>(&bytes[4..]).unwrap();
+        assert!(matches!(decoded.gen_entry.content, Content::GroupEnd(t) if t == group));
+    }
+
+    #[test]
+    fn corrupted_frames_are_rejected() {
+        let mut bytes = Vec::new();
+        encode(&Entry::<DefaultLevels>::message(level::Error,"app".into(),"message"),&mut bytes);
+        let truncated = &bytes[..bytes.len() - 1];
+        let mut reader = IpcReader::new(truncated,Recorder::default());
+        assert!(reader.read_entry::<DefaultLevels>().is_err());
+        bytes[4] = 100;
+        assert!(decode::<DefaultLevels>(&bytes[4..]).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! A processor scrubbing sensitive content, like project paths or user code snippets, from the
+//! entries before they reach remote or file consumers.
+
+use crate::prelude::*;
+
+use crate::entry::Content;
+use crate::entry::Entry;
+use crate::processor::Processor;
+
+
+
+// ================
+// === Redactor ===
+// ================
+
+/// A redaction rule. Returns the redacted text, or the unchanged input if nothing was redacted.
+pub type Rule = Box<dyn for<'t> Fn(&'t str) -> Cow<'t,str>>;
+
+/// Processor applying all the registered redaction rules, in order of registration, to the messages
+/// of entries and groups, and passing the redacted entries to the subsequent processor. Rules are
+/// configured with the builder API:
+///
+/// ```
+/// # use enso_logger::processor::*;
+/// type Console = Seq<Formatter<formatter::NativeConsole>,Consumer<consumer::NativeConsole>>;
+/// let redactor = Redactor::<Console>::new()
+///     .replace("/home/user","~")
+///     .rule(|text| text.replace("secret","***").into());
+/// ```
+///
+/// Place the redactor before all the consumers which should not see the sensitive content, for
+/// example as the first stage of a route of the `Router`. Regex based rules are available with the
+/// `regex` feature.
+pub struct Redactor<Next> {
+    rules : Vec<Rule>,
+    next  : Next,
+}
+
+impl<Next> Redactor<Next> {
+    /// Constructor of a redactor without any rules.
+    pub fn new() -> Self where Next:Default {
+        let rules = default();
+        let next  = default();
+        Self {rules,next}
+    }
+
+    /// Apply the closure to all the messages.
+    pub fn rule(mut self, rule:impl for<'t> Fn(&'t str) -> Cow<'t,str> + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Replace all occurrences of the `pattern` in all the messages.
+    pub fn replace(self, pattern:impl Into<String>, replacement:impl Into<String>) -> Self {
+        let pattern     = pattern.into();
+        let replacement = replacement.into();
+        self.rule(move |text| {
+            if text.contains(&pattern) { text.replace(&pattern,&replacement).into() }
+            else                       { text.into() }
+        })
+    }
+
+    /// Replace all matches of the regular expression in all the messages. The replacement can refer
+    /// to the capture groups, like `$1`. See `regex::Regex::replace_all` to learn more.
+    #[cfg(feature="regex")]
+    pub fn replace_regex(self, regex:regex::Regex, replacement:impl Into<String>) -> Self {
+        let replacement = replacement.into();
+        self.rule(move |text| regex.replace_all(text,replacement.as_str()))
+    }
+
+    /// Apply all the rules to the text.
+    pub fn redact(&self, text:String) -> String {
+        self.rules.iter().fold(text,|text,rule| {
+            let redacted = match rule(&text) {
+                Cow::Borrowed(slice) if slice == text => None,
+                Cow::Borrowed(slice)                  => Some(slice.to_owned()),
+                Cow::Owned(owned)                     => Some(owned),
+            };
+            redacted.unwrap_or(text)
+        })
+    }
+}
+
+impl<Next:Default> Default for Redactor<Next> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Next:Debug> Debug for Redactor<Next> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Redactor")
+            .field("rules",&self.rules.len())
+            .field("next",&self.next)
+            .finish()
+    }
+}
+
+impl<Levels,Next> Processor<Entry<Levels>> for Redactor<Next>
+where Next:Processor<Entry<Levels>> {
+    type Output = Next::Output;
+    fn submit(&mut self, mut entry:Entry<Levels>) -> Self::Output {
+        let texts : Vec<&mut String> = match &mut entry.gen_entry.content {
+            Content::Message(message)  => vec![message],
+            Content::GroupBegin(group) => vec![&mut group.message],
+            Content::GroupEnd(group)   => group.message.iter_mut().collect(),
+            Content::Table(table)      => {
+                let cells = table.columns.iter_mut().chain(table.rows.iter_mut().flatten());
+                iter::once(&mut table.title).chain(cells).collect()
+            }
+        };
+        for text in texts {
+            *text = self.redact(mem::take(text));
+        }
+        self.next.submit(entry)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::DefaultLevels;
+    use crate::entry::level;
+    use crate::processor::Identity;
+
+    fn message(entry:Entry<DefaultLevels>) -> String {
+        entry.content.message().unwrap_or_default().to_owned()
+    }
+
+    fn hide_secrets(text:&str) -> Cow<str> {
+        if text.starts_with("~/secret") { "<hidden>".into() } else { text.into() }
+    }
+
+    #[test]
+    fn rules_are_applied_in_order() {
+        let mut redactor = Redactor::<Identity>::new().replace("/home/user","~").rule(hide_secrets);
+        let entry = Entry::message(level::Info,"app".into(),"/home/user/project/main.enso");
+        assert_eq!(message(redactor.submit(entry)),"~/project/main.enso");
+        let entry = Entry::group_begin(level::Info,"app".into(),"/home/user/secret",false);
+        assert_eq!(message(redactor.submit(entry)),"<hidden>");
+        let entry = Entry::<DefaultLevels>::group_end(level::Info,"app".into());
+        assert!(redactor.submit(entry).content.message().is_none());
+    }
+
+    #[cfg(feature="regex")]
+    #[test]
+    fn regex_rules() {
+        let regex        = regex::Regex::new(r"main = (\w+)").unwrap();
+        let mut redactor = Redactor::<Identity>::new().replace_regex(regex,"main = <code:$1>");
+        let entry        = Entry::message(level::Info,"app".into(),"Parsing main = foo");
+        assert_eq!(message(redactor.submit(entry)),"Parsing main = <code:foo>");
+    }
+}
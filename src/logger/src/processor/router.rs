@@ -0,0 +1,166 @@
+//! A processor dispatching entries to different pipelines, based on their level or path. For
+//! example, errors can be sent both to a remote consumer and to the console, while trace entries
+//! are kept in a ring buffer only.
+
+use crate::prelude::*;
+
+use crate::entry::Entry;
+use crate::path::Path;
+use crate::processor::Processor;
+
+
+
+// ==============
+// === Router ===
+// ==============
+
+/// Predicate deciding whether an entry should be passed to a route.
+pub type Predicate<Levels> = Box<dyn Fn(&Entry<Levels>) -> bool>;
+
+/// A processor which is a destination of a route.
+pub type Destination<Levels> = Box<dyn Processor<Entry<Levels>,Output=()>>;
+
+/// Processor dispatching entries to the pipelines of all the routes matching them. Entries not
+/// matching any route are passed to the fallback pipeline, if set. Routes are configured with the
+/// builder API:
+///
+/// ```
+/// # use enso_logger::entry::DefaultLevels;
+/// # use enso_logger::processor::*;
+/// type Console = Seq<Formatter<formatter::NativeConsole>,Consumer<consumer::NativeConsole>>;
+/// let router = Router::<DefaultLevels>::new()
+///     .route_levels(&[DefaultLevels::Warning,DefaultLevels::Error],Console::default())
+///     .route_path("app.gui",Console::default())
+///     .fallback(Drop);
+/// ```
+///
+/// As routes are configured at runtime, a router is usually stored as a global processor (see
+/// `define_global_processor`) and configured on application startup. The default router has no
+/// routes and drops all the entries.
+pub struct Router<Levels> {
+    routes   : Vec<(Predicate<Levels>,Destination<Levels>)>,
+    fallback : Option<Destination<Levels>>,
+}
+
+impl<Levels> Router<Levels> {
+    /// Constructor of a router without any routes.
+    pub fn new() -> Self {
+        let routes   = default();
+        let fallback = default();
+        Self {routes,fallback}
+    }
+
+    /// Pass all the entries matching the predicate to the processor.
+    pub fn route<P>(mut self, predicate:impl Fn(&Entry<Levels>) -> bool + 'static, processor:P)
+    -> Self where P : Processor<Entry<Levels>,Output=()> + 'static {
+        self.routes.push((Box::new(predicate),Box::new(processor)));
+        self
+    }
+
+    /// Pass all the entries with one of the given levels to the processor.
+    pub fn route_levels<P>(self, levels:&[Levels], processor:P) -> Self
+    where P      : Processor<Entry<Levels>,Output=()> + 'static,
+          Levels : PartialEq + Clone + 'static {
+        let levels = levels.to_vec();
+        self.route(move |entry| levels.contains(&entry.level),processor)
+    }
+
+    /// Pass all the entries of the logger with the given path and its sub-loggers to the processor.
+    pub fn route_path<P>(self, path:impl Into<Path>, processor:P) -> Self
+    where P : Processor<Entry<Levels>,Output=()> + 'static {
+        let path = path.into();
+        self.route(move |entry| entry.path.starts_with(&path),processor)
+    }
+
+    /// Pass all the entries not matching any route to the processor.
+    pub fn fallback<P>(mut self, processor:P) -> Self
+    where P : Processor<Entry<Levels>,Output=()> + 'static {
+        self.fallback = Some(Box::new(processor));
+        self
+    }
+}
+
+impl<Levels> Default for Router<Levels> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Levels> Debug for Router<Levels> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("routes",&self.routes.len())
+            .field("fallback",&self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl<Levels:Clone> Processor<Entry<Levels>> for Router<Levels> {
+    type Output = ();
+    fn submit(&mut self, entry:Entry<Levels>) {
+        let mut matched = false;
+        for (predicate,processor) in &mut self.routes {
+            if predicate(&entry) {
+                matched = true;
+                processor.submit(entry.clone());
+            }
+        }
+        if !matched {
+            if let Some(fallback) = &mut self.fallback {
+                fallback.submit(entry)
+            }
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::DefaultLevels;
+
+    #[derive(Debug,Default)]
+    struct Recorder {
+        entries : Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            let message = entry.content.message().unwrap_or_default().to_owned();
+            self.entries.borrow_mut().push(message);
+        }
+    }
+
+    fn recorder() -> (Recorder,Rc<RefCell<Vec<String>>>) {
+        let recorder = Recorder::default();
+        let entries  = recorder.entries.clone();
+        (recorder,entries)
+    }
+
+    #[test]
+    fn routing() {
+        let (errors,error_entries)      = recorder();
+        let (console,console_entries)   = recorder();
+        let (gui,gui_entries)           = recorder();
+        let (fallback,fallback_entries) = recorder();
+        let mut router = Router::new()
+            .route_levels(&[DefaultLevels::Error],errors)
+            .route(|entry| entry.level != DefaultLevels::Trace,console)
+            .route_path("app.gui",gui)
+            .fallback(fallback);
+        router.submit(Entry::message(DefaultLevels::Error,"app".into(),"error"));
+        router.submit(Entry::message(DefaultLevels::Info,"app.gui.node".into(),"info"));
+        router.submit(Entry::message(DefaultLevels::Trace,"app.gui".into(),"gui trace"));
+        router.submit(Entry::message(DefaultLevels::Trace,"app.guide".into(),"trace"));
+        assert_eq!(*error_entries.borrow(),vec!["error"]);
+        assert_eq!(*console_entries.borrow(),vec!["error","info"]);
+        assert_eq!(*gui_entries.borrow(),vec!["info","gui trace"]);
+        assert_eq!(*fallback_entries.borrow(),vec!["trace"]);
+    }
+}
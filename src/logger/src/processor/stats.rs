@@ -0,0 +1,253 @@
+//! Statistics of the logging pipeline. Allows checking which loggers produce the most entries and
+//! whether logging itself became a performance bottleneck.
+
+use crate::prelude::*;
+
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::entry::Entry;
+use crate::entry::level;
+use crate::path::Path;
+use crate::processor::Processor;
+
+use std::time::Duration;
+
+
+
+// =================
+// === PathStats ===
+// =================
+
+/// Statistics gathered for a single logger path.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct PathStats {
+    /// Number of entries submitted with this path.
+    pub count        : usize,
+    /// Cumulative time spent in the formatter.
+    pub format_time  : Duration,
+    /// Cumulative time spent in the consumer.
+    pub consume_time : Duration,
+    /// Number of entries for which the consumer exceeded the slow consumer threshold.
+    pub slow_count   : usize,
+}
+
+impl PathStats {
+    /// Total time spent processing entries with this path.
+    pub fn total_time(&self) -> Duration {
+        self.format_time + self.consume_time
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// Snapshot of the statistics of all logger paths, ordered by path.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct Report {
+    /// Statistics of every path which submitted at least one entry.
+    pub paths : BTreeMap<String,PathStats>,
+}
+
+impl Report {
+    /// Sum of the statistics of all paths.
+    pub fn total(&self) -> PathStats {
+        self.paths.values().fold(default(),|mut total,stats| {
+            total.count        += stats.count;
+            total.format_time  += stats.format_time;
+            total.consume_time += stats.consume_time;
+            total.slow_count   += stats.slow_count;
+            total
+        })
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f,"{:<40} {:>8} {:>12} {:>12} {:>6}","path","count","format","consume","slow")?;
+        for (path,stats) in &self.paths {
+            let format  = format!("{:?}",stats.format_time);
+            let consume = format!("{:?}",stats.consume_time);
+            let slow    = stats.slow_count;
+            writeln!(f,"{:<40} {:>8} {:>12} {:>12} {:>6}",path,stats.count,format,consume,slow)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+// ==================
+// === Statistics ===
+// ==================
+
+/// Default value of the slow consumer threshold.
+pub const DEFAULT_SLOW_CONSUMER_THRESHOLD : Duration = Duration::from_millis(1);
+
+/// Path of the warnings emitted when a slow consumer is detected.
+pub const SELF_PATH : &str = "logger.stats";
+
+#[derive(Debug)]
+struct Statistics {
+    paths     : HashMap<Path,PathStats>,
+    threshold : Duration,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        let paths     = default();
+        let threshold = DEFAULT_SLOW_CONSUMER_THRESHOLD;
+        Self {paths,threshold}
+    }
+}
+
+thread_local! {
+    static STATISTICS : RefCell<Statistics> = default();
+}
+
+/// Snapshot of the statistics gathered by all `Stats` processors in the current thread.
+pub fn report() -> Report {
+    STATISTICS.with(|stats| {
+        let paths = stats.borrow().paths.iter().map(|(path,s)| (path.to_string(),*s)).collect();
+        Report {paths}
+    })
+}
+
+/// Clear the gathered statistics. The slow consumer threshold is kept.
+pub fn reset() {
+    STATISTICS.with(|stats| stats.borrow_mut().paths.clear())
+}
+
+/// Time a consumer can spend on a single entry before it is reported as slow.
+pub fn slow_consumer_threshold() -> Duration {
+    STATISTICS.with(|stats| stats.borrow().threshold)
+}
+
+/// Set the time a consumer can spend on a single entry before it is reported as slow.
+pub fn set_slow_consumer_threshold(threshold:Duration) {
+    STATISTICS.with(|stats| stats.borrow_mut().threshold = threshold)
+}
+
+/// Record processing of a single entry. Returns true if the consumer was slow.
+fn record(path:&Path, format_time:Duration, consume_time:Duration) -> bool {
+    STATISTICS.with(|stats| {
+        let mut stats = stats.borrow_mut();
+        let is_slow   = consume_time > stats.threshold;
+        let entry     = stats.paths.entry(path.clone()).or_default();
+        entry.count        += 1;
+        entry.format_time  += format_time;
+        entry.consume_time += consume_time;
+        if is_slow { entry.slow_count += 1 }
+        is_slow
+    })
+}
+
+
+
+// =============
+// === Stats ===
+// =============
+
+/// A processor measuring the time spent in the formatter and consumer stages, for example
+/// `Stats<Formatter<formatter::JsConsole>,Consumer<consumer::JsConsole>>`. Entries are counted per
+/// logger path, and the results can be accessed with the `report` function. The time is measured
+/// with the `Clock`.
+///
+/// When the consumer spends more time on a single entry than `slow_consumer_threshold`, a warning
+/// with the `SELF_PATH` path is passed through the same stages. The warning itself is not measured.
+#[derive(Debug,Default)]
+pub struct Stats<Formatter,Consumer,C=SystemClock> {
+    formatter : Formatter,
+    consumer  : Consumer,
+    clock     : C,
+}
+
+impl<Formatter,Consumer,C> Stats<Formatter,Consumer,C> {
+    /// The clock measuring the time spent in the stages.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+impl<Levels,Fmt,Cons,C> Processor<Entry<Levels>> for Stats<Fmt,Cons,C>
+where Fmt    : Processor<Entry<Levels>>,
+      Cons   : Processor<Fmt::Output>,
+      C      : Clock,
+      Levels : From<level::Warning> {
+    type Output = ();
+    fn submit(&mut self, entry:Entry<Levels>) {
+        let path         = entry.path.clone();
+        let start        = self.clock.now();
+        let formatted    = self.formatter.submit(entry);
+        let formatted_at = self.clock.now();
+        self.consumer.submit(formatted);
+        let consumed_at  = self.clock.now();
+        let format_time  = Duration::from_micros(formatted_at.saturating_sub(start));
+        let consume_time = Duration::from_micros(consumed_at.saturating_sub(formatted_at));
+        if record(&path,format_time,consume_time) {
+            let threshold = slow_consumer_threshold();
+            let message   = iformat!(
+                "Consumer took {consume_time:?} to process an entry of '{path}', which exceeds \
+                 the threshold of {threshold:?}.");
+            let warning = Entry::message(level::Warning,SELF_PATH.into(),message);
+            let warning = self.formatter.submit(warning);
+            self.consumer.submit(warning);
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::entry::DefaultLevels;
+    use crate::processor::Identity;
+
+    /// Consumer recording the paths of the entries, advancing the clock on the ones with the `slow`
+    /// path.
+    #[derive(Debug,Default)]
+    struct Recorder {
+        paths : Vec<Path>,
+        clock : ManualClock,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            if entry.path == "slow" {
+                self.clock.advance(Duration::from_millis(5));
+            }
+            self.paths.push(entry.path.clone());
+        }
+    }
+
+    #[test]
+    fn counts_and_slow_consumers() {
+        reset();
+        set_slow_consumer_threshold(Duration::from_millis(2));
+        let mut stats = Stats::<Identity,Recorder,ManualClock>::default();
+        stats.consumer.clock = stats.clock().clone_ref();
+        stats.submit(Entry::message(level::Info,"fast".into(),"msg"));
+        stats.submit(Entry::message(level::Info,"fast".into(),"msg"));
+        stats.submit(Entry::message(level::Info,"slow".into(),"msg"));
+
+        let report = report();
+        assert_eq!(report.paths.len(),2);
+        assert_eq!(report.paths["fast"].count,2);
+        assert_eq!(report.paths["fast"].slow_count,0);
+        assert_eq!(report.paths["slow"].count,1);
+        assert_eq!(report.paths["slow"].slow_count,1);
+        assert_eq!(report.paths["fast"].consume_time,Duration::from_millis(0));
+        assert_eq!(report.paths["slow"].consume_time,Duration::from_millis(5));
+        assert_eq!(report.total().count,3);
+        assert_eq!(stats.consumer.paths,vec!["fast","fast","slow",SELF_PATH]);
+    }
+}
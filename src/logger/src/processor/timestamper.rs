@@ -0,0 +1,84 @@
+//! Timestamping of the entries. Formatters are stateless, so they cannot own a clock. Instead, the
+//! time is measured by the `Timestamper` processor and stored in the entries, where formatters like
+//! `Compact` or the `Timestamp` decorator can read it.
+
+use crate::prelude::*;
+
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::entry::Entry;
+use crate::processor::Processor;
+
+
+
+// ===================
+// === Timestamper ===
+// ===================
+
+/// A processor setting the `time` of every entry to the current time of its `Clock`, and passing
+/// the entry to the subsequent processor. The time is measured when the entries reach the
+/// timestamper, so it should precede any buffering processors. Entries which already have a time,
+/// for example the ones timestamped by another process, keep it.
+#[derive(Debug,Derivative)]
+#[derivative(Default(bound="Next:Default, C:Default"))]
+pub struct Timestamper<Next,C=SystemClock> {
+    clock : C,
+    next  : Next,
+}
+
+impl<Next,C> Timestamper<Next,C> {
+    /// The clock measuring the time of the entries.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+impl<Levels,Next,C> Processor<Entry<Levels>> for Timestamper<Next,C>
+where Next:Processor<Entry<Levels>>, C:Clock {
+    type Output = Next::Output;
+    fn submit(&mut self, mut entry:Entry<Levels>) -> Self::Output {
+        let clock = &self.clock;
+        entry.gen_entry.time.get_or_insert_with(|| clock.now());
+        self.next.submit(entry)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::entry::DefaultLevels;
+    use crate::entry::level::Info;
+
+    #[derive(Debug,Default)]
+    struct Recorder {
+        times : Vec<Option<u64>>,
+    }
+
+    impl Processor<Entry<DefaultLevels>> for Recorder {
+        type Output = ();
+        fn submit(&mut self, entry:Entry<DefaultLevels>) {
+            self.times.push(entry.time)
+        }
+    }
+
+    #[test]
+    fn entries_are_timestamped_with_the_clock() {
+        let mut timestamper = Timestamper::<Recorder,ManualClock>::default();
+        let clock           = timestamper.clock().clone_ref();
+        let entry           = || Entry::message(Info,"app".into(),"message");
+        timestamper.submit(entry());
+        clock.set(1500);
+        timestamper.submit(entry());
+        let mut stamped = entry();
+        stamped.gen_entry.time = Some(7);
+        timestamper.submit(stamped);
+        assert_eq!(timestamper.next.times,vec![Some(0),Some(1500),Some(7)]);
+    }
+}
@@ -84,8 +84,13 @@ impl_clone_ref_as_clone_no_from!(u64);
 impl_clone_ref_as_clone_no_from!(usize);
 impl_clone_ref_as_clone_no_from!(std::any::TypeId);
 impl_clone_ref_as_clone_no_from!([T] PhantomData<T>);
+impl_clone_ref_as_clone_no_from!([T:?Sized] ZST<T>);
 impl_clone_ref_as_clone_no_from!([T:?Sized] Rc<T>);
 impl_clone_ref_as_clone_no_from!([T:?Sized] Weak<T>);
+impl_clone_ref_as_clone_no_from!([T:?Sized] std::sync::Arc<T>);
+impl_clone_ref_as_clone_no_from!([T:?Sized] std::sync::Weak<T>);
+impl_clone_ref_as_clone_no_from!([T:?Sized] std::pin::Pin<Rc<T>>);
+impl_clone_ref_as_clone_no_from!([T:?Sized] std::pin::Pin<std::sync::Arc<T>>);
 
 impl_clone_ref_as_clone_no_from!(wasm_bindgen::JsValue);
 impl_clone_ref_as_clone_no_from!(web_sys::HtmlDivElement);
@@ -96,6 +101,34 @@ impl_clone_ref_as_clone_no_from!(web_sys::HtmlCanvasElement);
 impl_clone_ref_as_clone_no_from!(web_sys::EventTarget);
 
 
+// === Wrappers Impls ===
+
+impl<T:CloneRef> CloneRef for Option<T> {
+    fn clone_ref(&self) -> Self {
+        self.as_ref().map(|t| t.clone_ref())
+    }
+}
+
+/// Implements `CloneRef` for tuples of all the arities up to the number of provided type
+/// parameters, if all their elements implement `CloneRef`.
+macro_rules! impl_clone_ref_for_tuples {
+    () => {};
+    ($t:ident $($ts:ident)*) => {
+        impl_clone_ref_for_tuples!{$($ts)*}
+
+        #[allow(non_snake_case)]
+        impl<$t:CloneRef,$($ts:CloneRef),*> CloneRef for ($t,$($ts,)*) {
+            fn clone_ref(&self) -> Self {
+                let ($t,$($ts,)*) = self;
+                ($t.clone_ref(),$($ts.clone_ref(),)*)
+            }
+        }
+    };
+}
+
+impl_clone_ref_for_tuples!{T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12}
+
+
 // === Option ===
 
 /// Trait for types that can be internally cloned using `CloneRef`, like `Option<&T>`.
@@ -118,3 +151,42 @@ impl<T:CloneRef> ClonedRef for Option<&mut T> {
         self.as_ref().map(|t| t.clone_ref())
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Shared = Rc<Cell<usize>>;
+
+    #[derive(Clone,CloneRef,Debug)]
+    struct Model {
+        rc     : Shared,
+        weak   : Weak<Cell<usize>>,
+        pinned : std::pin::Pin<Shared>,
+        tuple  : (Shared,usize,Option<Shared>),
+        empty  : Option<Shared>,
+    }
+
+    #[test]
+    fn clone_ref_of_wrappers() {
+        let value  = Rc::new(Cell::new(0));
+        let weak   = Rc::downgrade(&value);
+        let pinned = Rc::pin(Cell::new(0));
+        let tuple  = (value.clone_ref(),1,Some(value.clone_ref()));
+        let empty  = None;
+        let model  = Model {rc:value.clone_ref(),weak,pinned,tuple,empty};
+        let model2 = model.clone_ref();
+        model2.rc.set(1);
+        model2.pinned.set(2);
+        assert_eq!(model.weak.upgrade().map(|t| t.get()),Some(1));
+        assert_eq!(model.tuple.2.as_ref().map(|t| t.get()),Some(1));
+        assert_eq!(model.pinned.get(),2);
+        assert!(model2.empty.is_none());
+    }
+}
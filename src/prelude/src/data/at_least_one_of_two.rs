@@ -52,4 +52,130 @@ impl<T1,T2> AtLeastOneOfTwo<T1,T2> {
             _                 => None
         }
     }
+
+    /// Consumes self and returns the first value if exists.
+    pub fn into_first(self) -> Option<T1> {
+        self.into_options().0
+    }
+
+    /// Consumes self and returns the second value if exists.
+    pub fn into_second(self) -> Option<T2> {
+        self.into_options().1
+    }
+
+    /// Consumes self and returns both of the values if exist.
+    pub fn into_both(self) -> Option<(T1,T2)> {
+        match self {
+            Self::Both(t1,t2) => Some((t1,t2)),
+            _                 => None
+        }
+    }
+
+    /// Converts from `&AtLeastOneOfTwo<T1,T2>` to `AtLeastOneOfTwo<&T1,&T2>`.
+    pub fn as_refs(&self) -> AtLeastOneOfTwo<&T1,&T2> {
+        match self {
+            Self::First(t1)   => AtLeastOneOfTwo::First(t1),
+            Self::Second(t2)  => AtLeastOneOfTwo::Second(t2),
+            Self::Both(t1,t2) => AtLeastOneOfTwo::Both(t1,t2),
+        }
+    }
+
+    /// Converts from `&mut AtLeastOneOfTwo<T1,T2>` to `AtLeastOneOfTwo<&mut T1,&mut T2>`.
+    pub fn as_mut(&mut self) -> AtLeastOneOfTwo<&mut T1,&mut T2> {
+        match self {
+            Self::First(t1)   => AtLeastOneOfTwo::First(t1),
+            Self::Second(t2)  => AtLeastOneOfTwo::Second(t2),
+            Self::Both(t1,t2) => AtLeastOneOfTwo::Both(t1,t2),
+        }
+    }
+
+    /// Maps the first value, leaving the second one untouched.
+    pub fn map_first<U>(self, f:impl FnOnce(T1) -> U) -> AtLeastOneOfTwo<U,T2> {
+        match self {
+            Self::First(t1)   => AtLeastOneOfTwo::First(f(t1)),
+            Self::Second(t2)  => AtLeastOneOfTwo::Second(t2),
+            Self::Both(t1,t2) => AtLeastOneOfTwo::Both(f(t1),t2),
+        }
+    }
+
+    /// Maps the second value, leaving the first one untouched.
+    pub fn map_second<U>(self, f:impl FnOnce(T2) -> U) -> AtLeastOneOfTwo<T1,U> {
+        match self {
+            Self::First(t1)   => AtLeastOneOfTwo::First(t1),
+            Self::Second(t2)  => AtLeastOneOfTwo::Second(f(t2)),
+            Self::Both(t1,t2) => AtLeastOneOfTwo::Both(t1,f(t2)),
+        }
+    }
+
+    /// Checked constructor. Returns `None` if both values are missing.
+    pub fn from_options(first:Option<T1>, second:Option<T2>) -> Option<Self> {
+        match (first,second) {
+            (Some(t1),Some(t2)) => Some(Self::Both(t1,t2)),
+            (Some(t1),None)     => Some(Self::First(t1)),
+            (None,Some(t2))     => Some(Self::Second(t2)),
+            (None,None)         => None,
+        }
+    }
+
+    /// Converts to a pair of options, at least one of which is `Some`.
+    pub fn into_options(self) -> (Option<T1>,Option<T2>) {
+        match self {
+            Self::First(t1)   => (Some(t1),None),
+            Self::Second(t2)  => (None,Some(t2)),
+            Self::Both(t1,t2) => (Some(t1),Some(t2)),
+        }
+    }
+}
+
+impl<T> AtLeastOneOfTwo<T,T> {
+    /// Returns the only value, or merges both of them with the provided function.
+    pub fn merge(self, f:impl FnOnce(T,T) -> T) -> T {
+        match self {
+            Self::First(t)    => t,
+            Self::Second(t)   => t,
+            Self::Both(t1,t2) => f(t1,t2),
+        }
+    }
+}
+
+impl<T1,T2> From<AtLeastOneOfTwo<T1,T2>> for (Option<T1>,Option<T2>) {
+    fn from(t:AtLeastOneOfTwo<T1,T2>) -> Self {
+        t.into_options()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combinators() {
+        let first  = AtLeastOneOfTwo::<i32,&str>::First(1);
+        let second = AtLeastOneOfTwo::<i32,&str>::Second("a");
+        let both   = AtLeastOneOfTwo::<i32,&str>::Both(1,"a");
+        assert_eq!(both.map_first(|t| t + 1).into_first(),Some(2));
+        assert_eq!(second.map_first(|t| t + 1).into_first(),None);
+        assert_eq!(both.map_second(str::len).into_both(),Some((1,1)));
+        assert_eq!(first.as_refs().into_first(),Some(&1));
+        assert_eq!(first.into_both(),None);
+        assert_eq!(second.into_options(),(None,Some("a")));
+        assert_eq!(<(Option<i32>,Option<&str>)>::from(both),(Some(1),Some("a")));
+        assert_eq!(AtLeastOneOfTwo::<i32,i32>::Both(1,2).merge(|a,b| a + b),3);
+        assert_eq!(AtLeastOneOfTwo::<i32,i32>::Second(2).merge(|a,b| a + b),2);
+    }
+
+    #[test]
+    fn checked_constructor() {
+        assert!(AtLeastOneOfTwo::<i32,i32>::from_options(None,None).is_none());
+        let both = AtLeastOneOfTwo::from_options(Some(1),Some(2)).unwrap();
+        assert_eq!(both.into_both(),Some((1,2)));
+        let second = AtLeastOneOfTwo::<i32,i32>::from_options(None,Some(2)).unwrap();
+        assert_eq!(second.into_second(),Some(2));
+    }
 }
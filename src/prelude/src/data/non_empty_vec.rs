@@ -173,6 +173,86 @@ impl<T> NonEmptyVec<T> {
         (self.len() > 1).and_option_from(||self.elems.pop())
     }
 
+    /// Remove an element from the back of the collection, returning it.
+    ///
+    /// Unlike [`NonEmptyVec::pop`], it reports why no element was removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NonEmptyVecError::LastElement`] if there is only one item left in the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enso_prelude::NonEmptyVec;
+    /// use enso_prelude::NonEmptyVecError;
+    /// let mut vec = NonEmptyVec::new(0,vec![1]);
+    /// assert_eq!(vec.pop_checked(),Ok(1));
+    /// assert_eq!(vec.pop_checked(),Err(NonEmptyVecError::LastElement));
+    /// assert_eq!(vec.len(),1);
+    /// ```
+    pub fn pop_checked(&mut self) -> Result<T,NonEmptyVecError> {
+        self.check_removal(self.len() - 1)?;
+        Ok(self.elems.pop().expect("There is always one element in a NonEmptyVec."))
+    }
+
+    /// Remove and return the element at the specified `index`, shifting all elements after it to
+    /// the left.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NonEmptyVecError::LastElement`] if there is only one item left in the vector, or
+    /// [`NonEmptyVecError::OutOfBounds`] if the `index` is not smaller than its length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enso_prelude::NonEmptyVec;
+    /// use enso_prelude::NonEmptyVecError;
+    /// let mut vec = NonEmptyVec::new(0,vec![1,2]);
+    /// assert_eq!(vec.remove(0),Ok(0));
+    /// assert_eq!(vec.remove(2),Err(NonEmptyVecError::OutOfBounds{index:2,len:2}));
+    /// assert_eq!(vec.remove(1),Ok(2));
+    /// assert_eq!(vec.remove(0),Err(NonEmptyVecError::LastElement));
+    /// assert_eq!(vec.as_slice(),&[1]);
+    /// ```
+    pub fn remove(&mut self, index:usize) -> Result<T,NonEmptyVecError> {
+        self.check_removal(index)?;
+        Ok(self.elems.remove(index))
+    }
+
+    /// Remove and return the element at the specified `index`, replacing it with the last element
+    /// of the vector. It does not preserve the order of elements, but is O(1).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NonEmptyVecError::LastElement`] if there is only one item left in the vector, or
+    /// [`NonEmptyVecError::OutOfBounds`] if the `index` is not smaller than its length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enso_prelude::NonEmptyVec;
+    /// use enso_prelude::NonEmptyVecError;
+    /// let mut vec = NonEmptyVec::new(0,vec![1,2]);
+    /// assert_eq!(vec.swap_remove(0),Ok(0));
+    /// assert_eq!(vec.as_slice(),&[2,1]);
+    /// assert_eq!(vec.swap_remove(1),Ok(1));
+    /// assert_eq!(vec.swap_remove(0),Err(NonEmptyVecError::LastElement));
+    /// ```
+    pub fn swap_remove(&mut self, index:usize) -> Result<T,NonEmptyVecError> {
+        self.check_removal(index)?;
+        Ok(self.elems.swap_remove(index))
+    }
+
+    /// Check whether the element at the `index` can be removed.
+    fn check_removal(&self, index:usize) -> Result<(),NonEmptyVecError> {
+        let len = self.len();
+        if      index >= len { Err(NonEmptyVecError::OutOfBounds{index,len}) }
+        else if len == 1     { Err(NonEmptyVecError::LastElement) }
+        else                 { Ok(()) }
+    }
+
     /// Obtain a mutable reference to teh element in the vector at the specified `index`.
     ///
     /// # Examples
@@ -309,4 +389,36 @@ impl<T:Default> Default for NonEmptyVec<T> {
     fn default() -> Self {
         Self::singleton(default())
     }
-}
\ No newline at end of file
+}
+
+
+
+// ========================
+// === NonEmptyVecError ===
+// ========================
+
+/// Reason why an element could not be removed from a [`NonEmptyVec`].
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum NonEmptyVecError {
+    /// The vector has only one element, which can't be removed.
+    LastElement,
+    /// The index of the element is out of bounds of the vector.
+    OutOfBounds {
+        /// The index of the element.
+        index : usize,
+        /// The length of the vector.
+        len : usize,
+    },
+}
+
+impl Display for NonEmptyVecError {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LastElement => write!(f,"Cannot remove the last element of a NonEmptyVec."),
+            Self::OutOfBounds {index,len} =>
+                write!(f,"Index {} is out of bounds of a NonEmptyVec of length {}.",index,len),
+        }
+    }
+}
+
+impl std::error::Error for NonEmptyVecError {}
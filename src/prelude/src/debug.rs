@@ -1,9 +1,11 @@
 pub mod logging;
+pub mod trace_copies;
 
 use crate::*;
 
 #[cfg(target_arch="wasm32")]
 pub mod internal {
+    use super::Frame;
     use wasm_bindgen::prelude::*;
 
     #[wasm_bindgen]
@@ -24,11 +26,18 @@ pub mod internal {
     pub fn backtrace() -> String {
         Error::new().stack()
     }
+
+    /// Frames of the current backtrace. The locations are the ones reported by the JavaScript
+    /// engine, which maps them with source maps when they are available.
+    pub fn frames() -> Vec<Frame> {
+        backtrace().lines().filter_map(Frame::parse_js).collect()
+    }
 }
 
 #[cfg(not(target_arch="wasm32"))]
 mod internal {
     use crate::*;
+    use super::Frame;
 
     extern crate backtrace as bt;
 
@@ -39,88 +48,234 @@ mod internal {
         let bt = Backtrace::new();
         iformat!("{bt:?}")
     }
+
+    /// Frames of the current backtrace. Inlined functions are reported as separate frames.
+    pub fn frames() -> Vec<Frame> {
+        let bt = Backtrace::new();
+        bt.frames().iter().flat_map(|frame| frame.symbols()).map(|symbol| {
+            let name     = symbol.name().map(|name| name.to_string());
+            let file     = symbol.filename().map(|path| path.display().to_string());
+            let location = file.map(|file| {
+                let line = symbol.lineno().map(|t| iformat!(":{t}")).unwrap_or_default();
+                let col  = symbol.colno().map(|t| iformat!(":{t}")).unwrap_or_default();
+                iformat!("{file}{line}{col}")
+            });
+            Frame {name,location}
+        }).collect()
+    }
 }
 
 pub use internal::backtrace;
+pub use trace_copies::TraceCopies;
 
 
 
-// ===================
-// === TraceCopies ===
-// ===================
+// =============
+// === Frame ===
+// =============
 
-/// An utility for tracing all copies of CloneRef-able entity.
-///
-/// This structure should be added as a field to structure implementing Clone or CloneRef. It will
-/// mark each copy with unique id (the original copy has id of 0). Once enabled, it will print
-/// backtrace of each clone, clone_ref or drop operation with assigned name (the same for all
-/// copies) and copy id.
-#[derive(Debug,Default)]
-pub struct TraceCopies {
-    clone_id : u64,
-    handle   : Rc<RefCell<Option<ImString>>>,
+/// A single frame of a backtrace.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct Frame {
+    /// Name of the function, if known.
+    pub name     : Option<String>,
+    /// Location of the code in the `path:line:column` format, if known.
+    pub location : Option<String>,
 }
 
-thread_local! {
-    static NEXT_CLONE_ID : Cell<u64> = Cell::new(1);
-}
+impl Frame {
+    /// Parse a line of JavaScript `Error.stack`. Supports both the `at name (location)` format used
+    /// by Chrome and the `name@location` format used by Firefox. Returns `None` for lines which are
+    /// not frames, like the `Error` header.
+    pub fn parse_js(line:&str) -> Option<Self> {
+        let line = line.trim();
+        if let Some(frame) = line.strip_prefix("at ") {
+            let with_name = frame.strip_suffix(')').and_then(|t| t.split_once(" ("));
+            Some(match with_name {
+                Some((name,location)) => Self::new(Some(name),Some(location)),
+                None                  => Self::new(None,Some(frame)),
+            })
+        } else {
+            line.rsplit_once('@').map(|(name,location)| {
+                let name = (!name.is_empty()).then(|| name);
+                Self::new(name,Some(location))
+            })
+        }
+    }
+
+    fn new(name:Option<&str>, location:Option<&str>) -> Self {
+        let name     = name.map(|t| t.to_owned());
+        let location = location.map(|t| t.to_owned());
+        Self {name,location}
+    }
 
-fn next_clone_id() -> u64 {
-    NEXT_CLONE_ID.with(|id| {
-        let next = id.get();
-        id.set(next+1);
-        next
-    })
+    /// Demangle the function name, remove its hash, and shorten the location path to the crate
+    /// directory, like `prelude/src/debug.rs:12:5`.
+    pub fn shorten(self) -> Self {
+        let name     = self.name.map(|name| shorten_name(&name));
+        let location = self.location.map(|location| shorten_path(&location));
+        Self {name,location}
+    }
 }
 
-impl TraceCopies {
-    /// Create enabled structure with appointed entity name (shared between all copies).
-    pub fn enabled(name:impl Into<ImString>) -> Self {
-        Self {
-            clone_id : default(),
-            handle   : Rc::new(RefCell::new(Some(name.into()))),
+impl Display for Frame {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{}",self.name.as_deref().unwrap_or("<unknown>"))?;
+        if let Some(location) = &self.location {
+            write!(f,"\n    at {}",location)?;
         }
+        Ok(())
     }
+}
 
-    /// Assign a name to the entity (shared between all copies) and start printing logs.
-    pub fn enable(&self, name: impl Into<ImString>) {
-        *self.handle.borrow_mut() = Some(name.into());
+/// Demangle a legacy Rust symbol, like `_ZN3foo3bar17h0123456789abcdefE`, which is how the symbols
+/// are reported on wasm, and remove the trailing hash, like `::h0123456789abcdef`. Other names are
+/// returned with the hash removed only.
+fn shorten_name(name:&str) -> String {
+    let demangled = demangle(name).unwrap_or_else(|| name.to_owned());
+    match demangled.rsplit_once("::h") {
+        Some((prefix,hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+            prefix.to_owned(),
+        _ => demangled,
     }
 }
 
-impl Clone for TraceCopies {
-    fn clone(&self) -> Self {
-        let borrow   = self.handle.borrow();
-        let clone_id = next_clone_id();
-        let handle   = self.handle.clone();
-        if let Some(name) = &*borrow {
-            let bt = backtrace();
-            iprintln!("[{name}] Cloning {self.clone_id} -> {clone_id} {bt}");
-        }
-        Self {clone_id,handle}
+fn demangle(name:&str) -> Option<String> {
+    let start        = name.find("_ZN")?;
+    let mut input    = &name[start+3..];
+    let mut segments = Vec::new();
+    while !input.starts_with('E') {
+        let digits  = input.chars().take_while(|c| c.is_ascii_digit()).count();
+        let len     = input[..digits].parse::<usize>().ok()?;
+        let segment = input.get(digits..digits+len)?;
+        segments.push(unescape_segment(segment));
+        input = &input[digits+len..];
     }
+    Some(segments.join("::"))
+}
+
+fn unescape_segment(segment:&str) -> String {
+    let escapes = [("$LT$","<"),("$GT$",">"),("$RF$","&"),("$BP$","*"),("$C$",","),("$u20$"," "),
+        ("$u27$","'"),("$u5b$","["),("$u5d$","]"),("$u7b$","{"),("$u7d$","}"),("..","::")];
+    let segment = segment.strip_prefix('_').filter(|t| t.starts_with('$')).unwrap_or(segment);
+    escapes.iter().fold(segment.to_owned(),|t,(from,to)| t.replace(from,to))
 }
 
-impl CloneRef for TraceCopies {
-    fn clone_ref(&self) -> Self {
-        let borrow   = self.handle.borrow();
-        let clone_id = next_clone_id();
-        let handle   = self.handle.clone_ref();
-        if let Some(name) = &*borrow {
-            let bt = backtrace();
-            DEBUG!("[{name}] Cloning {self.clone_id} -> {clone_id} {bt}");
+/// Shorten the path to start at the crate directory, which is the directory containing the `src`
+/// directory.
+fn shorten_path(path:&str) -> String {
+    match path.rfind("/src/") {
+        None        => path.to_owned(),
+        Some(index) => {
+            let crate_start = path[..index].rfind('/').map(|i| i + 1).unwrap_or_default();
+            path[crate_start..].to_owned()
         }
-        Self {clone_id,handle}
     }
 }
 
-impl Drop for TraceCopies {
-    fn drop(&mut self) {
-        let borrow = self.handle.borrow();
-        if let Some(name) = &*borrow {
-            let bt        = backtrace();
-            let instances = Rc::strong_count(&self.handle) - 1;
-            DEBUG!("[{name}] Dropping {self.clone_id}; instances left: {instances} {bt}");
+
+
+// ========================
+// === BacktraceOptions ===
+// ========================
+
+/// Options of the `backtrace_frames` function.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct BacktraceOptions {
+    /// Number of frames to skip, counting from the caller of `backtrace_frames`.
+    pub skip    : usize,
+    /// Maximum number of returned frames.
+    pub depth   : Option<usize>,
+    /// Demangle and shorten the names and paths. See `Frame::shorten` to learn more.
+    pub shorten : bool,
+}
+
+impl BacktraceOptions {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Set the number of skipped frames.
+    pub fn skip(mut self, skip:usize) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    /// Set the maximum number of returned frames.
+    pub fn depth(mut self, depth:usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Enable demangling and shortening of names and paths.
+    pub fn shorten(mut self) -> Self {
+        self.shorten = true;
+        self
+    }
+}
+
+/// Substrings identifying the frames of the backtrace machinery.
+pub(crate) const INTERNAL_FRAME_MARKERS : &[&str] =
+    &["backtrace::","enso_prelude::debug::","__wbg_new"];
+
+/// Frames of the current backtrace, starting at the caller of this function.
+pub fn backtrace_frames(options:BacktraceOptions) -> Vec<Frame> {
+    let is_internal = |frame:&Frame| {
+        let name = frame.name.as_deref().map(shorten_name).unwrap_or_default();
+        INTERNAL_FRAME_MARKERS.iter().any(|marker| name.contains(marker))
+    };
+    let frames = internal::frames().into_iter().skip_while(is_internal).skip(options.skip);
+    let frames = frames.take(options.depth.unwrap_or(usize::MAX));
+    if options.shorten { frames.map(Frame::shorten).collect() } else { frames.collect() }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_js_frames() {
+        let chrome  = "    at app::Node::clone::h1f3 (wasm-function[6]:0x2)";
+        let firefox = "app::Node::clone::h1f3@http://localhost/app.wasm:1:2";
+        let anon    = "    at http://localhost/app.js:10:5";
+        let frame   = Frame::parse_js(chrome).unwrap();
+        assert_eq!(frame.name.as_deref(),Some("app::Node::clone::h1f3"));
+        assert_eq!(frame.location.as_deref(),Some("wasm-function[6]:0x2"));
+        let frame = Frame::parse_js(firefox).unwrap();
+        assert_eq!(frame.name.as_deref(),Some("app::Node::clone::h1f3"));
+        assert_eq!(frame.location.as_deref(),Some("http://localhost/app.wasm:1:2"));
+        let frame = Frame::parse_js(anon).unwrap();
+        assert_eq!(frame.name,None);
+        assert_eq!(frame.location.as_deref(),Some("http://localhost/app.js:10:5"));
+        assert_eq!(Frame::parse_js("Error"),None);
+    }
+
+    #[test]
+    fn shortening() {
+        let name = concat!("_ZN48_$LT$app..Node$u20$as$u20$core..clone..Clone$GT$",
+            "5clone17h0123456789abcdefE");
+        let path = "/root/crate/src/prelude/src/debug.rs:12:5";
+        assert_eq!(shorten_name(name),"<app::Node as core::clone::Clone>::clone");
+        assert_eq!(shorten_name("app::Node::clone::h0123456789abcdef"),"app::Node::clone");
+        assert_eq!(shorten_name("app::Node::clone"),"app::Node::clone");
+        assert_eq!(shorten_path(path),"prelude/src/debug.rs:12:5");
+        assert_eq!(shorten_path("wasm-function[6]:0x2"),"wasm-function[6]:0x2");
+    }
+
+    #[test]
+    fn native_frames() {
+        let options = BacktraceOptions::new().depth(2).shorten();
+        let frames  = backtrace_frames(options);
+        assert!(frames.len() <= 2);
+        if let Some(name) = frames.first().and_then(|frame| frame.name.as_ref()) {
+            assert!(!name.contains("backtrace::"), "Internal frames should be skipped: {}", name);
         }
     }
 }
@@ -8,46 +8,164 @@
 //! production-ready code, so they should be easy to visually catch during code reviews.
 //! Also, this gets us good names that otherwise would be already used.
 
+use crate::*;
+
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+use std::sync::RwLock;
+
+
+
+// =============
+// === Level ===
+// =============
+
+/// Verbosity level of the debug logging macros.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+#[allow(missing_docs)]
+pub enum Level { Trace,Debug,Info,Warning,Error }
+
+impl Level {
+    fn from_u8(value:u8) -> Self {
+        match value {
+            0 => Self::Trace,
+            1 => Self::Debug,
+            2 => Self::Info,
+            3 => Self::Warning,
+            _ => Self::Error,
+        }
+    }
+}
+
+
+
+// ===============
+// === Filters ===
+// ===============
+
+static MIN_LEVEL : AtomicU8 = AtomicU8::new(Level::Trace as u8);
+
+lazy_static! {
+    static ref DISABLED_TARGETS : RwLock<HashSet<String>> = default();
+}
+
+/// Set the minimum level of the messages printed by the debug logging macros. Messages with lower
+/// levels are silenced. All messages are printed by default.
+pub fn set_min_level(level:Level) {
+    MIN_LEVEL.store(level as u8,Ordering::Relaxed)
+}
+
+/// The minimum level of the messages printed by the debug logging macros.
+pub fn min_level() -> Level {
+    Level::from_u8(MIN_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Silence all messages with the given target, like `DEBUG!(target:"layout", "...")`.
+pub fn disable_target(target:impl Into<String>) {
+    DISABLED_TARGETS.write().unwrap().insert(target.into());
+}
+
+/// Print the messages with the given target again. See `disable_target` to learn more.
+pub fn enable_target(target:&str) {
+    DISABLED_TARGETS.write().unwrap().remove(target);
+}
+
+/// Check whether a message with the given level and target would be printed.
+pub fn is_enabled(level:Level, target:Option<&str>) -> bool {
+    let level_enabled  = level >= min_level();
+    let target_enabled = || target.map_or(true,|t| !DISABLED_TARGETS.read().unwrap().contains(t));
+    level_enabled && target_enabled()
+}
+
+/// Print the message if the level and the target are enabled. See `is_enabled` to learn more.
+pub fn log(level:Level, target:Option<&str>, text:impl AsRef<str>) {
+    if is_enabled(level,target) {
+        print(level,target,text.as_ref())
+    }
+}
+
+
+
+// ==============
+// === Macros ===
+// ==============
+
 /// Defines the methods from given names.
 ///
-/// Must be invoked with arguments `$ [...] [...]...` where [...] is triple `[lower UPPER color]`.
+/// Must be invoked with arguments `$ [...] [...]...` where [...] is quadruple
+/// `[lower UPPER color Level]`.
 /// `lower` refers to the name of the Web Console function.
 /// `UPPER` is the name of the generated logging macro.
 /// `color` is the log color that will be used when writing to native (non-web) console output.
+/// `Level` is the variant of `Level` used for filtering.
 ///
-/// For each given triple `[lower UPPER color]` two symbols are defined:
+/// For each given quadruple `[lower UPPER color Level]` two symbols are defined:
 /// * a function `$lower` that writes given text to standard output on native targets and to Web
-///   Console on wasm targets.
+///   Console on wasm targets, if the level is enabled.
 /// * a macro `$UPPER` that wraps the above function with `println`-like syntax.
 ///
+/// Moreover, a private `print` function dispatching to the right output by level is defined.
+///
 /// Note: The first argument `$d` must be `$` (dollar sign). It is used to insert dollar sign in the
 /// nested macro.
 macro_rules! define_debug_macros {
-    ($d:tt $([$lower:ident $upper:ident $color:ident])*) => {$(
-        /// Writes given text either to the stdout (non-wasm) or Web Console (wasm).
-        pub fn $lower(text:impl AsRef<str>) {
-            cfg_if::cfg_if! {
-                if #[cfg(target_arch="wasm32")] {
-                    use web_sys::console::*;
-                    concat_idents!($lower,_1)(&wasm_bindgen::JsValue::from_str(text.as_ref()));
-                } else {
-                    use colored::*;
-                    println!("[{}] {}", stringify!($upper).$color(), text.as_ref());
-                }
+    ($d:tt $([$lower:ident $upper:ident $color:ident $level:ident])*) => {
+        $(
+            /// Writes given text either to the stdout (non-wasm) or Web Console (wasm), if the
+            /// level is enabled. See `set_min_level` to learn more.
+            pub fn $lower(text:impl AsRef<str>) {
+                log(Level::$level,None,text)
             }
-        }
 
-        // FIXME [mwu] Should be restored. See [Clippy ICE workaround]
-        // /// Special logging macro that prints to the Web Console on wasm targets and stdout
-        // /// otherwise. It is supposed to be used only for development purposes and shouldn't be
-        // /// present in a production-ready code.
-        // /// Macro follows `iformat` formatting convention.
-        // #[macro_export] macro_rules! $upper  {
-        //     ($d($d arg:tt)*) => {
-        //         $crate::debug::logging:: $lower($crate::iformat!($d ($d arg)*))
-        //     }
-        // }
-    )*}
+            // FIXME [mwu] Should be restored. See [Clippy ICE workaround]
+            // /// Special logging macro that prints to the Web Console on wasm targets and stdout
+            // /// otherwise. It is supposed to be used only for development purposes and shouldn't
+            // /// be present in a production-ready code.
+            // /// Macro follows `iformat` formatting convention. The output can be silenced with
+            // /// `set_min_level` or, for messages with a target, with `disable_target`.
+            // #[macro_export] macro_rules! $upper  {
+            //     (target:$d target:expr, $d($d arg:tt)*) => {{
+            //         let target        = $d target;
+            //         let target : &str = target.as_ref();
+            //         let level         = $crate::debug::logging::Level::$level;
+            //         if $crate::debug::logging::is_enabled(level,Some(target)) {
+            //             let text = $crate::iformat!($d ($d arg)*);
+            //             $crate::debug::logging::log(level,Some(target),text)
+            //         }
+            //     }};
+            //     ($d($d arg:tt)*) => {
+            //         if $crate::debug::logging::is_enabled
+            //             ($crate::debug::logging::Level::$level,None) {
+            //             $crate::debug::logging:: $lower($crate::iformat!($d ($d arg)*))
+            //         }
+            //     }
+            // }
+        )*
+
+        fn print(level:Level, target:Option<&str>, text:&str) {
+            match level {$(
+                Level::$level => {
+                    cfg_if::cfg_if! {
+                        if #[cfg(target_arch="wasm32")] {
+                            use web_sys::console::*;
+                            let text = match target {
+                                Some(target) => iformat!("[{target}] {text}"),
+                                None         => text.to_owned(),
+                            };
+                            concat_idents!($lower,_1)(&wasm_bindgen::JsValue::from_str(&text));
+                        } else {
+                            use colored::*;
+                            let name = stringify!($upper).$color();
+                            match target {
+                                Some(target) => println!("[{} {}] {}",name,target,text),
+                                None         => println!("[{}] {}",name,text),
+                            }
+                        }
+                    }
+                }
+            )*}
+        }
+    }
 }
 
 // FIXME [mwu] Should be removed. See [Clippy ICE workaround]
@@ -62,16 +180,16 @@ mod manually_expanded;
 // 2) remove the `manually_expanded` module altogether.
 
 define_debug_macros!{$
-    [trace TRACE   purple]
-    [debug DEBUG   blue]
-    [info  INFO    white]
-    [warn  WARNING yellow]
-    [error ERROR   red]
+    [trace TRACE   purple Trace]
+    [debug DEBUG   blue   Debug]
+    [info  INFO    white  Info]
+    [warn  WARNING yellow Warning]
+    [error ERROR   red    Error]
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::*;
+    use super::*;
     use wasm_bindgen_test::*;
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
@@ -87,6 +205,25 @@ mod tests {
         WARNING!("test");
         ERROR!("test");
     }
+
+    #[test]
+    fn filtering() {
+        let var = 39;
+        DEBUG!(target:"layout", "Using new iformat syntax: var = " var ". Is that much?");
+        INFO!(target:String::from("layout"), "Using old iformat syntax: var = {var}.");
+        assert!(is_enabled(Level::Trace,Some("filtering_test")));
+        disable_target("filtering_test");
+        assert!(!is_enabled(Level::Error,Some("filtering_test")));
+        assert!(is_enabled(Level::Trace,None));
+        enable_target("filtering_test");
+        assert!(is_enabled(Level::Trace,Some("filtering_test")));
+        set_min_level(Level::Warning);
+        assert_eq!(min_level(),Level::Warning);
+        assert!(!is_enabled(Level::Info,None));
+        assert!(is_enabled(Level::Error,None));
+        set_min_level(Level::Trace);
+    }
+
     #[wasm_bindgen_test]
     fn wasm_calls() {
         let var = 39;
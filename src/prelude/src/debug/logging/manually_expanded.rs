@@ -4,49 +4,109 @@
 /// Special logging macro that prints to the Web Console on wasm targets and stdout
 /// otherwise. It is supposed to be used only for development purposes and shouldn't be
 /// present in a production-ready code.
-/// Macro follows `iformat` formatting convention.
+/// Macro follows `iformat` formatting convention. The output can be silenced with
+/// `set_min_level` or, for messages with a target, with `disable_target`.
 #[macro_export] macro_rules! TRACE {
+    (target:$target:expr, $($arg:tt)*) => {{
+        let target        = $target;
+        let target : &str = target.as_ref();
+        let level         = $crate::debug::logging::Level::Trace;
+        if $crate::debug::logging::is_enabled(level,Some(target)) {
+            let text = $crate::iformat!($($arg)*);
+            $crate::debug::logging::log(level,Some(target),text)
+        }
+    }};
     ($($arg:tt)*) => {
-        $crate::debug::logging::trace($crate::iformat!($($arg)*))
+        if $crate::debug::logging::is_enabled($crate::debug::logging::Level::Trace,None) {
+            $crate::debug::logging::trace($crate::iformat!($($arg)*))
+        }
     }
 }
 
 /// Special logging macro that prints to the Web Console on wasm targets and stdout
 /// otherwise. It is supposed to be used only for development purposes and shouldn't be
 /// present in a production-ready code.
-/// Macro follows `iformat` formatting convention.
+/// Macro follows `iformat` formatting convention. The output can be silenced with
+/// `set_min_level` or, for messages with a target, with `disable_target`.
 #[macro_export] macro_rules! DEBUG {
+    (target:$target:expr, $($arg:tt)*) => {{
+        let target        = $target;
+        let target : &str = target.as_ref();
+        let level         = $crate::debug::logging::Level::Debug;
+        if $crate::debug::logging::is_enabled(level,Some(target)) {
+            let text = $crate::iformat!($($arg)*);
+            $crate::debug::logging::log(level,Some(target),text)
+        }
+    }};
     ($($arg:tt)*) => {
-        $crate::debug::logging::debug($crate::iformat!($($arg)*))
+        if $crate::debug::logging::is_enabled($crate::debug::logging::Level::Debug,None) {
+            $crate::debug::logging::debug($crate::iformat!($($arg)*))
+        }
     }
 }
 
 /// Special logging macro that prints to the Web Console on wasm targets and stdout
 /// otherwise. It is supposed to be used only for development purposes and shouldn't be
 /// present in a production-ready code.
-/// Macro follows `iformat` formatting convention.
+/// Macro follows `iformat` formatting convention. The output can be silenced with
+/// `set_min_level` or, for messages with a target, with `disable_target`.
 #[macro_export] macro_rules! INFO {
+    (target:$target:expr, $($arg:tt)*) => {{
+        let target        = $target;
+        let target : &str = target.as_ref();
+        let level         = $crate::debug::logging::Level::Info;
+        if $crate::debug::logging::is_enabled(level,Some(target)) {
+            let text = $crate::iformat!($($arg)*);
+            $crate::debug::logging::log(level,Some(target),text)
+        }
+    }};
     ($($arg:tt)*) => {
-        $crate::debug::logging::info($crate::iformat!($($arg)*))
+        if $crate::debug::logging::is_enabled($crate::debug::logging::Level::Info,None) {
+            $crate::debug::logging::info($crate::iformat!($($arg)*))
+        }
     }
 }
 
 /// Special logging macro that prints to the Web Console on wasm targets and stdout
 /// otherwise. It is supposed to be used only for development purposes and shouldn't be
 /// present in a production-ready code.
-/// Macro follows `iformat` formatting convention.
+/// Macro follows `iformat` formatting convention. The output can be silenced with
+/// `set_min_level` or, for messages with a target, with `disable_target`.
 #[macro_export] macro_rules! WARNING {
+    (target:$target:expr, $($arg:tt)*) => {{
+        let target        = $target;
+        let target : &str = target.as_ref();
+        let level         = $crate::debug::logging::Level::Warning;
+        if $crate::debug::logging::is_enabled(level,Some(target)) {
+            let text = $crate::iformat!($($arg)*);
+            $crate::debug::logging::log(level,Some(target),text)
+        }
+    }};
     ($($arg:tt)*) => {
-        $crate::debug::logging::warn($crate::iformat!($($arg)*))
+        if $crate::debug::logging::is_enabled($crate::debug::logging::Level::Warning,None) {
+            $crate::debug::logging::warn($crate::iformat!($($arg)*))
+        }
     }
 }
 
 /// Special logging macro that prints to the Web Console on wasm targets and stdout
 /// otherwise. It is supposed to be used only for development purposes and shouldn't be
 /// present in a production-ready code.
-/// Macro follows `iformat` formatting convention.
+/// Macro follows `iformat` formatting convention. The output can be silenced with
+/// `set_min_level` or, for messages with a target, with `disable_target`.
 #[macro_export] macro_rules! ERROR {
+    (target:$target:expr, $($arg:tt)*) => {{
+        let target        = $target;
+        let target : &str = target.as_ref();
+        let level         = $crate::debug::logging::Level::Error;
+        if $crate::debug::logging::is_enabled(level,Some(target)) {
+            let text = $crate::iformat!($($arg)*);
+            $crate::debug::logging::log(level,Some(target),text)
+        }
+    }};
     ($($arg:tt)*) => {
-        $crate::debug::logging::error($crate::iformat!($($arg)*))
+        if $crate::debug::logging::is_enabled($crate::debug::logging::Level::Error,None) {
+            $crate::debug::logging::error($crate::iformat!($($arg)*))
+        }
     }
 }
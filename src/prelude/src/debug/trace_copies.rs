@@ -0,0 +1,435 @@
+//! Utilities for tracing copies of `Clone` and `CloneRef`-able entities.
+
+use crate::*;
+
+use crate::debug::backtrace;
+use crate::debug::INTERNAL_FRAME_MARKERS;
+use crate::debug::logging;
+
+
+
+// =============
+// === Event ===
+// =============
+
+/// Operation performed on a traced entity.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[allow(missing_docs)]
+pub enum EventKind { Clone,CloneRef,Drop }
+
+/// A single operation performed on a copy of a traced entity.
+#[derive(Clone,Debug)]
+pub struct Event {
+    /// Name of the traced entity, shared between all its copies.
+    pub name      : ImString,
+    /// Operation performed.
+    pub kind      : EventKind,
+    /// Id of the copy the operation was performed on. For clones, this is the source copy.
+    pub clone_id  : u64,
+    /// Id of the created copy. Set for clones only.
+    pub new_id    : Option<u64>,
+    /// Number of copies alive after the operation.
+    pub instances : usize,
+    /// Backtrace of the operation.
+    pub backtrace : String,
+}
+
+impl Display for Event {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        let name      = &self.name;
+        let instances = self.instances;
+        let bt        = &self.backtrace;
+        match (self.kind,self.new_id) {
+            (EventKind::Drop,_) =>
+                write!(f,"[{}] Dropping {}; instances left: {} {}",name,self.clone_id,instances,bt),
+            (kind,new_id) => {
+                let op = if kind == EventKind::Clone { "Cloning" } else { "Cloning (ref)" };
+                let to = new_id.unwrap_or_default();
+                write!(f,"[{}] {} {} -> {}; instances: {} {}",name,op,self.clone_id,to,instances,bt)
+            }
+        }
+    }
+}
+
+
+
+// ============
+// === Sink ===
+// ============
+
+/// Function receiving the events of traced entities.
+pub type Sink = Rc<dyn Fn(&Event)>;
+
+thread_local! {
+    static SINK : RefCell<Option<Sink>> = default();
+}
+
+/// Redirect the events of all `TraceCopies` in the current thread to the provided function. It can
+/// be used to pass the events to a logger, for example:
+///
+/// ```ignore
+/// trace_copies::set_sink(move |event| trace!(logger,"{event}"));
+/// ```
+pub fn set_sink(sink:impl Fn(&Event) + 'static) {
+    SINK.with(|s| *s.borrow_mut() = Some(Rc::new(sink)));
+}
+
+/// Restore the default sink, which reports the events with the `TRACE!` debug macro.
+pub fn reset_sink() {
+    SINK.with(|s| *s.borrow_mut() = None);
+}
+
+fn emit(mut event:Event) {
+    let config = CONFIG.with(|c| c.borrow().clone());
+    if !config.full_backtraces {
+        event.backtrace = trim_internal_frames(&event.backtrace);
+    }
+    if let Some(pattern) = &config.filter {
+        if !event.backtrace.contains(pattern.as_str()) { return }
+    }
+    let sink = SINK.with(|s| s.borrow().clone());
+    match sink {
+        Some(sink) => sink(&event),
+        None       => logging::trace(event.to_string()),
+    }
+}
+
+
+
+// ==============
+// === Config ===
+// ==============
+
+/// Configuration of the events reported in the current thread.
+#[derive(Clone,Debug,Default)]
+struct Config {
+    filter          : Option<String>,
+    full_backtraces : bool,
+}
+
+thread_local! {
+    static CONFIG : RefCell<Config> = default();
+}
+
+/// Report only the events whose backtrace contains the given pattern, for example a name of a
+/// function which clones the entity. Copies are still counted in the registry, see `report`.
+pub fn set_filter(pattern:impl Into<String>) {
+    CONFIG.with(|c| c.borrow_mut().filter = Some(pattern.into()));
+}
+
+/// Report all events. See `set_filter` to learn more.
+pub fn clear_filter() {
+    CONFIG.with(|c| c.borrow_mut().filter = None);
+}
+
+/// By default, the frames of the backtrace machinery and of this module are removed from the
+/// reported backtraces, so they start at the code which cloned or dropped the entity. This
+/// function allows disabling the trimming.
+pub fn set_full_backtraces(enabled:bool) {
+    CONFIG.with(|c| c.borrow_mut().full_backtraces = enabled);
+}
+
+
+
+// ==========================
+// === Backtrace Trimming ===
+// ==========================
+
+/// Remove the leading frames belonging to the backtrace machinery and to the `debug` module. Both
+/// native backtraces, where frames start with lines like `  3: path`, and JavaScript stacks, where
+/// each `at path` line is a frame, are supported. Lines preceding the first frame are kept.
+fn trim_internal_frames(backtrace:&str) -> String {
+    let lines     = backtrace.lines().collect_vec();
+    let is_native = lines.iter().any(|line| is_native_frame_header(line));
+    let is_header = |line:&str| {
+        if is_native { is_native_frame_header(line) } else { line.trim_start().starts_with("at ") }
+    };
+    let first_frame = lines.iter().position(|line| is_header(line)).unwrap_or(lines.len());
+    let mut result  = lines[..first_frame].to_vec();
+    let mut frames  = Vec::<Vec<&str>>::new();
+    for line in &lines[first_frame..] {
+        match frames.last_mut() {
+            Some(frame) if !is_header(line) => frame.push(line),
+            _                               => frames.push(vec![line]),
+        }
+    }
+    let is_internal = |frame:&Vec<&str>| {
+        INTERNAL_FRAME_MARKERS.iter().any(|marker| frame[0].contains(marker))
+    };
+    result.extend(frames.into_iter().skip_while(is_internal).flatten());
+    result.join("\n")
+}
+
+fn is_native_frame_header(line:&str) -> bool {
+    let line   = line.trim_start();
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && line[digits..].starts_with(':')
+}
+
+
+
+// ================
+// === Registry ===
+// ================
+
+thread_local! {
+    static REGISTRY : RefCell<HashMap<ImString,usize>> = default();
+}
+
+fn register(name:&ImString, count:usize) {
+    REGISTRY.with(|r| *r.borrow_mut().entry(name.clone()).or_default() += count);
+}
+
+fn unregister(name:&ImString, count:usize) {
+    REGISTRY.with(|r| {
+        let mut registry = r.borrow_mut();
+        if let Some(instances) = registry.get_mut(name) {
+            *instances = instances.saturating_sub(count);
+            if *instances == 0 {
+                registry.remove(name);
+            }
+        }
+    })
+}
+
+/// Number of live copies of enabled `TraceCopies` in the current thread, per entity name.
+pub fn report() -> BTreeMap<String,usize> {
+    REGISTRY.with(|r| r.borrow().iter().map(|(name,count)| (name.to_string(),*count)).collect())
+}
+
+/// Number of live copies of the entity with the given name in the current thread.
+pub fn live_instances(name:&str) -> usize {
+    REGISTRY.with(|r| r.borrow().get(&ImString::new(name)).copied().unwrap_or_default())
+}
+
+/// Panic if there is any live copy of the entity with the given name in the current thread.
+pub fn assert_no_leaks(name:&str) {
+    let instances = live_instances(name);
+    assert_eq!(instances,0,"Entity '{}' leaked: {} instance(s) alive.",name,instances);
+}
+
+
+
+// ===================
+// === TraceCopies ===
+// ===================
+
+/// An utility for tracing all copies of CloneRef-able entity.
+///
+/// This structure should be added as a field to structure implementing Clone or CloneRef. It will
+/// mark each copy with unique id (the original copy has id of 0). Once enabled, it will report
+/// an `Event` with the backtrace of each clone, clone_ref or drop operation with assigned name
+/// (the same for all copies) and copy id. See `set_sink` to learn where the events go. Live copies
+/// of enabled entities are counted per name, see `report` and `assert_no_leaks`.
+#[derive(Debug,Default)]
+pub struct TraceCopies {
+    clone_id : u64,
+    handle   : Rc<RefCell<Option<ImString>>>,
+}
+
+thread_local! {
+    static NEXT_CLONE_ID : Cell<u64> = Cell::new(1);
+}
+
+fn next_clone_id() -> u64 {
+    NEXT_CLONE_ID.with(|id| {
+        let next = id.get();
+        id.set(next+1);
+        next
+    })
+}
+
+impl TraceCopies {
+    /// Create enabled structure with appointed entity name (shared between all copies).
+    pub fn enabled(name:impl Into<ImString>) -> Self {
+        let name = name.into();
+        register(&name,1);
+        Self {
+            clone_id : default(),
+            handle   : Rc::new(RefCell::new(Some(name))),
+        }
+    }
+
+    /// Assign a name to the entity (shared between all copies) and start reporting events.
+    pub fn enable(&self, name: impl Into<ImString>) {
+        let name      = name.into();
+        let instances = Rc::strong_count(&self.handle);
+        register(&name,instances);
+        if let Some(old_name) = self.handle.borrow_mut().replace(name) {
+            unregister(&old_name,instances);
+        }
+    }
+
+    /// Id of this copy. The original copy has id of 0.
+    pub fn clone_id(&self) -> u64 {
+        self.clone_id
+    }
+
+    fn copy(&self, kind:EventKind) -> Self {
+        let clone_id = next_clone_id();
+        let handle   = self.handle.clone_ref();
+        let name     = self.handle.borrow().clone();
+        if let Some(name) = name {
+            register(&name,1);
+            let new_id    = Some(clone_id);
+            let instances = Rc::strong_count(&handle);
+            let backtrace = backtrace();
+            emit(Event {name,kind,clone_id:self.clone_id,new_id,instances,backtrace});
+        }
+        Self {clone_id,handle}
+    }
+}
+
+impl Clone for TraceCopies {
+    fn clone(&self) -> Self {
+        self.copy(EventKind::Clone)
+    }
+}
+
+impl CloneRef for TraceCopies {
+    fn clone_ref(&self) -> Self {
+        self.copy(EventKind::CloneRef)
+    }
+}
+
+impl Drop for TraceCopies {
+    fn drop(&mut self) {
+        let name = self.handle.borrow().clone();
+        if let Some(name) = name {
+            unregister(&name,1);
+            let kind      = EventKind::Drop;
+            let clone_id  = self.clone_id;
+            let new_id    = None;
+            let instances = Rc::strong_count(&self.handle) - 1;
+            let backtrace = backtrace();
+            emit(Event {name,kind,clone_id,new_id,instances,backtrace});
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        set_sink(f!([events](event:&Event)
+            events.borrow_mut().push((event.kind,event.clone_id,event.new_id,event.instances))
+        ));
+        let original = TraceCopies::enabled("test");
+        let copy     = original.clone();
+        let copy_ref = copy.clone_ref();
+        drop(copy);
+        drop(copy_ref);
+        drop(original);
+        reset_sink();
+
+        let events   = events.borrow();
+        let copy_id  = events[0].2.unwrap();
+        let ref_id   = events[1].2.unwrap();
+        assert_eq!(events.len(),5);
+        assert_eq!(events[0],(EventKind::Clone    ,0       ,Some(copy_id),2));
+        assert_eq!(events[1],(EventKind::CloneRef ,copy_id ,Some(ref_id) ,3));
+        assert_eq!(events[2],(EventKind::Drop     ,copy_id ,None         ,2));
+        assert_eq!(events[3],(EventKind::Drop     ,ref_id  ,None         ,1));
+        assert_eq!(events[4],(EventKind::Drop     ,0       ,None         ,0));
+    }
+
+    #[test]
+    fn leak_report() {
+        set_sink(|_| {});
+        let original = TraceCopies::enabled("leak_report");
+        let copy     = original.clone_ref();
+        let other    = TraceCopies::default();
+        let copy2    = other.clone();
+        assert_eq!(live_instances("leak_report"),2);
+        other.enable("leak_report_other");
+        assert_eq!(report().get("leak_report_other"),Some(&2));
+        drop(copy);
+        assert_eq!(report().get("leak_report"),Some(&1));
+        drop(original);
+        assert_no_leaks("leak_report");
+        assert_eq!(report().get("leak_report"),None);
+        other.enable("leak_report");
+        assert_eq!(live_instances("leak_report"),2);
+        assert_no_leaks("leak_report_other");
+        drop(copy2);
+        drop(other);
+        assert_no_leaks("leak_report");
+        reset_sink();
+    }
+
+    #[test]
+    #[should_panic]
+    fn leak_assertion() {
+        set_sink(|_| {});
+        let leaked = TraceCopies::enabled("leak_assertion");
+        mem::forget(leaked);
+        assert_no_leaks("leak_assertion");
+    }
+
+    #[test]
+    fn trimming_native_backtrace() {
+        let backtrace = "stack backtrace:
+   0: backtrace::capture::Backtrace::new
+             at backtrace/src/capture.rs:140:20
+   1: enso_prelude::debug::internal::backtrace
+             at src/prelude/src/debug.rs:38:18
+   2: app::Node::clone
+             at src/node.rs:10:5
+   3: enso_prelude::debug::other
+             at src/prelude/src/debug.rs:50:18";
+        let expected = "stack backtrace:
+   2: app::Node::clone
+             at src/node.rs:10:5
+   3: enso_prelude::debug::other
+             at src/prelude/src/debug.rs:50:18";
+        assert_eq!(trim_internal_frames(backtrace),expected);
+    }
+
+    #[test]
+    fn trimming_js_stack() {
+        let backtrace = "Error
+    at imports.wbg.__wbg_new_59cb74e423758ede (http://localhost/app.js:1:2)
+    at enso_prelude::debug::internal::backtrace::h8b2 (wasm-function[5]:0x1)
+    at app::Node::clone::h1f3 (wasm-function[6]:0x2)";
+        let expected = "Error
+    at app::Node::clone::h1f3 (wasm-function[6]:0x2)";
+        assert_eq!(trim_internal_frames(backtrace),expected);
+    }
+
+    #[test]
+    fn filtering() {
+        let events = Rc::new(Cell::new(0));
+        set_sink(f!([events](_:&Event) events.set(events.get() + 1)));
+        set_filter("no_function_has_such_name");
+        let original = TraceCopies::enabled("filtering");
+        let copy     = original.clone();
+        assert_eq!(events.get(),0);
+        assert_eq!(live_instances("filtering"),2);
+        clear_filter();
+        drop(copy);
+        assert_eq!(events.get(),1);
+        drop(original);
+        reset_sink();
+    }
+
+    #[test]
+    fn disabled_copies_are_silent() {
+        let events = Rc::new(Cell::new(0));
+        set_sink(f!([events](_:&Event) events.set(events.get() + 1)));
+        let original = TraceCopies::default();
+        let copy     = original.clone();
+        drop(copy);
+        drop(original);
+        reset_sink();
+        assert_eq!(events.get(),0);
+    }
+}
@@ -0,0 +1,137 @@
+//! Utilities for dropping deeply nested structures without recursion. The default drop glue of
+//! structures like trees or linked lists recurses once per nesting level, which can overflow the
+//! stack, especially on wasm, where the stack is small.
+
+use std::rc::Rc;
+
+
+
+// ================
+// === DeepDrop ===
+// ================
+
+/// A structure owning children of the `Child` type, which can be dropped iteratively. Implement
+/// `Drop` with `drop_children` to use it for the default drop glue:
+///
+/// ```
+/// # use enso_prelude::*;
+/// struct Node {
+///     next : Option<Box<Node>>,
+/// }
+///
+/// impl DeepDrop for Node {
+///     type Child = Box<Node>;
+///     fn take_children(&mut self, children:&mut Vec<Box<Node>>) {
+///         children.extend(self.next.take());
+///     }
+/// }
+///
+/// impl Drop for Node {
+///     fn drop(&mut self) {
+///         self.drop_children()
+///     }
+/// }
+///
+/// let mut list = Node {next:None};
+/// for _ in 0..1_000_000 {
+///     list = Node {next:Some(Box::new(list))};
+/// }
+/// drop(list);
+/// ```
+pub trait DeepDrop {
+    /// Type of the directly owned children.
+    type Child : DeepDrop<Child=Self::Child>;
+
+    /// Move all directly owned children out of this value, so it can be dropped without recursion.
+    fn take_children(&mut self, children:&mut Vec<Self::Child>);
+
+    /// Drop all children of this value iteratively. Meant to be called in `Drop::drop`.
+    fn drop_children(&mut self) {
+        let mut children = Vec::new();
+        self.take_children(&mut children);
+        iter_drop_all(children);
+    }
+}
+
+impl<T:DeepDrop<Child=Box<T>>> DeepDrop for Box<T> {
+    type Child = Box<T>;
+    fn take_children(&mut self, children:&mut Vec<Box<T>>) {
+        (**self).take_children(children)
+    }
+}
+
+/// Children are taken only from the last reference to the value. Values shared with other
+/// references are not dropped, so their children are left untouched.
+impl<T:DeepDrop<Child=Rc<T>>> DeepDrop for Rc<T> {
+    type Child = Rc<T>;
+    fn take_children(&mut self, children:&mut Vec<Rc<T>>) {
+        if let Some(value) = Rc::get_mut(self) {
+            value.take_children(children)
+        }
+    }
+}
+
+
+
+// =================
+// === iter_drop ===
+// =================
+
+/// Drop the value and all of its descendants iteratively. Every value has its children taken
+/// before it is dropped, so the stack depth does not depend on the depth of the structure.
+pub fn iter_drop<T:DeepDrop<Child=T>>(value:T) {
+    iter_drop_all(vec![value])
+}
+
+/// Drop all the values and their descendants iteratively. See `iter_drop` to learn more.
+pub fn iter_drop_all<T:DeepDrop<Child=T>>(mut stack:Vec<T>) {
+    while let Some(mut value) = stack.pop() {
+        value.take_children(&mut stack);
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node {
+        children : Vec<Rc<Node>>,
+    }
+
+    impl DeepDrop for Node {
+        type Child = Rc<Node>;
+        fn take_children(&mut self, children:&mut Vec<Rc<Node>>) {
+            children.append(&mut self.children);
+        }
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            self.drop_children()
+        }
+    }
+
+    #[test]
+    fn deep_rc_chain() {
+        let mut node = Rc::new(Node {children:vec![]});
+        for _ in 0..1_000_000 {
+            node = Rc::new(Node {children:vec![node]});
+        }
+        iter_drop(node);
+    }
+
+    #[test]
+    fn shared_children_are_kept() {
+        let shared = Rc::new(Node {children:vec![Rc::new(Node {children:vec![]})]});
+        let parent = Rc::new(Node {children:vec![shared.clone()]});
+        iter_drop(parent);
+        assert_eq!(Rc::strong_count(&shared),1);
+        assert_eq!(shared.children.len(),1);
+    }
+}
@@ -0,0 +1,213 @@
+//! A lightweight error type with context messages and source chaining. It does not depend on
+//! `std::backtrace`, so it works the same way on native and wasm targets.
+
+use crate::*;
+
+use std::error::Error as StdError;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// Result of a fallible operation. See `Error` to learn more.
+pub type FallibleResult<T=()> = Result<T,Error>;
+
+/// An error message with an optional cause. Any `std::error::Error` can be converted to it, so it
+/// can be used with the `?` operator, and the context of the failure can be attached with the
+/// `ResultContext` methods:
+///
+/// ```
+/// # use enso_prelude::*;
+/// fn parse(text:&str) -> FallibleResult<usize> {
+///     Ok(text.parse::<usize>().context("parsing the node id")?)
+/// }
+///
+/// let error = parse("x").unwrap_err();
+/// assert_eq!(error.to_string(),"parsing the node id");
+/// assert_eq!(format!("{:#}",error),"parsing the node id: invalid digit found in string");
+/// ```
+///
+/// The `Display` implementation prints the outermost message only, and its alternate form (`{:#}`)
+/// prints the whole chain. This type does not implement `std::error::Error` itself, as it would
+/// conflict with the conversion from all such types.
+pub struct Error {
+    message : String,
+    cause   : Option<Box<Error>>,
+    origin  : Option<Box<dyn StdError>>,
+}
+
+impl Error {
+    /// Constructor.
+    pub fn new(message:impl Into<String>) -> Self {
+        let message = message.into();
+        let cause   = None;
+        let origin  = None;
+        Self {message,cause,origin}
+    }
+
+    /// Wrap this error in a new one with the provided message.
+    pub fn context(self, message:impl Into<String>) -> Self {
+        let mut error = Self::new(message);
+        error.cause   = Some(Box::new(self));
+        error
+    }
+
+    /// The message of this error, without its causes.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The direct cause of this error.
+    pub fn cause(&self) -> Option<&Error> {
+        self.cause.as_deref()
+    }
+
+    /// Iterator over this error and all of its causes, from the outermost one.
+    pub fn chain(&self) -> impl Iterator<Item=&Error> {
+        std::iter::successors(Some(self),|error| error.cause())
+    }
+
+    /// The innermost cause of this error.
+    pub fn root_cause(&self) -> &Error {
+        self.chain().last().unwrap_or(self)
+    }
+
+    /// Find the original error of the given type in the chain, if this error was created from it.
+    pub fn downcast_ref<E:StdError+'static>(&self) -> Option<&E> {
+        self.chain().find_map(|error| error.origin.as_ref()?.downcast_ref())
+    }
+}
+
+impl<E:StdError+'static> From<E> for Error {
+    fn from(error:E) -> Self {
+        let mut sources = Vec::new();
+        let mut source  = error.source();
+        while let Some(current) = source {
+            sources.push(current.to_string());
+            source = current.source();
+        }
+        let cause = sources.into_iter().rev().fold(None,|cause,message| {
+            let origin = None;
+            Some(Box::new(Self {message,cause,origin}))
+        });
+        let message = error.to_string();
+        let origin  = Some(Box::new(error) as Box<dyn StdError>);
+        Self {message,cause,origin}
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f,"{}",self.chain().map(|error| &error.message).join(": "))
+        } else {
+            write!(f,"{}",self.message)
+        }
+    }
+}
+
+impl Debug for Error {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{}",self.message)?;
+        for (index,cause) in self.chain().skip(1).enumerate() {
+            write!(f,"\n    {}: {}",index,cause.message)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+// =====================
+// === ResultContext ===
+// =====================
+
+/// Attaching context to failures of `Result` and `Option`.
+pub trait ResultContext {
+    /// The success type.
+    type Item;
+
+    /// Convert the failure to an `Error` wrapped in the provided context message.
+    fn context(self, message:impl Into<String>) -> FallibleResult<Self::Item>;
+
+    /// Like `context`, but the message is computed only in case of a failure.
+    fn with_context<S:Into<String>>(self, f:impl FnOnce() -> S) -> FallibleResult<Self::Item>;
+}
+
+impl<T,E:Into<Error>> ResultContext for Result<T,E> {
+    type Item = T;
+
+    fn context(self, message:impl Into<String>) -> FallibleResult<T> {
+        self.map_err(|error| error.into().context(message))
+    }
+
+    fn with_context<S:Into<String>>(self, f:impl FnOnce() -> S) -> FallibleResult<T> {
+        self.map_err(|error| error.into().context(f()))
+    }
+}
+
+/// A `None` value is converted to an `Error` with the context message only.
+impl<T> ResultContext for Option<T> {
+    type Item = T;
+
+    fn context(self, message:impl Into<String>) -> FallibleResult<T> {
+        self.ok_or_else(|| Error::new(message))
+    }
+
+    fn with_context<S:Into<String>>(self, f:impl FnOnce() -> S) -> FallibleResult<T> {
+        self.ok_or_else(|| Error::new(f()))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Outer(std::num::ParseIntError);
+
+    impl Display for Outer {
+        fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f,"outer")
+        }
+    }
+
+    impl StdError for Outer {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    fn load(text:&str) -> FallibleResult<usize> {
+        let number = text.parse::<usize>().map_err(Outer).context("loading module")?;
+        Ok(number)
+    }
+
+    #[test]
+    fn context_and_chain() {
+        assert_eq!(load("1").unwrap(),1);
+        let error    = load("x").unwrap_err();
+        let messages = error.chain().map(|t| t.message()).collect_vec();
+        assert_eq!(messages,vec!["loading module","outer","invalid digit found in string"]);
+        assert_eq!(error.root_cause().message(),"invalid digit found in string");
+        assert!(error.downcast_ref::<Outer>().is_some());
+        assert!(error.downcast_ref::<std::fmt::Error>().is_none());
+        assert_eq!(format!("{:?}",error),
+            "loading module\n    0: outer\n    1: invalid digit found in string");
+    }
+
+    #[test]
+    fn option_context() {
+        let error = None::<usize>.with_context(|| "missing node").unwrap_err();
+        assert_eq!(format!("{:#}",error),"missing node");
+        assert_eq!(Some(1).context("missing node").unwrap(),1);
+    }
+}
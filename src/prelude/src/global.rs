@@ -0,0 +1,62 @@
+//! Safe global values, replacing the unsound `static mut` pattern.
+
+
+
+// =====================
+// === define_global ===
+// =====================
+
+/// Define a lazily initialized global value, accessed with the `with` and `with_mut` functions of
+/// the generated unit struct. For example:
+///
+/// ```
+/// # use enso_prelude::*;
+/// define_global! {
+///     /// Number of created nodes.
+///     pub NodeCount : usize = 0;
+/// }
+///
+/// NodeCount::with_mut(|count| *count += 1);
+/// assert_eq!(NodeCount::with(|count| *count),1);
+/// ```
+///
+/// The initializer is optional, the `Default` value is used if it is not provided. The value is
+/// created on the first access.
+///
+/// The value is stored in a thread-local variable, so on native targets every thread has its own
+/// instance and the type does not need to be `Send` or `Sync`. On wasm, which is single-threaded,
+/// it is compiled to a plain static variable. The value can not be accessed from within its own
+/// `with_mut` closure, as it would create two mutable references to it. Such access panics.
+#[macro_export]
+macro_rules! define_global {
+    ($(#[$meta:meta])* $vis:vis $name:ident : $tp:ty $(= $init:expr)?;) => {
+        $(#[$meta])*
+        #[derive(Copy,Clone,Debug,Default)]
+        $vis struct $name;
+
+        impl $name {
+            fn cell<R>(f:impl FnOnce(&std::cell::RefCell<$tp>) -> R) -> R {
+                thread_local! {
+                    static VALUE : std::cell::RefCell<$tp> =
+                        std::cell::RefCell::new($crate::define_global!{@init $($init)?});
+                }
+                VALUE.with(f)
+            }
+
+            /// Run the function with a reference to the global value.
+            #[allow(dead_code)]
+            $vis fn with<R>(f:impl FnOnce(&$tp) -> R) -> R {
+                Self::cell(|cell| f(&cell.borrow()))
+            }
+
+            /// Run the function with a mutable reference to the global value.
+            #[allow(dead_code)]
+            $vis fn with_mut<R>(f:impl FnOnce(&mut $tp) -> R) -> R {
+                Self::cell(|cell| f(&mut cell.borrow_mut()))
+            }
+        }
+    };
+
+    (@init) => { Default::default() };
+    (@init $init:expr) => { $init };
+}
@@ -0,0 +1,102 @@
+//! Scope guards, running cleanup code when the scope is left, including early returns and panics.
+
+
+
+// ==================
+// === ScopeGuard ===
+// ==================
+
+/// Runs the provided function when dropped, unless it was cancelled. See `defer` to learn more.
+#[must_use = "The guard runs the function immediately if it is not bound to a variable."]
+pub struct ScopeGuard<F:FnOnce()> {
+    action : Option<F>,
+}
+
+impl<F:FnOnce()> ScopeGuard<F> {
+    /// Constructor.
+    pub fn new(action:F) -> Self {
+        let action = Some(action);
+        Self {action}
+    }
+
+    /// Disarm the guard, so the function will not be run.
+    pub fn cancel(mut self) {
+        self.action = None;
+    }
+}
+
+impl<F:FnOnce()> Drop for ScopeGuard<F> {
+    fn drop(&mut self) {
+        if let Some(action) = self.action.take() {
+            action()
+        }
+    }
+}
+
+impl<F:FnOnce()> std::fmt::Debug for ScopeGuard<F> {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"ScopeGuard")
+    }
+}
+
+/// Run the function when the returned guard goes out of scope, for example:
+///
+/// ```
+/// # use enso_prelude::*;
+/// let log = RefCell::new(Vec::new());
+/// {
+///     let _guard = defer(|| log.borrow_mut().push("cleanup"));
+///     log.borrow_mut().push("work");
+/// }
+/// assert_eq!(*log.borrow(),vec!["work","cleanup"]);
+/// ```
+///
+/// Remember to bind the guard to a named variable. Binding it to `_` drops it immediately.
+pub fn defer<F:FnOnce()>(action:F) -> ScopeGuard<F> {
+    ScopeGuard::new(action)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn early_return(counter:&Cell<usize>, exit_early:bool) -> usize {
+        let _guard = defer(|| counter.set(counter.get() + 1));
+        if exit_early { return 0 }
+        1
+    }
+
+    #[test]
+    fn runs_on_every_exit_path() {
+        let counter = Cell::new(0);
+        early_return(&counter,true);
+        early_return(&counter,false);
+        assert_eq!(counter.get(),2);
+    }
+
+    #[test]
+    fn runs_on_panic() {
+        let counter = Cell::new(0);
+        let result  = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = defer(|| counter.set(counter.get() + 1));
+            panic!("test");
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(),1);
+    }
+
+    #[test]
+    fn cancel() {
+        let counter = Cell::new(0);
+        let guard   = defer(|| counter.set(counter.get() + 1));
+        guard.cancel();
+        assert_eq!(counter.get(),0);
+    }
+}
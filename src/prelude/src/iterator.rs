@@ -0,0 +1,135 @@
+//! This module defines utilities for working with iterators.
+
+use std::fmt;
+use std::iter::Peekable;
+
+
+
+// ===================
+// === IteratorOps ===
+// ===================
+
+/// Adds utilities to all iterators.
+pub trait IteratorOps : Iterator + Sized {
+    /// Collect the `Ok` values into a vector, stopping at the first error and returning it.
+    fn try_collect_vec<T,E>(self) -> Result<Vec<T>,E>
+    where Self : Iterator<Item=Result<T,E>> {
+        self.collect()
+    }
+
+    /// Group the consecutive elements with equal keys. Returns the key of every group along with
+    /// its elements. Unlike [`itertools::Itertools::group_by`], the groups are owned vectors, so
+    /// the iterator can be used without borrowing an intermediate value.
+    fn group_consecutive_by<K,F>(self, key:F) -> GroupConsecutiveBy<Self,F>
+    where K:PartialEq, F:FnMut(&Self::Item) -> K {
+        let iter = self.peekable();
+        GroupConsecutiveBy {iter,key}
+    }
+
+    /// Split the elements into chunks, which continue as long as the predicate holds for every two
+    /// neighbor elements. For example, chunking `[1,2,3,5,6]` while the next element is bigger by
+    /// one than the previous one gives `[[1,2,3],[5,6]]`.
+    fn chunk_while<F>(self, pred:F) -> ChunkWhile<Self,F>
+    where F:FnMut(&Self::Item,&Self::Item) -> bool {
+        let iter = self.peekable();
+        ChunkWhile {iter,pred}
+    }
+}
+
+impl<I:Iterator> IteratorOps for I {}
+
+
+
+// ==========================
+// === GroupConsecutiveBy ===
+// ==========================
+
+/// Iterator returned by [`IteratorOps::group_consecutive_by`].
+pub struct GroupConsecutiveBy<I:Iterator,F> {
+    iter : Peekable<I>,
+    key  : F,
+}
+
+impl<I,K,F> Iterator for GroupConsecutiveBy<I,F>
+where I:Iterator, K:PartialEq, F:FnMut(&I::Item) -> K {
+    type Item = (K,Vec<I::Item>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let first     = self.iter.next()?;
+        let key_fn    = &mut self.key;
+        let key       = key_fn(&first);
+        let mut group = vec![first];
+        while let Some(item) = self.iter.next_if(|item| key_fn(item) == key) {
+            group.push(item)
+        }
+        Some((key,group))
+    }
+}
+
+impl<I:Iterator,F> fmt::Debug for GroupConsecutiveBy<I,F> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"GroupConsecutiveBy")
+    }
+}
+
+
+
+// ==================
+// === ChunkWhile ===
+// ==================
+
+/// Iterator returned by [`IteratorOps::chunk_while`].
+pub struct ChunkWhile<I:Iterator,F> {
+    iter : Peekable<I>,
+    pred : F,
+}
+
+impl<I,F> Iterator for ChunkWhile<I,F>
+where I:Iterator, F:FnMut(&I::Item,&I::Item) -> bool {
+    type Item = Vec<I::Item>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = vec![self.iter.next()?];
+        let pred      = &mut self.pred;
+        while let Some(item) = self.iter.next_if(|item| pred(chunk.last().unwrap(),item)) {
+            chunk.push(item)
+        }
+        Some(chunk)
+    }
+}
+
+impl<I:Iterator,F> fmt::Debug for ChunkWhile<I,F> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"ChunkWhile")
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_collect_vec() {
+        let ok  : Vec<Result<usize,&str>> = vec![Ok(1),Ok(2)];
+        let err : Vec<Result<usize,&str>> = vec![Ok(1),Err("first"),Err("second")];
+        assert_eq!(ok.into_iter().try_collect_vec(),Ok(vec![1,2]));
+        assert_eq!(err.into_iter().try_collect_vec(),Err("first"));
+    }
+
+    #[test]
+    fn test_group_consecutive_by() {
+        let groups = vec![1,3,2,4,6,5].into_iter().group_consecutive_by(|t| t % 2);
+        assert_eq!(groups.collect::<Vec<_>>(),vec![(1,vec![1,3]),(0,vec![2,4,6]),(1,vec![5])]);
+    }
+
+    #[test]
+    fn test_chunk_while() {
+        let chunks = vec![1,2,3,5,6,8].into_iter().chunk_while(|a,b| a + 1 == *b);
+        assert_eq!(chunks.collect::<Vec<_>>(),vec![vec![1,2,3],vec![5,6],vec![8]]);
+        assert_eq!(Vec::<usize>::new().into_iter().chunk_while(|_,_| true).count(),0);
+    }
+}
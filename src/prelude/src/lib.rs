@@ -15,6 +15,11 @@ mod clone;
 mod collections;
 mod data;
 pub mod debug;
+mod deep_drop;
+pub mod error;
+mod global;
+mod guard;
+mod iterator;
 mod macros;
 mod option;
 mod phantom;
@@ -25,6 +30,7 @@ mod smallvec;
 mod std_reexports;
 mod string;
 mod switch;
+mod time;
 mod tp;
 mod vec;
 mod wrapper;
@@ -33,6 +39,11 @@ pub use debug::*;
 pub use clone::*;
 pub use collections::*;
 pub use data::*;
+pub use deep_drop::*;
+pub use error::FallibleResult;
+pub use error::ResultContext;
+pub use guard::*;
+pub use iterator::*;
 pub use macros::*;
 pub use crate::smallvec::*;
 pub use option::*;
@@ -43,6 +54,7 @@ pub use result::*;
 pub use std_reexports::*;
 pub use string::*;
 pub use switch::*;
+pub use time::*;
 pub use tp::*;
 pub use vec::*;
 pub use wrapper::*;
@@ -242,6 +254,8 @@ impl<T:Default> Default for CloneCell<T> {
 // === CloneRefCell ===
 // ====================
 
+/// A cell allowing replacing its content, which is cloned with `CloneRef` when accessed. Together
+/// with `WeakCloneRef` it is the preferred way to store replaceable back-references.
 #[derive(Debug)]
 pub struct CloneRefCell<T:?Sized> {
     data : UnsafeCell<T>
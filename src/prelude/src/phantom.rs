@@ -2,6 +2,8 @@
 
 use super::std_reexports::*;
 use derivative::Derivative;
+use std::cmp::Ordering;
+use std::hash::Hasher;
 use shrinkwraprs::Shrinkwrap;
 
 
@@ -50,6 +52,57 @@ impl<T, P> WithPhantom<T, P> {
 
 
 
+// ===========
+// === ZST ===
+// ===========
+
+/// A zero-sized marker of the `T` type, like `PhantomData<T>`, but implementing all the common
+/// traits regardless of `T`. Generic types can use it for phantom parameters and derive their
+/// traits without hand-writing bounds, as `T` does not need to implement the derived traits.
+pub struct ZST<T:?Sized>(PhantomData<T>);
+
+impl<T:?Sized> ZST<T> {
+    /// Constructor.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T:?Sized> Copy    for ZST<T> {}
+impl<T:?Sized> Eq      for ZST<T> {}
+impl<T:?Sized> Clone   for ZST<T> { fn clone   (&self) -> Self { *self } }
+impl<T:?Sized> Default for ZST<T> { fn default ()      -> Self { Self::new() } }
+
+impl<T:?Sized> PartialEq for ZST<T> {
+    fn eq(&self, _:&Self) -> bool { true }
+}
+
+impl<T:?Sized> PartialOrd for ZST<T> {
+    fn partial_cmp(&self, other:&Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<T:?Sized> Ord for ZST<T> {
+    fn cmp(&self, _:&Self) -> Ordering { Ordering::Equal }
+}
+
+impl<T:?Sized> Hash for ZST<T> {
+    fn hash<H:Hasher>(&self, _:&mut H) {}
+}
+
+impl<T:?Sized> Debug for ZST<T> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"ZST<{}>",std::any::type_name::<T>())
+    }
+}
+
+impl<T:?Sized> From<PhantomData<T>> for ZST<T> {
+    fn from(_:PhantomData<T>) -> Self {
+        Self::new()
+    }
+}
+
+
+
 // ==========================
 // === PhantomConversions ===
 // ==========================
@@ -92,3 +145,30 @@ impl<T> PhantomConversions for T {}
 
 /// Like `Into` but for phantom types.
 pub trait PhantomInto<T> = where PhantomData<Self>: Into<T>;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoTraits;
+
+    #[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+    struct Marked {
+        marker : ZST<NoTraits>,
+    }
+
+    #[test]
+    fn zst_traits_do_not_depend_on_parameter() {
+        let marked = Marked::default();
+        let copy   = marked;
+        assert_eq!(marked,copy);
+        assert_eq!(std::mem::size_of::<Marked>(),0);
+        assert!(format!("{:?}",marked).contains("NoTraits"));
+    }
+}
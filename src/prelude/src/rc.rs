@@ -1,9 +1,11 @@
 //! This module defines utilities for working with the `Rc` and `Weak` types.
 
-use std::rc::Rc;
-use std::rc::Weak;
+use crate::*;
+
 use super::option::*;
 
+use std::panic::Location;
+
 // TODO[WD,AO]: Think about merging it with `OptionOps`.
 /// Mapping methods to the `Weak` type.
 pub trait WeakOps {
@@ -23,3 +25,262 @@ impl<T> WeakOps for Weak<T> {
         self.upgrade().for_each_ref(f)
     }
 }
+
+
+
+// ====================
+// === WeakCloneRef ===
+// ====================
+
+/// A weak reference to any `StrongRef`, like `Rc` or newtypes over it. Use it to store
+/// back-references, like a child pointing to its parent, without creating reference cycles. To
+/// store a reference which can be replaced later, wrap it in a `CloneRefCell`.
+///
+/// A reference created with `WeakCloneRef::traced` carries an enabled `TraceCopies`, so all of its
+/// copies are counted in the `trace_copies` registry. When an object is not freed, comparing the
+/// numbers of live strong and weak handles helps to find the owner that closes a cycle.
+pub struct WeakCloneRef<T:StrongRef> {
+    weak  : T::WeakRef,
+    trace : Option<TraceCopies>,
+}
+
+impl<T:StrongRef> WeakCloneRef<T> {
+    /// Constructor.
+    pub fn new(strong:&T) -> Self {
+        let weak  = strong.downgrade();
+        let trace = None;
+        Self {weak,trace}
+    }
+
+    /// Constructor of a reference whose copies are traced with `TraceCopies` with the given name.
+    pub fn traced(strong:&T, name:impl Into<ImString>) -> Self {
+        let weak  = strong.downgrade();
+        let trace = Some(TraceCopies::enabled(name));
+        Self {weak,trace}
+    }
+
+    /// Attempt to upgrade to a strong reference.
+    pub fn upgrade(&self) -> Option<T> {
+        self.weak.upgrade()
+    }
+
+    /// Run the function with the upgraded reference, if the referenced object is still alive.
+    pub fn with<R>(&self, f:impl FnOnce(T) -> R) -> Option<R> {
+        self.upgrade().map(f)
+    }
+
+    /// Check whether the referenced object is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.upgrade().is_some()
+    }
+}
+
+impl<T:StrongRef> Clone for WeakCloneRef<T> {
+    fn clone(&self) -> Self {
+        let weak  = self.weak.clone_ref();
+        let trace = self.trace.clone();
+        Self {weak,trace}
+    }
+}
+
+impl<T:StrongRef> CloneRef for WeakCloneRef<T> {
+    fn clone_ref(&self) -> Self {
+        let weak  = self.weak.clone_ref();
+        let trace = self.trace.as_ref().map(|trace| trace.clone_ref());
+        Self {weak,trace}
+    }
+}
+
+impl<T:StrongRef> Debug for WeakCloneRef<T> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"WeakCloneRef({})",if self.is_alive() { "alive" } else { "dropped" })
+    }
+}
+
+impl<T:StrongRef> From<&T> for WeakCloneRef<T> {
+    fn from(strong:&T) -> Self {
+        Self::new(strong)
+    }
+}
+
+
+
+// ==============
+// === Shared ===
+// ==============
+
+/// A shared, mutable value, a newtype over `Rc<RefCell<T>>`. The value is accessed with the `with`
+/// and `with_mut` closures, so borrows never outlive the access.
+///
+/// In debug builds, the location of the most recent active borrow is recorded. When a borrow fails
+/// because of a conflict, the panic message reports where the conflicting borrow was made, instead
+/// of only the location of the failed one.
+pub struct Shared<T> {
+    rc : Rc<SharedCell<T>>,
+}
+
+struct SharedCell<T> {
+    value       : RefCell<T>,
+    #[cfg(debug_assertions)]
+    borrowed_at : Cell<Option<&'static Location<'static>>>,
+}
+
+impl<T> Shared<T> {
+    /// Constructor.
+    pub fn new(value:T) -> Self {
+        let value       = RefCell::new(value);
+        #[cfg(debug_assertions)]
+        let borrowed_at = default();
+        #[cfg(debug_assertions)]
+        let rc          = Rc::new(SharedCell {value,borrowed_at});
+        #[cfg(not(debug_assertions))]
+        let rc          = Rc::new(SharedCell {value});
+        Self {rc}
+    }
+
+    /// Run the function with a reference to the value. Panics if the value is mutably borrowed.
+    #[track_caller]
+    pub fn with<R>(&self, f:impl FnOnce(&T) -> R) -> R {
+        let value     = self.rc.value.try_borrow().unwrap_or_else(|_| self.conflict("borrow"));
+        let _borrowed = self.track(Location::caller());
+        f(&value)
+    }
+
+    /// Run the function with a mutable reference to the value. Panics if the value is borrowed.
+    #[track_caller]
+    pub fn with_mut<R>(&self, f:impl FnOnce(&mut T) -> R) -> R {
+        let value     = self.rc.value.try_borrow_mut();
+        let mut value = value.unwrap_or_else(|_| self.conflict("mutably borrow"));
+        let _borrowed = self.track(Location::caller());
+        f(&mut value)
+    }
+
+    /// Replace the value, returning the old one. Panics if the value is borrowed.
+    #[track_caller]
+    pub fn replace(&self, value:T) -> T {
+        self.with_mut(|t| mem::replace(t,value))
+    }
+
+    /// Check whether both handles point to the same value.
+    pub fn ptr_eq(&self, other:&Self) -> bool {
+        Rc::ptr_eq(&self.rc,&other.rc)
+    }
+
+    /// Record the borrow location until the returned guard is dropped.
+    #[cfg(debug_assertions)]
+    fn track(&self, location:&'static Location<'static>) -> ScopeGuard<impl FnOnce() + '_> {
+        let previous = self.rc.borrowed_at.replace(Some(location));
+        defer(move || self.rc.borrowed_at.set(previous))
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn track(&self, _location:&'static Location<'static>) -> ScopeGuard<impl FnOnce()> {
+        defer(|| {})
+    }
+
+    #[track_caller]
+    fn conflict(&self, operation:&str) -> ! {
+        #[cfg(debug_assertions)]
+        {
+            if let Some(location) = self.rc.borrowed_at.get() {
+                panic!("Cannot {} the shared value, as it is already borrowed at {}.",operation,
+                    location)
+            }
+        }
+        panic!("Cannot {} the shared value, as it is already borrowed.",operation)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        let rc = self.rc.clone();
+        Self {rc}
+    }
+}
+
+impl<T> CloneRef for Shared<T> {
+    fn clone_ref(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<T:Default> Default for Shared<T> {
+    fn default() -> Self {
+        Self::new(default())
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value:T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T:Debug> Debug for Shared<T> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.rc.value.try_borrow() {
+            Ok(value) => write!(f,"Shared({:?})",value),
+            Err(_)    => write!(f,"Shared(<borrowed>)"),
+        }
+    }
+}
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_clone_ref() {
+        let strong = Rc::new(Cell::new(5));
+        let weak   = WeakCloneRef::new(&strong);
+        let copy   = weak.clone_ref();
+        assert_eq!(copy.with(|t| t.get()),Some(5));
+        assert!(weak.is_alive());
+        drop(strong);
+        assert!(!weak.is_alive());
+        assert_eq!(copy.with(|t| t.get()),None);
+    }
+
+    #[test]
+    fn traced_weak_clone_ref() {
+        trace_copies::set_sink(|_| {});
+        let strong = Rc::new(());
+        let weak   = WeakCloneRef::traced(&strong,"traced_weak_clone_ref");
+        let copy   = weak.clone();
+        assert_eq!(trace_copies::live_instances("traced_weak_clone_ref"),2);
+        drop(weak);
+        drop(copy);
+        trace_copies::assert_no_leaks("traced_weak_clone_ref");
+        trace_copies::reset_sink();
+    }
+
+    #[test]
+    fn shared() {
+        let shared = Shared::new(vec![1]);
+        let copy   = shared.clone_ref();
+        copy.with_mut(|t| t.push(2));
+        assert_eq!(shared.with(|t| t.clone()),vec![1,2]);
+        assert_eq!(shared.with(|t| copy.with(|s| t.len() + s.len())),4);
+        assert_eq!(shared.replace(vec![]),vec![1,2]);
+        assert!(shared.ptr_eq(&copy));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn shared_borrow_conflict() {
+        let shared = Shared::new(0);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            shared.with(|_| shared.with_mut(|t| *t += 1))
+        }));
+        let error   = result.unwrap_err();
+        let message = error.downcast_ref::<String>().unwrap();
+        assert!(message.contains("already borrowed at"),"{}",message);
+        assert!(message.contains("rc.rs"),"{}",message);
+        assert_eq!(shared.with(|t| *t),0);
+    }
+}
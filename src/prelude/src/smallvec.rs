@@ -40,6 +40,16 @@ pub trait SmallVecOps {
     /// [`Vec::binary_search`].
     fn binary_search(&self, t:&Self::Item) -> Result<usize, usize>
     where Self::Item:Ord;
+
+    /// Remove first element equal to `item` and returns it if any.
+    fn remove_item(&mut self, item:&Self::Item) -> Option<Self::Item>
+    where Self::Item:PartialEq;
+
+    /// Remove first element equal to `item` and returns it if any. The last element is moved to
+    /// its place, so it takes `O(1)` time after the element is found, but it does not preserve the
+    /// order of the elements.
+    fn swap_remove_item(&mut self, item:&Self::Item) -> Option<Self::Item>
+    where Self::Item:PartialEq;
 }
 
 impl<T:smallvec::Array> SmallVecOps for SmallVec<T> {
@@ -73,6 +83,18 @@ impl<T:smallvec::Array> SmallVecOps for SmallVec<T> {
     where Self::Item:Ord {
         self.binary_search_by(|p| p.cmp(t))
     }
+
+    fn remove_item(&mut self, item:&Self::Item) -> Option<Self::Item>
+    where Self::Item:PartialEq {
+        let index = self.iter().position(|x| *x == *item);
+        index.map(|i| self.remove(i))
+    }
+
+    fn swap_remove_item(&mut self, item:&Self::Item) -> Option<Self::Item>
+    where Self::Item:PartialEq {
+        let index = self.iter().position(|x| *x == *item);
+        index.map(|i| self.swap_remove(i))
+    }
 }
 
 
@@ -107,4 +129,20 @@ mod tests {
         assert_eq!(v.binary_search(&10), Ok(1));
         assert_eq!(v.binary_search(&11), Err(2));
     }
+
+    #[test]
+    fn test_remove_item() {
+        let mut v = SmallVec::<[usize;4]>::from_iter([5,10,5].iter().copied());
+        assert_eq!(v.remove_item(&5), Some(5));
+        assert_eq!(v.remove_item(&7), None);
+        assert_eq!(v.as_slice(), &[10,5]);
+    }
+
+    #[test]
+    fn test_swap_remove_item() {
+        let mut v = SmallVec::<[usize;4]>::from_iter([5,10,20,5].iter().copied());
+        assert_eq!(v.swap_remove_item(&5), Some(5));
+        assert_eq!(v.swap_remove_item(&7), None);
+        assert_eq!(v.as_slice(), &[5,10,20]);
+    }
 }
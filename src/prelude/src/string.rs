@@ -5,11 +5,16 @@ use std::borrow::Cow;
 
 use crate::impls;
 use crate::clone::*;
+use std::cell::RefCell;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::ops::Deref;
 use std::rc::Rc;
+use std::rc::Weak;
 use derive_more::*;
 use serde::Deserialize;
 use serde::Serialize;
+use weak_table::WeakHashSet;
 
 
 
@@ -105,12 +110,17 @@ impl AsRef<str> for CowString {
 // === ImString ===
 // ================
 
-/// Immutable string implementation with a fast clone implementation.
-#[derive(Clone,CloneRef,Debug,Default,Eq,Hash,PartialEq,Serialize,Deserialize)]
+/// Immutable string implementation with a fast clone implementation. Strings created with
+/// `ImString::interned` share their content, which makes comparing them O(1).
+#[derive(Clone,CloneRef,Debug,Default,Eq,Serialize,Deserialize)]
 pub struct ImString {
     content : Rc<String>
 }
 
+thread_local! {
+    static INTERNED : RefCell<WeakHashSet<Weak<String>>> = RefCell::new(WeakHashSet::new());
+}
+
 impl ImString {
     /// Constructor.
     pub fn new(content:impl Into<String>) -> Self {
@@ -118,10 +128,58 @@ impl ImString {
         Self {content}
     }
 
+    /// Constructor of an interned string. All interned strings with equal content created in the
+    /// same thread share the same allocation, so they are compared in O(1). The pool keeps weak
+    /// references only, so the content is freed when the last copy is dropped.
+    pub fn interned(content:impl AsRef<str>) -> Self {
+        let content = content.as_ref();
+        INTERNED.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            let content  = pool.get(content).unwrap_or_else(|| {
+                let content = Rc::new(content.to_owned());
+                pool.insert(content.clone());
+                content
+            });
+            Self {content}
+        })
+    }
+
+    /// Interned version of this string. See `ImString::interned` to learn more.
+    pub fn intern(&self) -> Self {
+        Self::interned(self.as_str())
+    }
+
+    /// Check whether both strings share the same allocation. It is always the case for equal
+    /// interned strings.
+    pub fn ptr_eq(&self, other:&Self) -> bool {
+        Rc::ptr_eq(&self.content,&other.content)
+    }
+
     /// Extract a string slice containing the entire string.
     pub fn as_str(&self) -> &str {
         &self.content
     }
+
+    /// Constructor of a string from the formatting arguments, usually created with the
+    /// `format_args!` macro. The formatted content is moved to the string, not copied. See also the
+    /// `im_format!` macro.
+    pub fn from_fmt(args:std::fmt::Arguments) -> Self {
+        let mut builder = ImStringBuilder::new();
+        std::fmt::Write::write_fmt(&mut builder,args).expect("Formatting a string can not fail.");
+        builder.freeze()
+    }
+}
+
+impl PartialEq for ImString {
+    fn eq(&self, other:&Self) -> bool {
+        self.ptr_eq(other) || self.content == other.content
+    }
+}
+
+impl Hash for ImString {
+    fn hash<H:Hasher>(&self, state:&mut H) {
+        self.content.hash(state)
+    }
 }
 
 impl std::fmt::Display for ImString {
@@ -213,6 +271,160 @@ impl PartialEq<ImString> for String {
 }
 
 
+// === ImStringBuilder ===
+
+/// Builder of an `ImString`, allowing incremental construction, for example with the `write!`
+/// macro. The built content is moved to the frozen `ImString`, not copied.
+#[derive(Clone,Debug,Default)]
+pub struct ImStringBuilder {
+    content : String
+}
+
+impl ImStringBuilder {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructor of a builder with preallocated space for at least `capacity` bytes.
+    pub fn with_capacity(capacity:usize) -> Self {
+        let content = String::with_capacity(capacity);
+        Self {content}
+    }
+
+    /// Append the string slice.
+    pub fn push_str(&mut self, string:&str) -> &mut Self {
+        self.content.push_str(string);
+        self
+    }
+
+    /// Append the character.
+    pub fn push(&mut self, char:char) -> &mut Self {
+        self.content.push(char);
+        self
+    }
+
+    /// Length of the built string in bytes.
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Check whether the built string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Extract a string slice containing the built string.
+    pub fn as_str(&self) -> &str {
+        &self.content
+    }
+
+    /// Finish building, moving the content to the returned `ImString`.
+    pub fn freeze(self) -> ImString {
+        ImString::new(self.content)
+    }
+
+    /// Finish building, returning an interned string. See `ImString::interned` to learn more.
+    pub fn freeze_interned(self) -> ImString {
+        ImString::interned(self.content)
+    }
+}
+
+impl std::fmt::Write for ImStringBuilder {
+    fn write_str(&mut self, string:&str) -> std::fmt::Result {
+        self.content.push_str(string);
+        Ok(())
+    }
+}
+
+impl<'a> Extend<&'a str> for ImStringBuilder {
+    fn extend<I:IntoIterator<Item=&'a str>>(&mut self, iter:I) {
+        self.content.extend(iter)
+    }
+}
+
+impl From<ImStringBuilder> for ImString {
+    fn from(builder:ImStringBuilder) -> Self {
+        builder.freeze()
+    }
+}
+
+/// Creates an `ImString` using interpolation of runtime expressions, like `format!`, without
+/// copying the formatted content.
+#[macro_export]
+macro_rules! im_format {
+    ($($arg:tt)*) => {
+        $crate::ImString::from_fmt(format_args!($($arg)*))
+    };
+}
+
+
+// === Interned ===
+
+/// An interned `ImString` with O(1) equality and hashing, as the pointer to the shared content is
+/// compared and hashed instead of the content. Useful as a key of hash maps with long keys, like
+/// logger paths.
+#[derive(Clone,CloneRef,Debug,Default,Serialize,Deserialize)]
+#[serde(from="ImString")]
+#[serde(into="ImString")]
+pub struct Interned {
+    content : ImString
+}
+
+impl Interned {
+    /// Constructor.
+    pub fn new(content:impl AsRef<str>) -> Self {
+        let content = ImString::interned(content);
+        Self {content}
+    }
+}
+
+impl Deref for Interned {
+    type Target = ImString;
+    fn deref(&self) -> &Self::Target {
+        &self.content
+    }
+}
+
+impl PartialEq for Interned {
+    fn eq(&self, other:&Self) -> bool {
+        self.content.ptr_eq(&other.content)
+    }
+}
+
+impl Eq for Interned {}
+
+impl Hash for Interned {
+    fn hash<H:Hasher>(&self, state:&mut H) {
+        Rc::as_ptr(&self.content.content).hash(state)
+    }
+}
+
+impl From<ImString> for Interned {
+    fn from(t:ImString) -> Self {
+        Self::new(t)
+    }
+}
+
+impl From<&str> for Interned {
+    fn from(t:&str) -> Self {
+        Self::new(t)
+    }
+}
+
+impl From<Interned> for ImString {
+    fn from(t:Interned) -> Self {
+        t.content
+    }
+}
+
+impl std::fmt::Display for Interned {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.content.fmt(f)
+    }
+}
+
+
 // === Macros ===
 
 /// Defines a newtype for `ImString`.
@@ -299,6 +511,212 @@ macro_rules! im_string_newtype {
 
 
 
+// ===================
+// === SmallString ===
+// ===================
+
+/// Maximum length in bytes of strings stored inline in `SmallString`.
+pub const SMALL_STRING_INLINE_CAPACITY : usize = 22;
+
+/// A string stored inline if it is not longer than `SMALL_STRING_INLINE_CAPACITY` bytes, and on the
+/// heap otherwise. Most identifiers are short, so storing them in `SmallString` avoids most of the
+/// allocations. Strings which do not fit are stored in a `String`, so no content is copied when
+/// converting a long `String` to a `SmallString` and back.
+#[derive(Clone)]
+pub struct SmallString {
+    repr : SmallStringRepr,
+}
+
+#[derive(Clone)]
+enum SmallStringRepr {
+    Inline {len:u8, bytes:[u8;SMALL_STRING_INLINE_CAPACITY]},
+    Heap   (String),
+}
+
+impl SmallString {
+    /// Constructor of an empty string.
+    pub fn new() -> Self {
+        let repr = SmallStringRepr::Inline {len:0, bytes:[0;SMALL_STRING_INLINE_CAPACITY]};
+        Self {repr}
+    }
+
+    /// Check whether the content is stored inline.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr,SmallStringRepr::Inline{..})
+    }
+
+    /// Extract a string slice containing the entire string.
+    #[allow(unsafe_code)]
+    pub fn as_str(&self) -> &str {
+        match &self.repr {
+            // SAFETY: The inline bytes are only written by `push_str`, which copies whole strings.
+            SmallStringRepr::Inline {len,bytes} => unsafe {
+                std::str::from_utf8_unchecked(&bytes[..*len as usize])
+            }
+            SmallStringRepr::Heap(string) => string,
+        }
+    }
+
+    /// Append the string slice, moving the content to the heap if it does not fit inline anymore.
+    pub fn push_str(&mut self, string:&str) {
+        match &mut self.repr {
+            SmallStringRepr::Heap(heap) => heap.push_str(string),
+            SmallStringRepr::Inline {len,bytes} => {
+                let start = *len as usize;
+                let end   = start + string.len();
+                if end <= SMALL_STRING_INLINE_CAPACITY {
+                    bytes[start..end].copy_from_slice(string.as_bytes());
+                    *len = end as u8;
+                } else {
+                    let mut heap = String::with_capacity(end);
+                    heap.push_str(self.as_str());
+                    heap.push_str(string);
+                    self.repr = SmallStringRepr::Heap(heap);
+                }
+            }
+        }
+    }
+
+    /// Append the character.
+    pub fn push(&mut self, char:char) {
+        self.push_str(char.encode_utf8(&mut [0;4]))
+    }
+}
+
+impl Default for SmallString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SmallString {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::borrow::Borrow<str> for SmallString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for SmallString {
+    fn eq(&self, other:&Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallString {}
+
+impl PartialOrd for SmallString {
+    fn partial_cmp(&self, other:&Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallString {
+    fn cmp(&self, other:&Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SmallString {
+    fn hash<H:Hasher>(&self, state:&mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl std::fmt::Debug for SmallString {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_str(),f)
+    }
+}
+
+impl std::fmt::Display for SmallString {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.as_str(),f)
+    }
+}
+
+impl PartialEq<str> for SmallString {
+    fn eq(&self, other:&str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SmallString {
+    fn eq(&self, other:&&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl From<&str> for SmallString {
+    fn from(t:&str) -> Self {
+        if t.len() > SMALL_STRING_INLINE_CAPACITY { t.to_owned().into() } else {
+            let mut string = Self::new();
+            string.push_str(t);
+            string
+        }
+    }
+}
+
+impl From<String> for SmallString {
+    fn from(t:String) -> Self {
+        if t.len() <= SMALL_STRING_INLINE_CAPACITY { t.as_str().into() } else {
+            let repr = SmallStringRepr::Heap(t);
+            Self {repr}
+        }
+    }
+}
+
+impl From<&String> for SmallString {
+    fn from(t:&String) -> Self {
+        t.as_str().into()
+    }
+}
+
+impl From<&ImString> for SmallString {
+    fn from(t:&ImString) -> Self {
+        t.as_str().into()
+    }
+}
+
+impl From<ImString> for SmallString {
+    fn from(t:ImString) -> Self {
+        String::from(t).into()
+    }
+}
+
+impl From<SmallString> for String {
+    fn from(t:SmallString) -> Self {
+        match t.repr {
+            SmallStringRepr::Heap(string) => string,
+            SmallStringRepr::Inline{..}   => t.as_str().to_owned(),
+        }
+    }
+}
+
+impl From<SmallString> for ImString {
+    fn from(t:SmallString) -> Self {
+        Self::new(t)
+    }
+}
+
+impl From<&SmallString> for ImString {
+    fn from(t:&SmallString) -> Self {
+        Self::new(t.as_str())
+    }
+}
+
+
 // =============
 // === Tests ===
 // =============
@@ -307,6 +725,69 @@ macro_rules! im_string_newtype {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_interning() {
+        let interned1 = ImString::interned("app.gui.graph");
+        let interned2 = ImString::interned(String::from("app.gui.graph"));
+        let plain     = ImString::new("app.gui.graph");
+        assert!(interned1.ptr_eq(&interned2));
+        assert!(!interned1.ptr_eq(&plain));
+        assert!(interned1.ptr_eq(&plain.intern()));
+        assert_eq!(interned1,plain);
+
+        let key1 = Interned::new("app.gui.graph");
+        let key2 = Interned::from(plain);
+        let key3 = Interned::new("app.gui");
+        assert_eq!(key1,key2);
+        assert_ne!(key1,key3);
+        assert!(key1.ptr_eq(&interned1));
+        let set : std::collections::HashSet<Interned> = vec![key1,key2,key3].into_iter().collect();
+        assert_eq!(set.len(),2);
+    }
+
+    #[test]
+    fn test_interned_strings_are_freed() {
+        let weak = Rc::downgrade(&ImString::interned("test_interned_strings_are_freed").content);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_im_string_builder() {
+        use std::fmt::Write;
+        let mut builder = ImStringBuilder::with_capacity(16);
+        builder.push_str("app").push('.');
+        write!(builder,"node_{}",7).unwrap();
+        builder.extend(vec![".","port"]);
+        assert_eq!(builder.as_str(),"app.node_7.port");
+        let interned = ImString::interned("app.node_7.port");
+        assert!(builder.clone().freeze_interned().ptr_eq(&interned));
+        assert_eq!(builder.freeze(),interned);
+        assert_eq!(im_format!("{}.{}","app",1),"app.1");
+        assert_eq!(ImString::from_fmt(format_args!("{:>3}",1)),"  1");
+    }
+
+    #[test]
+    fn test_small_string() {
+        let short = SmallString::from("node_1");
+        let long  = SmallString::from("a_very_long_identifier_name");
+        assert!(short.is_inline());
+        assert!(!long.is_inline());
+        assert_eq!(short,"node_1");
+        assert_eq!(String::from(long.clone()),"a_very_long_identifier_name");
+
+        let mut growing = SmallString::from(String::from("a_long_identifier"));
+        assert!(growing.is_inline());
+        growing.push_str("_name");
+        growing.push('字');
+        assert!(!growing.is_inline());
+        assert_eq!(growing,"a_long_identifier_name字");
+        assert_eq!(ImString::from(growing),"a_long_identifier_name字");
+        assert_eq!(SmallString::from(ImString::new("node_1")),short);
+
+        let set : std::collections::HashSet<SmallString> = vec![short,long].into_iter().collect();
+        assert!(set.contains("node_1"));
+    }
+
     #[test]
     fn test_string_ops() {
         // === Matching against ascii ===
@@ -0,0 +1,183 @@
+//! Utilities for measuring and displaying time, working the same way on native and wasm targets.
+
+use std::time::Duration;
+
+
+
+// =================
+// === Timestamp ===
+// =================
+
+#[cfg(target_arch="wasm32")]
+mod js {
+    use wasm_bindgen::prelude::*;
+    #[wasm_bindgen(inline_js = "
+        export function performance_now() {
+            return performance.now()
+        }
+    ")]
+    extern "C" {
+        #[allow(unsafe_code)]
+        pub fn performance_now() -> f64;
+    }
+}
+
+/// A point in time, in milliseconds. Only differences between timestamps are meaningful.
+#[cfg(target_arch="wasm32")]
+type Timestamp = f64;
+
+/// A point in time.
+#[cfg(not(target_arch="wasm32"))]
+type Timestamp = std::time::Instant;
+
+/// Current time. The global `performance` object is used, so it works in web workers as well.
+#[cfg(target_arch="wasm32")]
+fn now() -> Timestamp {
+    js::performance_now()
+}
+
+/// Current time.
+#[cfg(not(target_arch="wasm32"))]
+fn now() -> Timestamp {
+    std::time::Instant::now()
+}
+
+/// Time elapsed between the timestamps, or zero if `end` is earlier than `start`.
+#[cfg(target_arch="wasm32")]
+fn duration_between(start:Timestamp, end:Timestamp) -> Duration {
+    Duration::from_secs_f64((end - start).max(0.0) / 1000.0)
+}
+
+/// Time elapsed between the timestamps, or zero if `end` is earlier than `start`.
+#[cfg(not(target_arch="wasm32"))]
+fn duration_between(start:Timestamp, end:Timestamp) -> Duration {
+    end.saturating_duration_since(start)
+}
+
+
+
+// =================
+// === Stopwatch ===
+// =================
+
+/// Measures the time elapsed since it was started. It uses `Instant` on native targets and
+/// `performance.now()` on wasm, so it can be used without target-specific code. The start time is
+/// stored in the stopwatch, so it can be read on any thread:
+///
+/// ```
+/// # use enso_prelude::*;
+/// let stopwatch = Stopwatch::start();
+/// // ... Measured code ...
+/// println!("Done in {}.",stopwatch);
+/// ```
+#[derive(Clone,Copy,Debug)]
+pub struct Stopwatch {
+    start : Timestamp,
+}
+
+impl Stopwatch {
+    /// Constructor. Starts measuring the time immediately.
+    pub fn start() -> Self {
+        let start = now();
+        Self {start}
+    }
+
+    /// Time elapsed since the stopwatch was started.
+    pub fn elapsed(&self) -> Duration {
+        duration_between(self.start,now())
+    }
+
+    /// Return the elapsed time and start measuring again. Useful for measuring consecutive steps.
+    pub fn lap(&mut self) -> Duration {
+        let now     = now();
+        let elapsed = duration_between(self.start,now);
+        self.start  = now;
+        elapsed
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl std::fmt::Display for Stopwatch {
+    fn fmt(&self, f:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}",format_duration(self.elapsed()))
+    }
+}
+
+
+
+// =======================
+// === format_duration ===
+// =======================
+
+/// Format the duration in a human-readable way, using the most appropriate unit and three
+/// significant digits, like `1.23 s`, `45.6 ms`, `789 µs`, or `2 min 3.4 s`.
+pub fn format_duration(duration:Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs >= 60.0 {
+        let minutes = (secs / 60.0).floor();
+        let rest    = secs - minutes * 60.0;
+        format!("{} min {:.1} s",minutes,rest)
+    } else if secs >= 1.0 {
+        format_significant(secs,"s")
+    } else if secs >= 1e-3 {
+        format_significant(secs * 1e3,"ms")
+    } else if secs >= 1e-6 {
+        format_significant(secs * 1e6,"µs")
+    } else {
+        format!("{} ns",duration.as_nanos())
+    }
+}
+
+/// Format a value in the `[1,1000)` range with three significant digits.
+fn format_significant(value:f64, unit:&str) -> String {
+    if value >= 100.0 {
+        format!("{:.0} {}",value,unit)
+    } else if value >= 10.0 {
+        format!("{:.1} {}",value,unit)
+    } else {
+        format!("{:.2} {}",value,unit)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format_duration(Duration::from_nanos(789))        , "789 ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_234))      , "1.23 µs");
+        assert_eq!(format_duration(Duration::from_micros(456))       , "456 µs");
+        assert_eq!(format_duration(Duration::from_micros(45_600))    , "45.6 ms");
+        assert_eq!(format_duration(Duration::from_millis(1_230))     , "1.23 s");
+        assert_eq!(format_duration(Duration::from_millis(123_400))   , "2 min 3.4 s");
+    }
+
+    #[test]
+    fn stopwatch() {
+        let mut stopwatch = Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(2));
+        let lap = stopwatch.lap();
+        assert!(lap >= Duration::from_millis(2));
+        assert!(stopwatch.elapsed() < lap + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn stopwatch_is_read_on_other_threads() {
+        let stopwatch = Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(2));
+        let elapsed = std::thread::spawn(move || stopwatch.elapsed()).join().unwrap();
+        assert!(elapsed >= Duration::from_millis(2));
+    }
+}
@@ -31,6 +31,26 @@ pub trait VecOps {
     /// Remove first element equal to `item` and returns it if any.
     fn remove_item(&mut self, item:&Self::Item) -> Option<Self::Item>
     where Self::Item : PartialEq<Self::Item>;
+
+    /// Remove first element matching the predicate and returns it if any.
+    fn remove_item_by<F>(&mut self, f:F) -> Option<Self::Item>
+    where F : FnMut(&Self::Item) -> bool;
+
+    /// Remove first element equal to `item` and returns it if any. The last element is moved to
+    /// its place, so it takes `O(1)` time after the element is found, but it does not preserve the
+    /// order of the elements.
+    fn swap_remove_item(&mut self, item:&Self::Item) -> Option<Self::Item>
+    where Self::Item : PartialEq<Self::Item>;
+
+    /// Insert `item` into the sorted vector, after all the elements smaller or equal to it, so the
+    /// vector stays sorted. Returns the index of the inserted element.
+    fn insert_sorted(&mut self, item:Self::Item) -> usize
+    where Self::Item : Ord;
+
+    /// Removes all but the last of consecutive elements that resolve to the same key. It is the
+    /// counterpart of [`Vec::dedup_by_key`], which keeps the first of them.
+    fn dedup_by_key_keeping_last<K,F>(&mut self, key:F)
+    where K:PartialEq, F:FnMut(&mut Self::Item) -> K;
 }
 
 impl<T> VecOps for Vec<T> {
@@ -70,6 +90,32 @@ impl<T> VecOps for Vec<T> {
         let index = self.iter().position(|x| *x == *item);
         index.map(|i| self.remove(i))
     }
+
+    fn remove_item_by<F>(&mut self, f:F) -> Option<T>
+    where F : FnMut(&T) -> bool {
+        let index = self.iter().position(f);
+        index.map(|i| self.remove(i))
+    }
+
+    fn swap_remove_item(&mut self, item:&T) -> Option<T>
+    where T: PartialEq<T> {
+        let index = self.iter().position(|x| *x == *item);
+        index.map(|i| self.swap_remove(i))
+    }
+
+    fn insert_sorted(&mut self, item:T) -> usize
+    where T: Ord {
+        let index = self.partition_point(|x| *x <= item);
+        self.insert(index,item);
+        index
+    }
+
+    fn dedup_by_key_keeping_last<K,F>(&mut self, key:F)
+    where K:PartialEq, F:FnMut(&mut T) -> K {
+        self.reverse();
+        self.dedup_by_key(key);
+        self.reverse();
+    }
 }
 
 
@@ -102,4 +148,32 @@ mod tests {
         item_in_vec.item = 20;
         assert_eq!(item_in_vec.item, 20);
     }
+
+    #[test]
+    fn test_removal() {
+        let mut vec = vec![1,2,3,4,2];
+        assert_eq!(vec.remove_item_by(|x| *x > 2),Some(3));
+        assert_eq!(vec.remove_item_by(|x| *x > 5),None);
+        assert_eq!(vec,vec![1,2,4,2]);
+        assert_eq!(vec.swap_remove_item(&1),Some(1));
+        assert_eq!(vec.swap_remove_item(&5),None);
+        assert_eq!(vec,vec![2,2,4]);
+    }
+
+    #[test]
+    fn test_insert_sorted() {
+        let mut vec = vec![(1,'a'),(3,'a')];
+        assert_eq!(vec.insert_sorted((2,'a')),1);
+        assert_eq!(vec.insert_sorted((0,'a')),0);
+        assert_eq!(vec.insert_sorted((4,'a')),4);
+        assert_eq!(vec.insert_sorted((3,'a')),4);
+        assert_eq!(vec.iter().map(|t| t.0).collect::<Vec<_>>(),vec![0,1,2,3,3,4]);
+    }
+
+    #[test]
+    fn test_dedup_by_key_keeping_last() {
+        let mut vec = vec![(1,'a'),(1,'b'),(2,'c'),(1,'d'),(1,'e'),(1,'f')];
+        vec.dedup_by_key_keeping_last(|t| t.0);
+        assert_eq!(vec,vec![(1,'b'),(2,'c'),(1,'f')]);
+    }
 }